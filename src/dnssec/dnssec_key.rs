@@ -0,0 +1,218 @@
+use std::{fmt, fs};
+use std::fmt::Formatter;
+use std::path::PathBuf;
+use crate::dnssec::inter::dnssec_algorithms::DnsSecAlgorithms;
+use crate::dnssec::inter::key_roles::KeyRoles;
+use crate::records::dnskey_record::DnsKeyRecord;
+use crate::utils::{base64, ecdsa, ed25519, rsa, sha1, sha256, sha512};
+use crate::utils::bigint::BigUint;
+use crate::utils::ecdsa::EcCurves;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DnsSecKeyError {
+    Io(String),
+    ParseError { line: usize, reason: String },
+    MissingField(String),
+    InvalidKey(String)
+}
+
+impl fmt::Display for DnsSecKeyError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(reason) => write!(f, "io error: {}", reason),
+            Self::ParseError { line, reason } => write!(f, "line {}: {}", line, reason),
+            Self::MissingField(field) => write!(f, "missing field: {}", field),
+            Self::InvalidKey(reason) => write!(f, "invalid key: {}", reason)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PrivateKey {
+    Rsa {
+        modulus: BigUint,
+        private_exponent: BigUint
+    },
+    Ecdsa {
+        curve: EcCurves,
+        private_key: Vec<u8>
+    },
+    Ed25519 {
+        seed: Vec<u8>
+    }
+}
+
+//A SIGNING KEY, THE PRIVATE HALF AND THE DNSKEY IT PUBLISHES AS
+#[derive(Debug, Clone)]
+pub struct DnsSecKey {
+    role: KeyRoles,
+    algorithm: DnsSecAlgorithms,
+    private_key: PrivateKey,
+    dnskey: DnsKeyRecord
+}
+
+impl DnsSecKey {
+
+    //BIND PRIVATE-KEY-FORMAT V1.X AS WRITTEN BY DNSSEC-KEYGEN, THE ROLE LIVES IN THE .KEY FILE SO IT IS PASSED IN
+    pub fn parse(s: &str, role: KeyRoles) -> Result<Self, DnsSecKeyError> {
+        let mut algorithm = None;
+        let mut fields = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(DnsSecKeyError::ParseError { line: i+1, reason: format!("expected name: value, found {}", line) });
+            };
+
+            let value = value.trim();
+            match name.trim() {
+                "Private-key-format" if !value.starts_with("v1.") => {
+                    return Err(DnsSecKeyError::ParseError { line: i+1, reason: format!("unsupported format {}", value) });
+                }
+                "Algorithm" => {
+                    let code = value.split_whitespace().next()
+                        .and_then(|code| code.parse::<u8>().ok())
+                        .ok_or_else(|| DnsSecKeyError::ParseError { line: i+1, reason: format!("invalid algorithm {}", value) })?;
+
+                    algorithm = Some(DnsSecAlgorithms::try_from(code)
+                        .map_err(|e| DnsSecKeyError::ParseError { line: i+1, reason: e.to_string() })?);
+                }
+                "Modulus" | "PublicExponent" | "PrivateExponent" | "PrivateKey" => {
+                    let decoded = base64::decode(value)
+                        .map_err(|e| DnsSecKeyError::ParseError { line: i+1, reason: e.to_string() })?;
+                    fields.push((name.trim().to_string(), decoded));
+                }
+                //PRIMES, CRT EXPONENTS AND TIMING METADATA AREN'T NEEDED TO SIGN
+                _ => {}
+            }
+        }
+
+        let field = |name: &str| {
+            fields.iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_slice())
+                .ok_or_else(|| DnsSecKeyError::MissingField(name.to_string()))
+        };
+
+        let algorithm = algorithm.ok_or_else(|| DnsSecKeyError::MissingField("Algorithm".to_string()))?;
+
+        let (private_key, public_key) = match algorithm {
+            DnsSecAlgorithms::RsaSha1 | DnsSecAlgorithms::RsaSha1Nsec3Sha1 | DnsSecAlgorithms::RsaSha256 | DnsSecAlgorithms::RsaSha512 => {
+                let modulus = BigUint::from_bytes_be(field("Modulus")?);
+                let public_exponent = BigUint::from_bytes_be(field("PublicExponent")?);
+                let private_exponent = BigUint::from_bytes_be(field("PrivateExponent")?);
+
+                if !modulus.bit(0) || public_exponent.is_zero() || private_exponent.is_zero() {
+                    return Err(DnsSecKeyError::InvalidKey("rsa modulus and exponents must be non zero and the modulus odd".to_string()));
+                }
+
+                let public_key = rsa::encode_public_key(&public_exponent, &modulus);
+                (PrivateKey::Rsa { modulus, private_exponent }, public_key)
+            }
+            DnsSecAlgorithms::EcdsaP256Sha256 | DnsSecAlgorithms::EcdsaP384Sha384 => {
+                let curve = match algorithm {
+                    DnsSecAlgorithms::EcdsaP256Sha256 => EcCurves::P256,
+                    _ => EcCurves::P384
+                };
+
+                let private_key = field("PrivateKey")?.to_vec();
+                let public_key = match private_key.len() == curve.get_len() {
+                    true => ecdsa::public_key(curve, &private_key),
+                    false => None
+                }.ok_or_else(|| DnsSecKeyError::InvalidKey(format!("not a {} private key", algorithm)))?;
+
+                (PrivateKey::Ecdsa { curve, private_key }, public_key)
+            }
+            DnsSecAlgorithms::Ed25519 => {
+                let seed = field("PrivateKey")?.to_vec();
+                let public_key = ed25519::public_key(&seed)
+                    .ok_or_else(|| DnsSecKeyError::InvalidKey(format!("not a {} private key", algorithm)))?;
+
+                (PrivateKey::Ed25519 { seed }, public_key)
+            }
+        };
+
+        Ok(Self {
+            role,
+            algorithm,
+            private_key,
+            dnskey: DnsKeyRecord::new(role.get_flags(), 3, algorithm.get_code(), public_key)
+        })
+    }
+
+    pub fn open<P: Into<PathBuf>>(file_path: P, role: KeyRoles) -> Result<Self, DnsSecKeyError> {
+        Self::parse(&fs::read_to_string(file_path.into()).map_err(|e| DnsSecKeyError::Io(e.to_string()))?, role)
+    }
+
+    pub fn get_role(&self) -> KeyRoles {
+        self.role
+    }
+
+    pub fn get_algorithm(&self) -> DnsSecAlgorithms {
+        self.algorithm
+    }
+
+    pub fn get_dnskey(&self) -> &DnsKeyRecord {
+        &self.dnskey
+    }
+
+    pub fn get_key_tag(&self) -> u16 {
+        self.dnskey.get_key_tag()
+    }
+
+    //DATA IS THE RFC 4034 S3.1.8.1 SIGNED DATA, THE SAME BYTES DnsSecAlgorithms::verify TAKES
+    pub fn sign(&self, data: &[u8]) -> Option<Vec<u8>> {
+        match &self.private_key {
+            PrivateKey::Rsa { modulus, private_exponent } => match self.algorithm {
+                DnsSecAlgorithms::RsaSha256 => rsa::sign(modulus, private_exponent, &rsa::SHA256_DIGEST_INFO, &sha256::hash(data)),
+                DnsSecAlgorithms::RsaSha512 => rsa::sign(modulus, private_exponent, &rsa::SHA512_DIGEST_INFO, &sha512::hash(data)),
+                _ => rsa::sign(modulus, private_exponent, &rsa::SHA1_DIGEST_INFO, &sha1::hash(data))
+            }
+            PrivateKey::Ecdsa { curve, private_key } => match curve {
+                EcCurves::P256 => ecdsa::sign(*curve, private_key, &sha256::hash(data)),
+                EcCurves::P384 => ecdsa::sign(*curve, private_key, &sha512::hash_384(data))
+            }
+            PrivateKey::Ed25519 { seed } => ed25519::sign(seed, data)
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let rsa = "Private-key-format: v1.3\n\
+        Algorithm: 8 (RSASHA256)\n\
+        Modulus: re7HiPnQSbCWir84Fvmi0T+hx7GLKpZ5CnifZd4ZKiyXiF722AaxHcaF4SW+ZgX7Q8zzFXV+VTrCEfwr/Skt5ktL4Ux/ZsFlSO7NQxRD/Acjw3xQqelCKr8ShSC0Z4ahklr1M3w1QqHl5lpjK7zqFRtCWLfDK6gVZi+uooHFpjk=\n\
+        PublicExponent: AQAB\n\
+        PrivateExponent: Ok4N41Pix6EDhfU9xy3dr4lGIRvaC+evIZx3yn0eMG4MSfGTi60KmHGhFW1Cmw6sQPkmD254b/encMH4uP+h28CtwTYzrovLuOkQNWce//I2P1iKdD27lgm5lUryrB9PPhpA6C+v8oE/TghfNYZtVkRWV+SYH7ch1guO09EVxWU=\n\
+        Created: 20250101000000\n";
+
+    let key = DnsSecKey::parse(rsa, KeyRoles::Ksk).unwrap();
+    assert_eq!(key.get_algorithm(), DnsSecAlgorithms::RsaSha256);
+    assert_eq!(key.get_dnskey().get_flags(), 257);
+    assert_eq!(base64::encode(key.get_dnskey().get_public_key()), "AwEAAa3ux4j50Emwloq/OBb5otE/ocexiyqWeQp4n2XeGSosl4he9tgGsR3GheElvmYF+0PM8xV1flU6whH8K/0pLeZLS+FMf2bBZUjuzUMUQ/wHI8N8UKnpQiq/EoUgtGeGoZJa9TN8NUKh5eZaYyu86hUbQli3wyuoFWYvrqKBxaY5");
+    assert_eq!(base64::encode(&key.sign(b"rlibdns").unwrap()), "Nm54Tq2vVnPz3qflJeyum3U+Sj1/IXquiUbkSFqnX69CMnCVGTNjHcTOBB2juN1wq00AxxC9gbBOSjd599FHoCUb/2qhjWZpv+4Kw9iW0XsIjMrdQ/fYwSzT4PbTLIcDEW57WXWprhfhfKr1iNqOwcmE7i/N0YBKDyB1AnsRhtE=");
+
+    let ecdsa = "Private-key-format: v1.3\nAlgorithm: 13 (ECDSAP256SHA256)\nPrivateKey: ya+p2EW6dRZrXCFXZ7HWk05Qw9s26JsSe4piKxIPZyE=\n";
+    let key = DnsSecKey::parse(ecdsa, KeyRoles::Zsk).unwrap();
+    assert_eq!(key.get_role(), KeyRoles::Zsk);
+    assert_eq!(key.get_dnskey().get_flags(), 256);
+    assert_eq!(base64::encode(key.get_dnskey().get_public_key()), "YP7UuiVanTHJYet0xjVtaMBJuJI7Yfps5mliLmDyn7Z5A/4QCLi8maQa6elWKLxk8vGyDC1+n1F3o8KU1EYimQ==");
+    assert!(key.get_algorithm().verify(key.get_dnskey().get_public_key(), b"rlibdns", &key.sign(b"rlibdns").unwrap()));
+
+    //RFC 8032 S7.1 TEST 1
+    let ed25519 = "Private-key-format: v1.3\nAlgorithm: 15 (ED25519)\nPrivateKey: nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=\n";
+    let key = DnsSecKey::parse(ed25519, KeyRoles::Ksk).unwrap();
+    assert_eq!(base64::encode(key.get_dnskey().get_public_key()), "11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=");
+    assert!(key.get_algorithm().verify(key.get_dnskey().get_public_key(), b"rlibdns", &key.sign(b"rlibdns").unwrap()));
+
+    assert_eq!(DnsSecKey::parse("Private-key-format: v1.3\nAlgorithm: 3 (DSA)\n", KeyRoles::Zsk).err(), Some(DnsSecKeyError::ParseError { line: 2, reason: "unsupported dnssec algorithm: 3".to_string() }));
+    assert_eq!(DnsSecKey::parse("Algorithm: 15 (ED25519)\n", KeyRoles::Zsk).err(), Some(DnsSecKeyError::MissingField("PrivateKey".to_string())));
+    assert_eq!(DnsSecKey::parse("Algorithm: 15\nPrivateKey AAAA\n", KeyRoles::Zsk).err(), Some(DnsSecKeyError::ParseError { line: 2, reason: "expected name: value, found PrivateKey AAAA".to_string() }));
+    assert!(matches!(DnsSecKey::parse("Algorithm: 13\nPrivateKey: AAAA\n", KeyRoles::Zsk), Err(DnsSecKeyError::InvalidKey(_))));
+    assert!(matches!(DnsSecKey::open("/nonexistent/Kfind9.net.+015+00000.private", KeyRoles::Zsk), Err(DnsSecKeyError::Io(_))));
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::dnssec::inter::dnssec_algorithms::DnsSecAlgorithms;
+use crate::messages::inter::rr_classes::RRClasses;
+use crate::records::dnskey_record::DnsKeyRecord;
+use crate::records::inter::record_base::RecordBase;
+use crate::records::rrsig_record::RRSigRecord;
+use crate::utils::fqdn_utils::pack_fqdn;
+use crate::zone::rr_set::RRSet;
+use crate::zone::zone::serial_gt;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DnsSecError {
+    NoSignatures,
+    UnsupportedAlgorithm(u8),
+    NoMatchingKey(u16),
+    NotYetValid(u32),
+    Expired(u32),
+    BadSignature(u16),
+    Malformed(String),
+    MissingSoa,
+    NoSigningKey,
+    SigningFailed(u16)
+}
+
+impl fmt::Display for DnsSecError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSignatures => write!(f, "no rrsig covers the rrset"),
+            Self::UnsupportedAlgorithm(algorithm) => write!(f, "unsupported dnssec algorithm: {}", algorithm),
+            Self::NoMatchingKey(key_tag) => write!(f, "no usable dnskey with key tag {}", key_tag),
+            Self::NotYetValid(inception) => write!(f, "signature not valid before {}", inception),
+            Self::Expired(expiration) => write!(f, "signature expired at {}", expiration),
+            Self::BadSignature(key_tag) => write!(f, "signature does not verify with key tag {}", key_tag),
+            Self::Malformed(reason) => write!(f, "malformed rrsig: {}", reason),
+            Self::MissingSoa => write!(f, "zone has no SOA record"),
+            Self::NoSigningKey => write!(f, "no signing keys"),
+            Self::SigningFailed(key_tag) => write!(f, "key with key tag {} failed to sign", key_tag)
+        }
+    }
+}
+
+//RFC 4035 S5.3 - RETURNS THE RRSIG AND DNSKEY THAT VALIDATED THE SET, OTHERWISE WHY THE LAST CANDIDATE RRSIG FAILED
+//NOW IS SECONDS SINCE THE EPOCH, COMPARED WITH RFC 1982 SERIAL ARITHMETIC
+pub fn validate_rrset<'a>(owner: &str, class: RRClasses, rrset: &RRSet, rrsigs: &'a [RRSigRecord], dnskeys: &'a [DnsKeyRecord], now: u32) -> Result<(&'a RRSigRecord, &'a DnsKeyRecord), DnsSecError> {
+    let mut error = DnsSecError::NoSignatures;
+
+    for rrsig in rrsigs.iter().filter(|rrsig| rrsig.get_type_covered() == rrset.get_type()) {
+        match validate_rrsig(owner, class, rrset, rrsig, dnskeys, now) {
+            Ok(dnskey) => return Ok((rrsig, dnskey)),
+            Err(e) => error = e
+        }
+    }
+
+    Err(error)
+}
+
+fn validate_rrsig<'a>(owner: &str, class: RRClasses, rrset: &RRSet, rrsig: &RRSigRecord, dnskeys: &'a [DnsKeyRecord], now: u32) -> Result<&'a DnsKeyRecord, DnsSecError> {
+    let owner = owner.trim_end_matches('.').to_lowercase();
+    let signer = rrsig.get_signer_name()
+        .ok_or_else(|| DnsSecError::Malformed("no signer name".to_string()))?
+        .trim_end_matches('.').to_lowercase();
+
+    //RFC 4035 S5.3.1 - THE SIGNER HAS TO BE THE ZONE THE RRSET IS IN
+    if !(signer.is_empty() || owner == signer || owner.ends_with(&format!(".{}", signer))) {
+        return Err(DnsSecError::Malformed(format!("signer {} is not an ancestor of {}", signer, owner)));
+    }
+
+    if serial_gt(rrsig.get_inception(), now) {
+        return Err(DnsSecError::NotYetValid(rrsig.get_inception()));
+    }
+
+    if serial_gt(now, rrsig.get_expiration()) {
+        return Err(DnsSecError::Expired(rrsig.get_expiration()));
+    }
+
+    let algorithm = DnsSecAlgorithms::try_from(rrsig.get_algorithm())
+        .map_err(|_| DnsSecError::UnsupportedAlgorithm(rrsig.get_algorithm()))?;
+
+    let data = signed_data(&owner, class, rrset, rrsig)?;
+
+    let mut candidates = dnskeys.iter()
+        .filter(|dnskey| dnskey.get_algorithm() == rrsig.get_algorithm()
+            && dnskey.get_protocol() == 3
+            && dnskey.is_zone_key()
+            && !dnskey.is_revoked()
+            && dnskey.get_key_tag() == rrsig.get_key_tag())
+        .peekable();
+
+    if candidates.peek().is_none() {
+        return Err(DnsSecError::NoMatchingKey(rrsig.get_key_tag()));
+    }
+
+    //KEY TAGS COLLIDE, SO EVERY MATCHING KEY GETS A TRY
+    candidates.find(|dnskey| algorithm.verify(dnskey.get_public_key(), &data, rrsig.get_signature()))
+        .ok_or(DnsSecError::BadSignature(rrsig.get_key_tag()))
+}
+
+//RFC 4034 S3.1.8.1 - RRSIG RDATA WITHOUT THE SIGNATURE, THEN EACH RR IN CANONICAL FORM AND ORDER WITH THE ORIGINAL TTL
+pub fn signed_data(owner: &str, class: RRClasses, rrset: &RRSet, rrsig: &RRSigRecord) -> Result<Vec<u8>, DnsSecError> {
+    let rdata = rrsig.to_canonical_bytes().map_err(|e| DnsSecError::Malformed(e.to_string()))?;
+    let mut buf = rdata[2..rdata.len()-rrsig.get_signature().len()].to_vec();
+
+    //RFC 4035 S5.3.2 - A WILDCARD EXPANSION IS SIGNED AS THE WILDCARD, LABELS SAYS HOW MANY LABELS IT KEPT
+    let owner = owner.trim_end_matches('.').to_lowercase();
+    //RFC 4034 S3.1.3 - THE LABELS FIELD NEVER COUNTS A LEADING *, BUT THE OWNER KEEPS IT
+    let labels: Vec<&str> = owner.split('.').filter(|label| !label.is_empty()).collect();
+    let significant = labels.len()-(labels.first() == Some(&"*")) as usize;
+
+    let owner = match rrsig.get_labels() as usize {
+        count if count > significant => return Err(DnsSecError::Malformed(format!("labels {} exceeds owner label count {}", count, significant))),
+        count if count < labels.len() => format!("*.{}", labels[labels.len()-count..].join(".")).trim_end_matches('.').to_string(),
+        _ => labels.join(".")
+    };
+
+    let mut prefix = pack_fqdn(&owner, &mut HashMap::new(), 0, false);
+    prefix.extend_from_slice(&rrset.get_type().get_code().to_be_bytes());
+    prefix.extend_from_slice(&class.get_code().to_be_bytes());
+    prefix.extend_from_slice(&rrsig.get_original_ttl().to_be_bytes());
+
+    //RFC 4034 S6.3 - SORTED AS UNSIGNED OCTET STRINGS, DUPLICATES REMOVED
+    let mut records = rrset.get_records().iter()
+        .map(|record| record.to_canonical_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| DnsSecError::Malformed(e.to_string()))?;
+    records.sort_by(|a, b| a[2..].cmp(&b[2..]));
+    records.dedup();
+
+    for record in records {
+        buf.extend_from_slice(&prefix);
+        buf.extend_from_slice(&record);
+    }
+
+    Ok(buf)
+}
+
+#[test]
+fn test() {
+    use crate::messages::inter::rr_types::RRTypes;
+    use crate::records::in_a_record::InARecord;
+    use crate::utils::base64;
+
+    //SIGNED BY AN INDEPENDENT IMPLEMENTATION
+    let vectors = [
+        (8, "AwEAAZGziHbZa8VOa0H4Dt5CjGAw+yN+Yorozn7eiZ4wwrv+U44dUr2vjbMQnEQYCVS9JSx3jzZrI4AXhMITyUPKh1ysoZ6SoJQy978uIeVZ49r2pnOClr/GUsrIt85UMVuq4YWHgc1tWVXU5r+U+1w95n7Cdf+ttDUidsL8p9g2p21kP8ZSgVnUkT5Gs7kFtZ13Uh40/ulrQcYdKMVmEy3SV1WXrgYGLZDd0HfQ33V7VyDcnSEdLRPWEmhSMg/DqXeGllDJNAQIXHwLrRZuEOqiP4wTNscD6ZysH0Qu6I8spm/693e2d3pSglIqC2fQTH2VPPeSb1qAsMYNr2liA6uqW10=", "ITw7y73X+C7MBlSqvOi756xLaO1UoXQx4OvNn6WT83fQzK7lsGx3bfRt3bUXF47VleHxpXaA+tvwAlkN6Z71buqDfT/4OOmIfe+a7+kCii6XyIvvkWdAyM45U5ZaYu6U9b0iSLys7o7fChBVpVHJeKP6ZE3OiFRZxyCvHzAJkXJd2Jp4nmvT+E5gDHMgjBz9MwMJ/A6vM6SMOSkE780h3jvNXDbfyrEqdOuLQfPHansc/aqsfZ8xUhgFDWfAK0Qmh1wCiQ52sdZBAk1vLE4SIjCrniT60iEWFTQz5EOu6RRXQVZq32vylnDZf4+Xo6gwNEU0jNnT8XiDHqs0X7neQg=="),
+        (13, "ReHobiHJlrnu8SM0b9oOvAwyXDkw6lUJwPXySax+2AjgkVThT97/041fQ9FxeDdYi8S09I0+veLASzG8Z/5KBw==", "49NCgnXBC882pWrJsDgnlK5stL59OUZmPuHdnlzhAcij3Fv2BWEZXIKcCz174UZptOGMtQQH3IsS/p+R15qX8A=="),
+        (14, "6vB5W9dDTCNzVsBssMBMjSbgLJmfybIerZJ1UAbH0s7Wlk60L03G9VpTjET9dac6nFa1jMFoS//vd9G8JcLW2KCO0rb7EjE6mZqvQmjDVFf5o4nfyfbZhEJNu6j/8xWx", "/X8sGle2iqP3wcI9EJ7QclExC+imtwHBhPUKW4tPoDRQIG1sl2vAsokU8OLDGK0gRxmGL5PgdC1nEk8FaXaMRAerWDq3Km2oYt2NI2ZpnW2gchLctRVygQg8JcLyTX5/"),
+        (15, "lXIOU5FHL0E6AjpQkcrpreCMVfjMzndJzWnitjGTPjk=", "39QMxF4BPklemmvjW0uMzfvufl5vN7q/iXTpyCvGu+QKJ5M3maMfoMX1Y5boZqXz+Cj6UpNHymWUcOwcikyQDA==")
+    ];
+
+    let mut rrset = RRSet::new(RRTypes::A, 250);
+    rrset.add_record(250, InARecord::new([10, 0, 0, 2].into()).upcast());
+    rrset.add_record(250, InARecord::new([10, 0, 0, 1].into()).upcast());
+    rrset.add_record(250, InARecord::new([10, 0, 0, 2].into()).upcast());
+
+    let now = 1750000000;
+
+    for (algorithm, public_key, signature) in vectors {
+        let dnskeys = vec![
+            DnsKeyRecord::new(256, 3, algorithm, base64::decode("AwEAAa3ux4j50Emwloq").unwrap()),
+            DnsKeyRecord::new(257, 3, algorithm, base64::decode(public_key).unwrap())
+        ];
+        let key_tag = dnskeys[1].get_key_tag();
+        let rrsig = RRSigRecord::new(RRTypes::A, algorithm, 3, 300, 1800000000, 1700000000, key_tag, "Find9.NET", base64::decode(signature).unwrap());
+
+        let rrsigs = vec![rrsig.clone()];
+        let (by_rrsig, by_key) = validate_rrset("WWW.find9.net.", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).unwrap();
+        assert_eq!(by_rrsig.get_key_tag(), key_tag);
+        assert_eq!(by_key.get_public_key(), dnskeys[1].get_public_key());
+
+        assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, 1800000001).err(), Some(DnsSecError::Expired(1800000000)));
+        assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, 1600000000).err(), Some(DnsSecError::NotYetValid(1700000000)));
+        assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys[..1], now).err(), Some(DnsSecError::NoMatchingKey(key_tag)));
+        assert_eq!(validate_rrset("mail.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).err(), Some(DnsSecError::BadSignature(key_tag)));
+
+        //A FORGED SIGNATURE FIRST DOESN'T STOP THE GOOD ONE VALIDATING
+        let mut forged = rrsig.clone();
+        forged.set_signature(&forged.get_signature().iter().map(|b| b ^ 0x01).collect::<Vec<u8>>());
+        let rrsigs = vec![forged.clone(), rrsig.clone()];
+        assert!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).is_ok());
+        assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs[..1], &dnskeys, now).err(), Some(DnsSecError::BadSignature(key_tag)));
+
+        assert!(matches!(validate_rrset("find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now), Err(DnsSecError::Malformed(_))));
+
+        let mut revoked = dnskeys.clone();
+        revoked[1].set_flags(257 | 0x0080);
+        assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs[1..], &revoked, now).err(), Some(DnsSecError::NoMatchingKey(key_tag)));
+    }
+
+    //WILDCARD EXPANSION VALIDATES AGAINST THE SIGNATURE OVER *.FIND9.NET
+    let (algorithm, public_key, _) = vectors[3];
+    let dnskeys = vec![DnsKeyRecord::new(257, 3, algorithm, base64::decode(public_key).unwrap())];
+    let rrsigs = vec![RRSigRecord::new(RRTypes::A, algorithm, 2, 300, 1800000000, 1700000000, 62131, "find9.net", base64::decode("4HO7BmTIH+F/83H8nOqZnoJNqVRGNkw2HOZYPgBoAmEk3HKKCM5FUtcgmUtND33G2SBSbsDeMRq/rQndf27WDQ==").unwrap())];
+    assert!(validate_rrset("anything.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).is_ok());
+    assert!(validate_rrset("deeper.anything.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).is_ok());
+    assert!(validate_rrset("*.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).is_ok());
+    assert!(validate_rrset("*.Find9.NET.", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).is_ok());
+    assert_eq!(validate_rrset("find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).err(), Some(DnsSecError::BadSignature(62131)));
+    assert!(matches!(validate_rrset("www.other.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now), Err(DnsSecError::Malformed(_))));
+
+    assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &[], &dnskeys, now).err(), Some(DnsSecError::NoSignatures));
+    let rrsigs = vec![RRSigRecord::new(RRTypes::A, 3, 3, 300, 1800000000, 1700000000, 1, "find9.net", vec![0; 40])];
+    assert_eq!(validate_rrset("www.find9.net", RRClasses::In, &rrset, &rrsigs, &dnskeys, now).err(), Some(DnsSecError::UnsupportedAlgorithm(3)));
+}
@@ -0,0 +1,28 @@
+use std::fmt;
+use std::fmt::Formatter;
+use crate::records::nsec3_record::salt_to_string;
+
+//RFC 5155 S7.1 - NSEC3 WITH SHA-1, OPT-OUT LEAVES DELEGATIONS WITHOUT A DS OUT OF THE CHAIN
+#[derive(Default, Clone, Eq, PartialEq, Debug)]
+pub enum DenialTypes {
+    #[default]
+    Nsec,
+    Nsec3 {
+        iterations: u16,
+        salt: Vec<u8>,
+        opt_out: bool
+    }
+}
+
+impl fmt::Display for DenialTypes {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nsec => write!(f, "NSEC"),
+            Self::Nsec3 { iterations, salt, opt_out } => write!(f, "NSEC3 {} {}{}", iterations, salt_to_string(salt), match opt_out {
+                true => " OPTOUT",
+                false => ""
+            })
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::fmt::Formatter;
+use crate::utils::{ecdsa, ed25519, rsa, sha1, sha256, sha512};
+use crate::utils::ecdsa::EcCurves;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum DnsSecAlgorithms {
+    RsaSha1,
+    RsaSha1Nsec3Sha1,
+    RsaSha256,
+    RsaSha512,
+    EcdsaP256Sha256,
+    EcdsaP384Sha384,
+    Ed25519
+}
+
+impl DnsSecAlgorithms {
+
+    pub fn get_code(&self) -> u8 {
+        match self {
+            Self::RsaSha1 => 5,
+            Self::RsaSha1Nsec3Sha1 => 7,
+            Self::RsaSha256 => 8,
+            Self::RsaSha512 => 10,
+            Self::EcdsaP256Sha256 => 13,
+            Self::EcdsaP384Sha384 => 14,
+            Self::Ed25519 => 15
+        }
+    }
+
+    //RFC 3110 / RFC 5702 / RFC 6605 / RFC 8080 - DATA IS THE RFC 4034 S3.1.8.1 SIGNED DATA
+    pub fn verify(&self, public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::RsaSha1 | Self::RsaSha1Nsec3Sha1 => rsa::verify(public_key, &rsa::SHA1_DIGEST_INFO, &sha1::hash(data), signature),
+            Self::RsaSha256 => rsa::verify(public_key, &rsa::SHA256_DIGEST_INFO, &sha256::hash(data), signature),
+            Self::RsaSha512 => rsa::verify(public_key, &rsa::SHA512_DIGEST_INFO, &sha512::hash(data), signature),
+            Self::EcdsaP256Sha256 => ecdsa::verify(EcCurves::P256, public_key, &sha256::hash(data), signature),
+            Self::EcdsaP384Sha384 => ecdsa::verify(EcCurves::P384, public_key, &sha512::hash_384(data), signature),
+            Self::Ed25519 => ed25519::verify(public_key, data, signature)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DnsSecAlgorithmParseError(pub u8);
+
+impl fmt::Display for DnsSecAlgorithmParseError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported dnssec algorithm: {}", self.0)
+    }
+}
+
+impl TryFrom<u8> for DnsSecAlgorithms {
+
+    type Error = DnsSecAlgorithmParseError;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        Ok(match v {
+            5 => Self::RsaSha1,
+            7 => Self::RsaSha1Nsec3Sha1,
+            8 => Self::RsaSha256,
+            10 => Self::RsaSha512,
+            13 => Self::EcdsaP256Sha256,
+            14 => Self::EcdsaP384Sha384,
+            15 => Self::Ed25519,
+            _ => return Err(DnsSecAlgorithmParseError(v))
+        })
+    }
+}
+
+impl fmt::Display for DnsSecAlgorithms {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::RsaSha1 => "RSASHA1",
+            Self::RsaSha1Nsec3Sha1 => "NSEC3RSASHA1",
+            Self::RsaSha256 => "RSASHA256",
+            Self::RsaSha512 => "RSASHA512",
+            Self::EcdsaP256Sha256 => "ECDSAP256SHA256",
+            Self::EcdsaP384Sha384 => "ECDSAP384SHA384",
+            Self::Ed25519 => "ED25519"
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum KeyRoles {
+    Ksk,
+    Zsk
+}
+
+impl KeyRoles {
+
+    //RFC 4034 S2.1.1 - ZONE KEY BIT, PLUS THE SECURE ENTRY POINT BIT FOR A KSK
+    pub fn get_flags(&self) -> u16 {
+        match self {
+            Self::Ksk => 257,
+            Self::Zsk => 256
+        }
+    }
+}
+
+impl fmt::Display for KeyRoles {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Ksk => "KSK",
+            Self::Zsk => "ZSK"
+        })
+    }
+}
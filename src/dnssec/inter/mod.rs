@@ -0,0 +1,3 @@
+pub mod dnssec_algorithms;
+pub mod key_roles;
+pub mod denial_types;
@@ -0,0 +1,4 @@
+pub mod inter;
+pub mod dnssec_validator;
+pub mod dnssec_key;
+pub mod zone_signer;
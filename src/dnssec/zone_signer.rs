@@ -0,0 +1,591 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::dnssec::dnssec_key::DnsSecKey;
+use crate::dnssec::dnssec_validator::{signed_data, DnsSecError};
+use crate::dnssec::inter::denial_types::DenialTypes;
+use crate::dnssec::inter::key_roles::KeyRoles;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::RecordBase;
+use crate::records::nsec3_record::{hash_owner, NSec3Record};
+use crate::records::nsec3param_record::NSec3ParamRecord;
+use crate::records::nsec_record::NSecRecord;
+use crate::records::rrsig_record::RRSigRecord;
+use crate::utils::{base32, random, sha256};
+use crate::utils::fqdn_utils::to_fqdn;
+use crate::zone::rr_set::RRSet;
+use crate::zone::zone::{serial_gt, Zone};
+
+pub const DEFAULT_VALIDITY: u32 = 30*86400;
+pub const DEFAULT_INCEPTION_OFFSET: u32 = 3600;
+pub const DEFAULT_REFRESH: u32 = DEFAULT_VALIDITY/4;
+
+//SIGNS A ZONE IN PLACE, CALLING SIGN AGAIN AFTER THE ZONE CHANGES ONLY RE-SIGNS THE RRSETS THAT CHANGED
+//AND THE ONES WHOSE SIGNATURES ARE WITHIN THE REFRESH WINDOW OF EXPIRING
+#[derive(Debug, Clone)]
+pub struct ZoneSigner {
+    apex: String,
+    keys: Vec<DnsSecKey>,
+    denial: DenialTypes,
+    validity: u32,
+    inception_offset: u32,
+    jitter: u32,
+    refresh: u32,
+    signed: HashMap<(String, RRTypes), [u8; 32]>
+}
+
+impl ZoneSigner {
+
+    pub fn new(apex: &str) -> Self {
+        Self {
+            apex: apex.trim_end_matches('.').to_lowercase(),
+            keys: Vec::new(),
+            denial: Default::default(),
+            validity: DEFAULT_VALIDITY,
+            inception_offset: DEFAULT_INCEPTION_OFFSET,
+            jitter: 0,
+            refresh: DEFAULT_REFRESH,
+            signed: HashMap::new()
+        }
+    }
+
+    pub fn get_apex(&self) -> &str {
+        &self.apex
+    }
+
+    pub fn add_key(&mut self, key: DnsSecKey) {
+        self.keys.retain(|k| k.get_key_tag() != key.get_key_tag() || k.get_algorithm() != key.get_algorithm());
+        self.keys.push(key);
+    }
+
+    //THE DNSKEY STAYS PUBLISHED, ONLY ITS SIGNATURES GO ON THE NEXT PASS
+    pub fn remove_key(&mut self, key_tag: u16) -> Option<DnsSecKey> {
+        let index = self.keys.iter().position(|k| k.get_key_tag() == key_tag)?;
+        Some(self.keys.remove(index))
+    }
+
+    pub fn get_keys(&self) -> &Vec<DnsSecKey> {
+        &self.keys
+    }
+
+    pub fn set_denial(&mut self, denial: DenialTypes) {
+        self.denial = denial;
+    }
+
+    pub fn get_denial(&self) -> &DenialTypes {
+        &self.denial
+    }
+
+    pub fn set_validity(&mut self, validity: u32) {
+        self.validity = validity;
+    }
+
+    pub fn get_validity(&self) -> u32 {
+        self.validity
+    }
+
+    //BACKDATES THE INCEPTION SO VALIDATORS WITH A SLOW CLOCK ACCEPT NEW SIGNATURES
+    pub fn set_inception_offset(&mut self, inception_offset: u32) {
+        self.inception_offset = inception_offset;
+    }
+
+    pub fn get_inception_offset(&self) -> u32 {
+        self.inception_offset
+    }
+
+    //UP TO JITTER SECONDS ARE TAKEN OFF EACH EXPIRATION SO RE-SIGNING SPREADS OUT, KEEP IT BELOW VALIDITY - REFRESH
+    pub fn set_jitter(&mut self, jitter: u32) {
+        self.jitter = jitter;
+    }
+
+    pub fn get_jitter(&self) -> u32 {
+        self.jitter
+    }
+
+    //SIGNATURES EXPIRING WITHIN REFRESH SECONDS ARE REPLACED
+    pub fn set_refresh(&mut self, refresh: u32) {
+        self.refresh = refresh;
+    }
+
+    pub fn get_refresh(&self) -> u32 {
+        self.refresh
+    }
+
+    //RFC 4035 S2 - PUBLISHES THE DNSKEYS, REBUILDS THE DENIAL CHAIN AND SIGNS EVERY AUTHORITATIVE RRSET,
+    //RETURNS HOW MANY RRSETS WERE RE-SIGNED, NOW IS SECONDS SINCE THE EPOCH
+    pub fn sign(&mut self, zone: &mut Zone, now: u32) -> Result<usize, DnsSecError> {
+        let (soa_ttl, minimum_ttl) = zone.get_soa()
+            .map(|(ttl, soa)| (ttl, soa.get_minimum_ttl()))
+            .ok_or(DnsSecError::MissingSoa)?;
+
+        if self.keys.is_empty() {
+            return Err(DnsSecError::NoSigningKey);
+        }
+
+        for key in &self.keys {
+            if !zone.get_sets("", &RRTypes::DnsKey).is_some_and(|set| set.contains_record(key.get_dnskey())) {
+                zone.add_record("", soa_ttl, key.get_dnskey().clone().upcast());
+            }
+        }
+
+        //RFC 9077 S3 - DENIAL RECORDS USE THE LOWER OF THE SOA TTL AND MINIMUM
+        let denial_ttl = soa_ttl.min(minimum_ttl);
+
+        match &self.denial {
+            DenialTypes::Nsec => {
+                replace_set(zone, "", RRTypes::Nsec3Param, 0, None);
+                replace_chain(zone, RRTypes::Nsec3, 0, Vec::new());
+                let chain = self.nsec_chain(zone);
+                replace_chain(zone, RRTypes::Nsec, denial_ttl, chain);
+            }
+            DenialTypes::Nsec3 { iterations, salt, opt_out } => {
+                replace_set(zone, "", RRTypes::Nsec3Param, 0, Some(NSec3ParamRecord::new(1, 0, *iterations, salt).upcast()));
+                replace_chain(zone, RRTypes::Nsec, 0, Vec::new());
+                let chain = self.nsec3_chain(zone, *iterations, salt, *opt_out);
+                replace_chain(zone, RRTypes::Nsec3, denial_ttl, chain);
+            }
+        }
+
+        let names: Vec<(String, Vec<RRTypes>)> = zone.get_all_sets_recursive()
+            .map(|(name, sets)| (name, sets.iter().map(|set| set.get_type()).collect()))
+            .collect();
+
+        let mut resigned = 0;
+        let mut seen = HashSet::new();
+
+        for (name, types) in names {
+            let signable: Vec<RRTypes> = match classify(zone, &name, &types) {
+                Node::Occluded => Vec::new(),
+                Node::Delegation => types.into_iter().filter(|t| matches!(t, RRTypes::Ds | RRTypes::Nsec)).collect(),
+                Node::Authoritative => types.into_iter().filter(|t| *t != RRTypes::RRSig).collect()
+            };
+
+            let existing: Vec<RRSigRecord> = zone.get_sets(&name, &RRTypes::RRSig)
+                .map(|set| set.get_records().iter()
+                    .filter_map(|record| record.as_any().downcast_ref::<RRSigRecord>().cloned())
+                    .collect())
+                .unwrap_or_default();
+
+            let mut rrsigs = Vec::new();
+            let mut changed = false;
+
+            for _type in signable {
+                let Some(set) = zone.get_sets(&name, &_type) else {
+                    continue;
+                };
+
+                let fingerprint = fingerprint(set)?;
+                let signers = self.signers(_type);
+                let covering: Vec<&RRSigRecord> = existing.iter().filter(|rrsig| rrsig.get_type_covered() == _type).collect();
+
+                let fresh = self.signed.get(&(name.clone(), _type)) == Some(&fingerprint)
+                    && covering.len() == signers.len()
+                    && signers.iter().all(|key| covering.iter().any(|rrsig| rrsig.get_key_tag() == key.get_key_tag()
+                        && rrsig.get_algorithm() == key.get_algorithm().get_code()
+                        && rrsig.get_original_ttl() == set.get_ttl()
+                        && !serial_gt(rrsig.get_inception(), now)
+                        && serial_gt(rrsig.get_expiration(), now.wrapping_add(self.refresh))));
+
+                match fresh {
+                    true => rrsigs.extend(covering.into_iter().map(|rrsig| (set.get_ttl(), rrsig.clone()))),
+                    false => {
+                        for key in signers {
+                            rrsigs.push((set.get_ttl(), self.sign_set(zone, &name, set, key, now)?));
+                        }
+
+                        self.signed.insert((name.clone(), _type), fingerprint);
+                        resigned += 1;
+                        changed = true;
+                    }
+                }
+
+                seen.insert((name.clone(), _type));
+            }
+
+            if changed || rrsigs.len() != existing.len() {
+                zone.remove_set(&name, &RRTypes::RRSig);
+                for (ttl, rrsig) in rrsigs {
+                    zone.add_record(&name, ttl, rrsig.upcast());
+                }
+            }
+        }
+
+        self.signed.retain(|key, _| seen.contains(key));
+
+        Ok(resigned)
+    }
+
+    //RFC 4034 S4.1.1 - ONE NSEC PER AUTHORITATIVE NAME AND DELEGATION IN CANONICAL ORDER, THE LAST POINTS BACK TO THE APEX
+    fn nsec_chain(&self, zone: &Zone) -> Vec<(String, Box<dyn RecordBase>)> {
+        let mut names: Vec<(String, Vec<RRTypes>)> = zone.get_all_sets_recursive()
+            .filter_map(|(name, sets)| {
+                let types: Vec<RRTypes> = sets.iter().map(|set| set.get_type()).collect();
+                let delegation = match classify(zone, &name, &types) {
+                    Node::Occluded => return None,
+                    Node::Delegation => true,
+                    Node::Authoritative => false
+                };
+
+                let mut types: Vec<RRTypes> = types.into_iter()
+                    .filter(|t| !matches!(t, RRTypes::RRSig | RRTypes::Nsec | RRTypes::Nsec3))
+                    .filter(|t| !delegation || matches!(t, RRTypes::Ns | RRTypes::Ds))
+                    .chain([RRTypes::RRSig, RRTypes::Nsec])
+                    .collect();
+                types.sort_by_key(|t| t.get_code());
+
+                Some((name, types))
+            })
+            .collect();
+        names.sort_by(|(a, _), (b, _)| canonical_cmp(a, b));
+
+        (0..names.len()).map(|i| {
+            let next = to_fqdn(&self.apex, &names[(i+1) % names.len()].0).to_lowercase();
+            (names[i].0.clone(), NSecRecord::new(&next, names[i].1.clone()).upcast())
+        }).collect()
+    }
+
+    //RFC 5155 S7.1 - HASHED OWNERS AS ONE LABEL UNDER THE APEX, EMPTY NON-TERMINALS GET AN NSEC3 WITH NO TYPES
+    fn nsec3_chain(&self, zone: &Zone, iterations: u16, salt: &[u8], opt_out: bool) -> Vec<(String, Box<dyn RecordBase>)> {
+        let mut names = HashMap::new();
+
+        for (name, sets) in zone.get_all_sets_recursive() {
+            let types: Vec<RRTypes> = sets.iter().map(|set| set.get_type()).collect();
+
+            //THE PREVIOUS CHAIN'S HASHED OWNERS AREN'T NAMES IN THE ZONE
+            if types.iter().all(|t| matches!(t, RRTypes::Nsec3 | RRTypes::RRSig)) {
+                continue;
+            }
+
+            let delegation = match classify(zone, &name, &types) {
+                Node::Occluded => continue,
+                Node::Delegation => true,
+                Node::Authoritative => false
+            };
+
+            let secure = types.contains(&RRTypes::Ds);
+            if delegation && opt_out && !secure {
+                continue;
+            }
+
+            let mut types: Vec<RRTypes> = types.into_iter()
+                .filter(|t| !matches!(t, RRTypes::RRSig | RRTypes::Nsec | RRTypes::Nsec3))
+                .filter(|t| !delegation || matches!(t, RRTypes::Ns | RRTypes::Ds))
+                .collect();
+
+            //AN INSECURE DELEGATION HAS NOTHING SIGNED AT IT
+            if !delegation || secure {
+                types.push(RRTypes::RRSig);
+            }
+            types.sort_by_key(|t| t.get_code());
+
+            names.insert(name, types);
+        }
+
+        let ents: BTreeSet<String> = names.keys()
+            .flat_map(|name| {
+                let labels: Vec<&str> = name.split('.').collect();
+                (1..labels.len()).map(move |i| labels[i..].join("."))
+            })
+            .filter(|ent| !names.contains_key(ent))
+            .collect();
+        names.extend(ents.into_iter().map(|ent| (ent, Vec::new())));
+
+        let mut hashed: Vec<([u8; 20], Vec<RRTypes>)> = names.into_iter()
+            .map(|(name, types)| (hash_owner(&to_fqdn(&self.apex, &name), salt, iterations), types))
+            .collect();
+        hashed.sort_by_key(|(hash, _)| *hash);
+        hashed.dedup_by(|(a, _), (b, _)| a == b);
+
+        (0..hashed.len()).map(|i| {
+            let mut record = NSec3Record::new(1, 0, iterations, salt, &hashed[(i+1) % hashed.len()].0, hashed[i].1.clone());
+            record.set_opt_out(opt_out);
+            (base32::encode(&hashed[i].0), record.upcast())
+        }).collect()
+    }
+
+    //RFC 6781 S3.1 - THE KSK SIGNS THE DNSKEY RRSET AND THE ZSK EVERYTHING ELSE, ONE ROLE ALONE SIGNS IT ALL
+    fn signers(&self, _type: RRTypes) -> Vec<&DnsSecKey> {
+        let role = match _type {
+            RRTypes::DnsKey => KeyRoles::Ksk,
+            _ => KeyRoles::Zsk
+        };
+
+        let keys: Vec<&DnsSecKey> = self.keys.iter().filter(|key| key.get_role() == role).collect();
+        match keys.is_empty() {
+            true => self.keys.iter().collect(),
+            false => keys
+        }
+    }
+
+    fn sign_set(&self, zone: &Zone, name: &str, set: &RRSet, key: &DnsSecKey, now: u32) -> Result<RRSigRecord, DnsSecError> {
+        let owner = to_fqdn(&self.apex, name);
+
+        //RFC 4034 S3.1.3 - A LEADING WILDCARD LABEL ISN'T COUNTED
+        let mut labels = owner.split('.').filter(|label| !label.is_empty()).count();
+        if owner.starts_with("*.") || owner == "*" {
+            labels -= 1;
+        }
+
+        let jitter = random::gen::<u32>() % self.jitter.saturating_add(1);
+
+        let mut rrsig = RRSigRecord::new(set.get_type(),
+                                         key.get_algorithm().get_code(),
+                                         labels as u8,
+                                         set.get_ttl(),
+                                         now.wrapping_add(self.validity).wrapping_sub(jitter),
+                                         now.wrapping_sub(self.inception_offset),
+                                         key.get_key_tag(),
+                                         &self.apex,
+                                         Vec::new());
+
+        let data = signed_data(&owner, zone.get_class(), set, &rrsig)?;
+        rrsig.set_signature(&key.sign(&data).ok_or(DnsSecError::SigningFailed(key.get_key_tag()))?);
+        Ok(rrsig)
+    }
+}
+
+enum Node {
+    Authoritative,
+    Delegation,
+    Occluded
+}
+
+//RFC 4035 S2.2 - BELOW A ZONE CUT OR A DNAME NOTHING IS AUTHORITATIVE, AT A CUT ONLY DS AND THE DENIAL RECORD ARE
+fn classify(zone: &Zone, name: &str, types: &[RRTypes]) -> Node {
+    if name.is_empty() || types.contains(&RRTypes::Nsec3) {
+        return Node::Authoritative;
+    }
+
+    let labels: Vec<&str> = name.split('.').collect();
+    let occluded = (1..=labels.len()).any(|i| {
+        let ancestor = labels[i..].join(".");
+        zone.get_sets(&ancestor, &RRTypes::DName).is_some() || (!ancestor.is_empty() && zone.get_sets(&ancestor, &RRTypes::Ns).is_some())
+    });
+
+    match (occluded, types.contains(&RRTypes::Ns)) {
+        (true, _) => Node::Occluded,
+        (false, true) => Node::Delegation,
+        (false, false) => Node::Authoritative
+    }
+}
+
+//RFC 4034 S6.1 - LABELS COMPARED RIGHT TO LEFT AS LOWERCASE OCTET STRINGS
+fn canonical_cmp(a: &str, b: &str) -> Ordering {
+    let labels = |name: &str| name.split('.')
+        .rev()
+        .filter(|label| !label.is_empty())
+        .map(|label| label.to_ascii_lowercase().into_bytes())
+        .collect::<Vec<_>>();
+
+    labels(a).cmp(&labels(b))
+}
+
+//THE TTL AND THE CANONICAL RDATA, ANY CHANGE TO EITHER MEANS THE OLD SIGNATURE NO LONGER COVERS THE SET
+fn fingerprint(set: &RRSet) -> Result<[u8; 32], DnsSecError> {
+    let mut records = set.get_records().iter()
+        .map(|record| record.to_canonical_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| DnsSecError::Malformed(e.to_string()))?;
+    records.sort();
+
+    let mut buf = set.get_ttl().to_be_bytes().to_vec();
+    for record in records {
+        buf.extend_from_slice(&record);
+    }
+
+    Ok(sha256::hash(&buf))
+}
+
+//ONLY TOUCHES THE ZONE WHEN THE RECORD OR ITS TTL ACTUALLY CHANGED
+fn replace_set(zone: &mut Zone, name: &str, _type: RRTypes, ttl: u32, record: Option<Box<dyn RecordBase>>) {
+    let current = zone.get_sets(name, &_type);
+
+    match record {
+        Some(record) => {
+            if !current.is_some_and(|set| set.get_ttl() == ttl && set.total_records() == 1 && set.contains_record(record.as_ref())) {
+                zone.remove_set(name, &_type);
+                zone.add_record(name, ttl, record);
+            }
+        }
+        None => {
+            if current.is_some() {
+                zone.remove_set(name, &_type);
+            }
+        }
+    }
+}
+
+fn replace_chain(zone: &mut Zone, _type: RRTypes, ttl: u32, chain: Vec<(String, Box<dyn RecordBase>)>) {
+    let owners: HashSet<&String> = chain.iter().map(|(name, _)| name).collect();
+    let stale: Vec<String> = zone.get_all_sets_recursive()
+        .filter(|(name, sets)| !owners.contains(name) && sets.iter().any(|set| set.get_type() == _type))
+        .map(|(name, _)| name)
+        .collect();
+
+    //A STALE OWNER LEFT WITH ONLY ITS RRSIGS WOULD OTHERWISE STILL LOOK LIKE A NAME IN THE ZONE
+    for name in stale {
+        zone.remove_set(&name, &_type);
+        if zone.get_all_sets(&name).is_some_and(|sets| sets.iter().all(|set| set.get_type() == RRTypes::RRSig)) {
+            zone.remove_all_records(&name);
+        }
+    }
+
+    for (name, record) in chain {
+        replace_set(zone, &name, _type, ttl, Some(record));
+    }
+}
+
+#[test]
+fn test() {
+    use crate::dnssec::dnssec_validator::validate_rrset;
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::records::dnskey_record::DnsKeyRecord;
+    use crate::records::ds_record::DsRecord;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::mx_record::MxRecord;
+    use crate::records::ns_record::NsRecord;
+    use crate::records::soa_record::SoaRecord;
+    use crate::records::txt_record::TxtRecord;
+    use crate::utils::base64;
+    use crate::zone::inter::zone_types::ZoneTypes;
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 3600, SoaRecord::new("ns1.find9.net", "admin.find9.net", 1, 7200, 3600, 1209600, 300).upcast());
+    zone.add_record("", 3600, NsRecord::new("ns1.find9.net").upcast());
+    zone.add_record("", 300, MxRecord::new(10, "mail.find9.net").upcast());
+    zone.add_record("ns1", 300, InARecord::new([10, 0, 0, 1].into()).upcast());
+    zone.add_record("www", 300, InARecord::new([10, 0, 0, 2].into()).upcast());
+    zone.add_record("*.wild", 300, InARecord::new([10, 0, 0, 3].into()).upcast());
+    zone.add_record("a.b.deep", 300, TxtRecord::new(vec!["deep".to_string()]).upcast());
+    zone.add_record("insecure", 3600, NsRecord::new("ns.insecure.find9.net").upcast());
+    zone.add_record("ns.insecure", 3600, InARecord::new([10, 0, 1, 1].into()).upcast());
+    zone.add_record("secure", 3600, NsRecord::new("ns.other.net").upcast());
+    zone.add_record("secure", 3600, DsRecord::new(12345, 13, 2, vec![0xab; 32]).upcast());
+
+    let ksk = DnsSecKey::parse("Private-key-format: v1.3\nAlgorithm: 13 (ECDSAP256SHA256)\nPrivateKey: ya+p2EW6dRZrXCFXZ7HWk05Qw9s26JsSe4piKxIPZyE=\n", KeyRoles::Ksk).unwrap();
+    let zsk = DnsSecKey::parse("Private-key-format: v1.3\nAlgorithm: 15 (ED25519)\nPrivateKey: nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=\n", KeyRoles::Zsk).unwrap();
+
+    let mut signer = ZoneSigner::new("Find9.net.");
+    assert_eq!(signer.sign(&mut zone, 1750000000).err(), Some(DnsSecError::NoSigningKey));
+    assert_eq!(signer.sign(&mut Zone::new(ZoneTypes::Master, RRClasses::In), 1750000000).err(), Some(DnsSecError::MissingSoa));
+
+    signer.add_key(ksk.clone());
+    signer.add_key(zsk.clone());
+
+    //EVERY AUTHORITATIVE RRSET VALIDATES AND NOTHING AT OR BELOW A CUT IS SIGNED EXCEPT DS AND NSEC
+    let verify = |zone: &Zone, now: u32| -> usize {
+        let dnskeys: Vec<DnsKeyRecord> = zone.get_sets("", &RRTypes::DnsKey).unwrap().get_records().iter()
+            .map(|record| record.as_any().downcast_ref::<DnsKeyRecord>().unwrap().clone())
+            .collect();
+
+        let mut signed = 0;
+        for (name, sets) in zone.get_all_sets_recursive() {
+            let rrsigs: Vec<RRSigRecord> = zone.get_sets(&name, &RRTypes::RRSig)
+                .map(|set| set.get_records().iter().map(|record| record.as_any().downcast_ref::<RRSigRecord>().unwrap().clone()).collect())
+                .unwrap_or_default();
+
+            for set in sets.iter().filter(|set| set.get_type() != RRTypes::RRSig) {
+                let unsigned = name.starts_with("ns.insecure") || (name.ends_with("secure") && set.get_type() == RRTypes::Ns);
+                match unsigned {
+                    true => assert!(rrsigs.iter().all(|rrsig| rrsig.get_type_covered() != set.get_type()), "{} {}", name, set.get_type()),
+                    false => {
+                        validate_rrset(&to_fqdn("find9.net", &name), RRClasses::In, set, &rrsigs, &dnskeys, now).unwrap();
+                        assert_eq!(rrsigs.iter().filter(|r| r.get_type_covered() == set.get_type()).count(), 1);
+                        signed += 1;
+                    }
+                }
+            }
+        }
+
+        signed
+    };
+
+    let now = 1750000000;
+    let total = signer.sign(&mut zone, now).unwrap();
+    assert_eq!(verify(&zone, now), total);
+    assert!(zone.get_sets("ns.insecure", &RRTypes::RRSig).is_none());
+    assert!(zone.get_sets("ns.insecure", &RRTypes::Nsec).is_none());
+    let key_tag = |name: &str| zone.get_sets(name, &RRTypes::RRSig).unwrap().get_records()[0].as_any().downcast_ref::<RRSigRecord>().unwrap().get_key_tag();
+    assert_eq!(key_tag("www"), zsk.get_key_tag());
+    assert_eq!(zone.get_sets("", &RRTypes::RRSig).unwrap().get_records().iter()
+        .map(|record| record.as_any().downcast_ref::<RRSigRecord>().unwrap())
+        .find(|rrsig| rrsig.get_type_covered() == RRTypes::DnsKey).unwrap().get_key_tag(), ksk.get_key_tag());
+    let wildcard = zone.get_sets("*.wild", &RRTypes::RRSig).unwrap().get_records()[0].as_any().downcast_ref::<RRSigRecord>().unwrap().clone();
+    assert_eq!(wildcard.get_labels(), 3);
+
+    //SIGNED BY AN INDEPENDENT IMPLEMENTATION OVER THE LITERAL *.WILD.FIND9.NET OWNER
+    assert_eq!(wildcard.get_key_tag(), 14016);
+    assert_eq!(wildcard.get_signature(), base64::decode("T7w+tT9OT5orhw2CKc7Y2mgflCBAfnzcncLxq9IfpgTl8d/E6krSqaOWb0p0jze2w02cLseISPHpSymn4l6KAg==").unwrap());
+
+    //THE CHAIN WALKS EVERY AUTHORITATIVE NAME AND DELEGATION IN CANONICAL ORDER AND COMES BACK TO THE APEX
+    let mut chain = Vec::new();
+    let mut name = String::new();
+    loop {
+        let nsec = zone.get_sets(&name, &RRTypes::Nsec).unwrap().get_records()[0].as_any().downcast_ref::<NSecRecord>().unwrap().clone();
+        assert!(nsec.get_rr_types().contains(&RRTypes::RRSig));
+        chain.push(name.clone());
+        name = nsec.get_next_domain().unwrap().strip_suffix("find9.net").unwrap().trim_end_matches('.').to_string();
+        if name.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(chain, vec!["", "a.b.deep", "insecure", "ns1", "secure", "*.wild", "www"]);
+    assert_eq!(zone.get_sets("secure", &RRTypes::Nsec).unwrap().get_records()[0].as_any().downcast_ref::<NSecRecord>().unwrap().get_rr_types(), &vec![RRTypes::Ns, RRTypes::Ds, RRTypes::RRSig, RRTypes::Nsec]);
+
+    //NOTHING CHANGED SO NOTHING IS RE-SIGNED
+    assert_eq!(signer.sign(&mut zone, now+60).unwrap(), 0);
+
+    //A CHANGED RRSET IS RE-SIGNED ALONE, A NEW NAME ALSO RE-SIGNS ITS OWN NSEC AND ITS PREDECESSOR'S
+    zone.add_record("www", 300, InARecord::new([10, 0, 0, 4].into()).upcast());
+    assert_eq!(signer.sign(&mut zone, now).unwrap(), 1);
+    zone.add_record("mail", 300, InARecord::new([10, 0, 0, 5].into()).upcast());
+    assert_eq!(signer.sign(&mut zone, now).unwrap(), 3);
+    assert_eq!(verify(&zone, now), total+2);
+
+    //SIGNATURES INSIDE THE REFRESH WINDOW ARE REPLACED, JITTER ONLY EVER SHORTENS THE VALIDITY
+    signer.set_jitter(3600);
+    let later = now+DEFAULT_VALIDITY-DEFAULT_REFRESH+1;
+    assert_eq!(signer.sign(&mut zone, later).unwrap(), total+2);
+    let rrsig = zone.get_sets("www", &RRTypes::RRSig).unwrap().get_records()[0].as_any().downcast_ref::<RRSigRecord>().unwrap().clone();
+    assert!(rrsig.get_expiration() <= later+DEFAULT_VALIDITY && rrsig.get_expiration() >= later+DEFAULT_VALIDITY-3600);
+
+    //NSEC3 REPLACES THE NSEC CHAIN, ENTS ARE HASHED TOO AND OPT-OUT SKIPS THE INSECURE DELEGATION
+    signer.set_denial(DenialTypes::Nsec3 { iterations: 0, salt: vec![0xaa, 0xbb], opt_out: true });
+    signer.sign(&mut zone, later).unwrap();
+    assert!(zone.get_all_sets_recursive().all(|(_, sets)| sets.iter().all(|set| set.get_type() != RRTypes::Nsec)));
+    assert!(zone.get_sets("", &RRTypes::Nsec3Param).is_some());
+
+    let owners: Vec<String> = ["", "a.b.deep", "b.deep", "deep", "mail", "ns1", "secure", "*.wild", "wild", "www"].iter()
+        .map(|name| base32::encode(&hash_owner(&to_fqdn("find9.net", name), &[0xaa, 0xbb], 0)))
+        .collect();
+    let mut hashed: Vec<String> = zone.get_all_sets_recursive()
+        .filter(|(_, sets)| sets.iter().any(|set| set.get_type() == RRTypes::Nsec3))
+        .map(|(name, _)| name)
+        .collect();
+    hashed.sort();
+    let mut expected = owners.clone();
+    expected.sort();
+    assert_eq!(hashed, expected);
+
+    let ent = zone.get_sets(&owners[2], &RRTypes::Nsec3).unwrap().get_records()[0].as_any().downcast_ref::<NSec3Record>().unwrap().clone();
+    assert!(ent.get_rr_types().is_empty());
+    assert!(ent.is_opt_out());
+    assert_eq!(verify(&zone, later), total+2-8+owners.len()+1);
+
+    //SIGNING AGAIN DOESN'T HASH THE HASHED OWNERS, THE CHAIN STAYS THE SAME AND NOTHING IS RE-SIGNED
+    assert_eq!(signer.sign(&mut zone, later).unwrap(), 0);
+    assert_eq!(signer.sign(&mut zone, later).unwrap(), 0);
+    assert_eq!(zone.get_all_sets_recursive().filter(|(_, sets)| sets.iter().any(|set| set.get_type() == RRTypes::Nsec3)).count(), owners.len());
+
+    //SWITCHING BACK REMOVES THE NSEC3 CHAIN
+    signer.set_denial(DenialTypes::Nsec);
+    signer.sign(&mut zone, later).unwrap();
+    assert!(zone.get_sets("", &RRTypes::Nsec3Param).is_none());
+    assert!(zone.get_all_sets_recursive().all(|(_, sets)| sets.iter().all(|set| set.get_type() != RRTypes::Nsec3)));
+    assert_eq!(verify(&zone, later), total+2);
+
+    //A REMOVED KEY'S SIGNATURES GO AND THE OTHER KEY SIGNS EVERYTHING
+    signer.remove_key(zsk.get_key_tag()).unwrap();
+    assert_eq!(signer.sign(&mut zone, later).unwrap(), total+2-1);
+    assert_eq!(zone.get_sets("www", &RRTypes::RRSig).unwrap().get_records()[0].as_any().downcast_ref::<RRSigRecord>().unwrap().get_key_tag(), ksk.get_key_tag());
+    assert_eq!(verify(&zone, later), total+2);
+}
@@ -1,5 +1,4 @@
-use std::io;
-use crate::journal::journal_reader::JournalReader;
+use crate::journal::journal_reader::{JournalError, JournalReader};
 use crate::journal::txn::Txn;
 use crate::utils::index_map::IndexMap;
 
@@ -16,11 +15,12 @@ impl Journal {
         }
     }
 
-    pub fn open(file_path: &str) -> io::Result<Self> {
+    pub fn open(file_path: &str) -> Result<Self, JournalError> {
         let mut txns = IndexMap::new();
 
         let mut reader = JournalReader::open(file_path)?;
         for txn in reader.iter() {
+            let txn = txn?;
             txns.insert(txn.get_serial_0(), txn);
         }
 
@@ -29,6 +29,10 @@ impl Journal {
         })
     }
 
+    pub fn add_txn(&mut self, txn: Txn) {
+        self.txns.insert(txn.get_serial_0(), txn);
+    }
+
     pub fn get_txns(&self) -> &IndexMap<u32, Txn> {
         self.txns.as_ref()
     }
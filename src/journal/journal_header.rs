@@ -0,0 +1,88 @@
+use std::io::Read;
+use crate::journal::journal_reader::{JournalError, JournalVersions};
+use crate::journal::journal_writer::JOURNAL_HEADER_SIZE;
+
+//THE FIXED HEADER AND INDEX EVERY JOURNAL STARTS WITH, SHARED SO THE READER AND WRITER CHECK IT THE SAME WAY
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct JournalHeader {
+    pub(crate) version: JournalVersions,
+    pub(crate) begin_serial: u32,
+    pub(crate) begin_offset: u32,
+    pub(crate) end_serial: u32,
+    pub(crate) end_offset: u32,
+    pub(crate) index: Vec<(u32, u32)>,
+    pub(crate) source_serial: u32,
+    pub(crate) flags: u8
+}
+
+impl JournalHeader {
+
+    //READS FROM THE START OF A FILE FILE_LEN BYTES LONG, LEAVING THE READER JUST PAST THE INDEX
+    pub(crate) fn read<R: Read>(reader: &mut R, file_len: u64) -> Result<Self, JournalError> {
+        let mut buf = vec![0u8; JOURNAL_HEADER_SIZE as usize];
+        reader.read_exact(&mut buf).map_err(|_| JournalError::HeaderError("file is shorter than the header".to_string()))?;
+
+        //THE MAGIC IS A NUL PADDED 16 BYTE FIELD, V9 IS A PREFIX OF NOTHING ELSE ONCE THE NEWLINE IS INCLUDED
+        let version = [JournalVersions::V92, JournalVersions::V9].into_iter()
+            .find(|v| buf[..16].starts_with(v.get_magic()) && buf[v.get_magic().len()..16].iter().all(|b| *b == 0))
+            .ok_or_else(|| JournalError::HeaderError("bad magic, expected ;BIND LOG V9 or V9.2".to_string()))?;
+
+        let begin_serial = u32::from_be_bytes([buf[16], buf[17], buf[18], buf[19]]);
+        let begin_offset = u32::from_be_bytes([buf[20], buf[21], buf[22], buf[23]]);
+        let end_serial = u32::from_be_bytes([buf[24], buf[25], buf[26], buf[27]]);
+        let end_offset = u32::from_be_bytes([buf[28], buf[29], buf[30], buf[31]]);
+        let index_size = u32::from_be_bytes([buf[32], buf[33], buf[34], buf[35]]);
+        let source_serial = u32::from_be_bytes([buf[36], buf[37], buf[38], buf[39]]);
+        let flags = buf[40];
+
+        //THE INDEX HAS TO FIT BETWEEN THE HEADER AND THE FIRST TRANSACTION BEFORE IT IS READ
+        let index_end = JOURNAL_HEADER_SIZE as u64+index_size as u64*8;
+        if (begin_offset as u64) < index_end || begin_offset > end_offset || end_offset as u64 > file_len {
+            return Err(JournalError::HeaderError(format!("offsets {}..{} do not fit a {} byte file with a {} entry index", begin_offset, end_offset, file_len, index_size)));
+        }
+
+        //EACH INDEX ENTRY IS SERIAL AND OFFSET, UNUSED ENTRIES ARE ZERO
+        let mut buf = vec![0u8; index_size as usize*8];
+        reader.read_exact(&mut buf).map_err(|e| JournalError::Io { offset: JOURNAL_HEADER_SIZE as u64, reason: e.to_string() })?;
+
+        let index = buf.chunks_exact(8)
+            .map(|e| (u32::from_be_bytes([e[0], e[1], e[2], e[3]]), u32::from_be_bytes([e[4], e[5], e[6], e[7]])))
+            .collect();
+
+        Ok(Self {
+            version,
+            begin_serial,
+            begin_offset,
+            end_serial,
+            end_offset,
+            index,
+            source_serial,
+            flags
+        })
+    }
+}
+
+#[test]
+fn test() {
+    let mut buf = vec![0u8; JOURNAL_HEADER_SIZE as usize+16];
+    buf[..14].copy_from_slice(b";BIND LOG V9\n\0");
+    buf[16..20].copy_from_slice(&1u32.to_be_bytes());
+    buf[20..24].copy_from_slice(&80u32.to_be_bytes());
+    buf[24..28].copy_from_slice(&2u32.to_be_bytes());
+    buf[28..32].copy_from_slice(&80u32.to_be_bytes());
+    buf[32..36].copy_from_slice(&2u32.to_be_bytes());
+    buf[40] = 1;
+    buf[64..72].copy_from_slice(&[0, 0, 0, 1, 0, 0, 0, 80]);
+
+    let header = JournalHeader::read(&mut buf.as_slice(), buf.len() as u64).unwrap();
+    assert_eq!(header.version, JournalVersions::V9);
+    assert_eq!((header.begin_serial, header.end_serial, header.flags), (1, 2, 1));
+    assert_eq!(header.index, vec![(1, 80), (0, 0)]);
+
+    //AN INDEX THAT RUNS PAST THE FIRST TRANSACTION IS REJECTED BEFORE ANYTHING IS ALLOCATED
+    buf[32..36].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(matches!(JournalHeader::read(&mut buf.as_slice(), buf.len() as u64), Err(JournalError::HeaderError(_))));
+
+    buf[5] = b'X';
+    assert!(matches!(JournalHeader::read(&mut buf.as_slice(), buf.len() as u64), Err(JournalError::HeaderError(_))));
+}
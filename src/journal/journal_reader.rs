@@ -1,79 +1,145 @@
+use std::fmt;
+use std::fmt::Formatter;
 use std::fs::File;
 use std::io;
 use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use crate::journal::inter::txn_op_codes::TxnOpCodes;
+use crate::journal::journal_header::JournalHeader;
 use crate::journal::txn::Txn;
 use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::RecordBase;
 use crate::utils::fqdn_utils::unpack_fqdn;
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum JournalError {
+    Io { offset: u64, reason: String },
+    HeaderError(String),
+    TxnError { offset: u64, reason: String },
+    RecordError { offset: u64, reason: String },
+    SerialNotFound(u32)
+}
+
+impl fmt::Display for JournalError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { offset, reason } => write!(f, "io error at offset {}: {}", offset, reason),
+            Self::HeaderError(reason) => write!(f, "invalid journal header: {}", reason),
+            Self::TxnError { offset, reason } => write!(f, "transaction at offset {}: {}", offset, reason),
+            Self::RecordError { offset, reason } => write!(f, "record at offset {}: {}", offset, reason),
+            Self::SerialNotFound(serial) => write!(f, "no transaction starts at serial {}", serial)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum JournalVersions {
+    V9,
+    V92
+}
+
+impl JournalVersions {
+
+    pub fn get_magic(&self) -> &'static [u8] {
+        match self {
+            Self::V9 => b";BIND LOG V9\n",
+            Self::V92 => b";BIND LOG V9.2\n"
+        }
+    }
+
+    //V9.2 ADDED THE RR COUNT TO THE TRANSACTION HEADER
+    pub fn get_txn_header_size(&self) -> u64 {
+        match self {
+            Self::V9 => 12,
+            Self::V92 => 16
+        }
+    }
+}
+
 pub struct JournalReader {
     reader: BufReader<File>,
+    version: JournalVersions,
     begin_serial: u32,
     begin_offset: u32,
     end_serial: u32,
     end_offset: u32,
-    index_size: u32,
+    index: Vec<(u32, u32)>,
     source_serial: u32,
-    flags: u8
+    flags: u8,
+    failed: bool
 }
 
 impl JournalReader {
 
-    pub fn open<P: Into<PathBuf>>(file_path: P) -> io::Result<Self> {
-        let file = File::open(file_path.into())?;
-        let mut reader = BufReader::new(file);
+    pub fn open<P: Into<PathBuf>>(file_path: P) -> Result<Self, JournalError> {
+        let io_error = |e: io::Error| JournalError::Io { offset: 0, reason: e.to_string() };
 
+        let file = File::open(file_path.into()).map_err(io_error)?;
+        let file_len = file.metadata().map_err(io_error)?.len();
+        let mut reader = BufReader::new(file);
 
+        let header = JournalHeader::read(&mut reader, file_len)?;
+        let (begin_offset, end_offset) = (header.begin_offset, header.end_offset);
 
-        let mut buf = vec![0u8; 64];
-        reader.read_exact(&mut buf)?;
-
-        // Magic (first 16 bytes): ";BIND LOG V9\n" or ";BIND LOG V9.2\n"
-        let magic = true;//&buf[0..16];
-        let v9 = b";BIND LOG V9\n";
-        let v92 = b";BIND LOG V9.2\n";
-        //if !(magic.starts_with(v9) || magic.starts_with(v92)) {
-        //    //return Err(io::Error::new(io::ErrorKind::InvalidData, "bad .jnl magic"));
-        //}
-
-        //let is_v92 = magic.starts_with(v92);
-
-        let begin_serial = u32::from_be_bytes([buf[16], buf[17], buf[18], buf[19]]);
-        let begin_offset = u32::from_be_bytes([buf[20], buf[21], buf[22], buf[23]]);
-        let end_serial = u32::from_be_bytes([buf[24], buf[25], buf[26], buf[27]]);
-        let end_offset = u32::from_be_bytes([buf[28], buf[29], buf[30], buf[31]]);
-        let index_size = u32::from_be_bytes([buf[32], buf[33], buf[34], buf[35]]);
-        let source_serial = u32::from_be_bytes([buf[36], buf[37], buf[38], buf[39]]);
-        let flags = buf[40];
-
-        // ===== 2) OPTIONAL INDEX =====
-        // Each index entry is 8 bytes: [serial(4) | offset(4)]
-        reader.seek(SeekFrom::Current((index_size as i64) * 8))?;
+        let index = header.index.into_iter()
+            .filter(|(_, offset)| *offset >= begin_offset && *offset < end_offset)
+            .collect();
 
-        // ===== 3) POSITION TO FIRST TRANSACTION =====
-        reader.seek(SeekFrom::Start(begin_offset as u64))?;
+        reader.seek(SeekFrom::Start(begin_offset as u64)).map_err(|e| JournalError::Io { offset: begin_offset as u64, reason: e.to_string() })?;
 
         Ok(Self {
             reader,
-            begin_serial,
+            version: header.version,
+            begin_serial: header.begin_serial,
             begin_offset,
-            end_serial,
+            end_serial: header.end_serial,
             end_offset,
-            index_size,
-            source_serial,
-            flags
+            index,
+            source_serial: header.source_serial,
+            flags: header.flags,
+            failed: false
         })
     }
 
+    //JUMPS TO THE CLOSEST INDEXED TRANSACTION AT OR BEFORE SERIAL AND SCANS FORWARD FROM THERE,
+    //THE POSITION IS LEFT UNCHANGED IF NO TRANSACTION STARTS AT SERIAL
+    pub fn seek_serial(&mut self, serial: u32) -> Result<(), JournalError> {
+        let position = self.reader.stream_position().map_err(|e| JournalError::Io { offset: 0, reason: e.to_string() })?;
+        let target = serial.wrapping_sub(self.begin_serial);
+
+        let mut offset = self.index.iter()
+            .filter(|(s, _)| s.wrapping_sub(self.begin_serial) <= target)
+            .max_by_key(|(s, _)| s.wrapping_sub(self.begin_serial))
+            .map(|(_, offset)| *offset as u64)
+            .unwrap_or(self.begin_offset as u64);
+
+        while offset < self.end_offset as u64 {
+            let (size, _, serial_0, _) = self.read_txn_header(offset)?;
+
+            if serial_0 == serial {
+                return self.reader.seek(SeekFrom::Start(offset)).map(|_| ())
+                    .map_err(|e| JournalError::Io { offset, reason: e.to_string() });
+            }
+
+            offset += self.version.get_txn_header_size()+size as u64;
+        }
+
+        self.reader.seek(SeekFrom::Start(position)).map_err(|e| JournalError::Io { offset: position, reason: e.to_string() })?;
+        Err(JournalError::SerialNotFound(serial))
+    }
+
     pub fn iter(&mut self) -> JournalReaderIter {
         JournalReaderIter {
             parser: self
         }
     }
 
+    pub fn get_version(&self) -> JournalVersions {
+        self.version
+    }
+
     pub fn get_begin_serial(&self) -> u32 {
         self.begin_serial
     }
@@ -90,8 +156,12 @@ impl JournalReader {
         self.end_offset
     }
 
+    pub fn get_index(&self) -> &[(u32, u32)] {
+        self.index.as_ref()
+    }
+
     pub fn get_index_size(&self) -> u32 {
-        self.index_size
+        self.index.len() as u32
     }
 
     pub fn get_source_serial(&self) -> u32 {
@@ -102,56 +172,81 @@ impl JournalReader {
         self.flags
     }
 
-    fn parse_record(&mut self) -> Option<Txn> {
-        let magic = true;
+    fn read_txn_header(&mut self, offset: u64) -> Result<(u32, Option<u32>, u32, u32), JournalError> {
+        let header_size = self.version.get_txn_header_size();
+        if offset+header_size > self.end_offset as u64 {
+            return Err(JournalError::TxnError { offset, reason: "truncated transaction header".to_string() });
+        }
 
-        let f = self.reader.stream_position().ok()?;
+        let mut buf = vec![0u8; header_size as usize];
+        self.reader.seek(SeekFrom::Start(offset))
+            .and_then(|_| self.reader.read_exact(&mut buf))
+            .map_err(|e| JournalError::Io { offset, reason: e.to_string() })?;
 
-        if self.reader.stream_position().ok()? >= self.end_offset as u64 {
-            return None;
+        let (size, rr_count, buf) = match self.version {
+            JournalVersions::V9 => (u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]), None, &buf[4..]),
+            JournalVersions::V92 => (u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]), Some(u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]])), &buf[8..])
+        };
+
+        if offset+header_size+size as u64 > self.end_offset as u64 {
+            return Err(JournalError::TxnError { offset, reason: format!("{} byte transaction runs past the end of the journal", size) });
         }
 
-        let (size, rr_count, serial_0, serial_1) = match magic {
-            true => {
-                let mut buf = vec![0u8; 16];
-                self.reader.read_exact(&mut buf).ok()?;
-                let size = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]);
-                let rr_count = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]);
-                let serial_0 = u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]);
-                let serial_1 = u32::from_be_bytes([buf[12], buf[13], buf[14], buf[15]]);
-                (size, Some(rr_count), serial_0, serial_1)
-            }
-            false => {
-                let mut buf = vec![0u8; 12];
-                self.reader.read_exact(&mut buf).ok()?;
-                let size = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]);
-                let serial_0 = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]);
-                let serial_1 = u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]);
-                (size, None, serial_0, serial_1)
-            }
-        };
+        Ok((size, rr_count, u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]), u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]])))
+    }
 
-        let mut remaining = size;
+    fn parse_record(&mut self) -> Result<Option<Txn>, JournalError> {
+        let offset = self.reader.stream_position().map_err(|e| JournalError::Io { offset: 0, reason: e.to_string() })?;
+
+        if offset >= self.end_offset as u64 {
+            return Ok(None);
+        }
+
+        let (size, rr_count, serial_0, serial_1) = self.read_txn_header(offset)?;
+
+        let mut buf = vec![0u8; size as usize];
+        self.reader.read_exact(&mut buf).map_err(|e| JournalError::Io { offset, reason: e.to_string() })?;
+
+        let base = offset+self.version.get_txn_header_size();
         let mut txn = Txn::new(serial_0, serial_1);
         let mut phase = TxnOpCodes::Delete;
         let mut seen_soa = 0;
+        let mut count = 0;
+        let mut off = 0;
+
+        while off < buf.len() {
+            let rr_offset = base+off as u64;
+            let error = |reason: String| JournalError::RecordError { offset: rr_offset, reason };
+
+            if off+4 > buf.len() {
+                return Err(error("truncated record length".to_string()));
+            }
 
-        while remaining > 0 {
-            let mut buf = vec![0u8; 4];
-            self.reader.read_exact(&mut buf).ok()?;
-            let rr_len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]);
-            remaining -= 4 + rr_len;
+            let rr_len = u32::from_be_bytes([buf[off], buf[off+1], buf[off+2], buf[off+3]]) as usize;
+            off += 4;
 
-            buf = vec![0u8; rr_len as usize];
-            self.reader.read_exact(&mut buf).ok()?;
+            if off+rr_len > buf.len() {
+                return Err(error(format!("{} byte record runs past the end of the transaction", rr_len)));
+            }
 
-            let mut off = 0;
+            let rr = &buf[off..off+rr_len];
+            off += rr_len;
 
-            let (name, length) = unpack_fqdn(&buf, off);
-            off += length;
+            let (name, length) = unpack_fqdn(rr, 0).map_err(|e| error(e.to_string()))?;
+            if length+10 > rr.len() {
+                return Err(error("truncated record header".to_string()));
+            }
 
-            let _type = RRTypes::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).ok()?;
+            let _type = RRTypes::try_from(u16::from_be_bytes([rr[length], rr[length+1]])).map_err(|e| error(e.to_string()))?;
+            let class = RRClasses::try_from(u16::from_be_bytes([rr[length+2], rr[length+3]]) & 0x7FFF).map_err(|e| error(e.to_string()))?;
+            let ttl = u32::from_be_bytes([rr[length+4], rr[length+5], rr[length+6], rr[length+7]]);
 
+            let data_length = u16::from_be_bytes([rr[length+8], rr[length+9]]) as usize;
+            if length+10+data_length != rr.len() {
+                return Err(error("record data does not match record length".to_string()));
+            }
+
+            //EVERY TRANSACTION IS OLD SOA, DELETES, NEW SOA, ADDS
             if _type == RRTypes::Soa {
                 seen_soa += 1;
 
@@ -159,19 +254,26 @@ impl JournalReader {
                     phase = TxnOpCodes::Add;
                 }
 
-                continue;
+            } else if count == 0 {
+                return Err(error("transaction does not start with an SOA".to_string()));
             }
 
-            let class = u16::from_be_bytes([buf[off+2], buf[off+3]]);
-            //let cache_flush = (class & 0x8000) != 0;
-            let class = RRClasses::try_from(class & 0x7FFF).ok()?;//.map_err(|e| MessageError::RecordError(e.to_string()))?;
-            let ttl = u32::from_be_bytes([buf[off+4], buf[off+5], buf[off+6], buf[off+7]]);
-
-            let record = <dyn RecordBase>::from_wire(_type, &class, &buf, off+8).ok()?;
+            let record = <dyn RecordBase>::from_wire(_type, &class, rr, length+8).map_err(|e| error(e.to_string()))?;
             txn.add_record(phase, &name, class, ttl, record);
+            count += 1;
         }
 
-        Some(txn)
+        if seen_soa < 2 {
+            return Err(JournalError::TxnError { offset, reason: "transaction is missing the new SOA".to_string() });
+        }
+
+        if let Some(rr_count) = rr_count {
+            if rr_count as usize != count {
+                return Err(JournalError::TxnError { offset, reason: format!("header claims {} records but {} were found", rr_count, count) });
+            }
+        }
+
+        Ok(Some(txn))
     }
 }
 
@@ -181,18 +283,96 @@ pub struct JournalReaderIter<'a> {
 
 impl<'a> Iterator for JournalReaderIter<'a> {
 
-    type Item = Txn;
+    type Item = Result<Txn, JournalError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.parser.parse_record()
+        if self.parser.failed {
+            return None;
+        }
+
+        match self.parser.parse_record() {
+            Ok(txn) => txn.map(Ok),
+            Err(e) => {
+                self.parser.failed = true;
+                Some(Err(e))
+            }
+        }
     }
 }
 
 #[test]
 fn test() {
-    let mut parser = JournalReader::open("/home/brad/Downloads/db.find9.net.jnl").unwrap();
-
-    for txn in parser.iter() {
-        println!("{:?}", txn);
+    use crate::journal::journal_writer::JournalWriter;
+    use crate::records::soa_record::SoaRecord;
+
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+    let path = std::env::temp_dir().join("rlibdns.journal_reader.test.jnl");
+
+    let mut writer = JournalWriter::create(&path).unwrap();
+    for serial in 1..=80 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        writer.write_txn(&txn).unwrap();
+    }
+    drop(writer);
+
+    let mut reader = JournalReader::open(&path).unwrap();
+    assert_eq!(reader.get_version(), JournalVersions::V92);
+    assert_eq!(reader.iter().filter_map(Result::ok).count(), 80);
+
+    reader.seek_serial(77).unwrap();
+    let txn = reader.iter().next().unwrap().unwrap();
+    assert_eq!((txn.get_serial_0(), txn.get_serial_1()), (77, 78));
+    assert_eq!(reader.seek_serial(90), Err(JournalError::SerialNotFound(90)));
+
+    let buf = std::fs::read(&path).unwrap();
+    let begin = reader.get_begin_offset() as usize;
+
+    //RR COUNT IN THE FIRST TRANSACTION HEADER NO LONGER MATCHES
+    let mut corrupt = buf.clone();
+    corrupt[begin+7] = 3;
+    std::fs::write(&path, &corrupt).unwrap();
+    let mut reader = JournalReader::open(&path).unwrap();
+    let mut iter = reader.iter();
+    assert!(matches!(iter.next(), Some(Err(JournalError::TxnError { offset, .. })) if offset == begin as u64));
+    assert!(iter.next().is_none());
+
+    //RR LENGTH LARGER THAN THE TRANSACTION
+    let mut corrupt = buf.clone();
+    corrupt[begin+16..begin+20].copy_from_slice(&u32::MAX.to_be_bytes());
+    std::fs::write(&path, &corrupt).unwrap();
+    let mut reader = JournalReader::open(&path).unwrap();
+    assert!(matches!(reader.iter().next(), Some(Err(JournalError::RecordError { offset, .. })) if offset == begin as u64+16));
+
+    let mut corrupt = buf.clone();
+    corrupt[10] = b'8';
+    std::fs::write(&path, &corrupt).unwrap();
+    assert!(matches!(JournalReader::open(&path), Err(JournalError::HeaderError(_))));
+
+    std::fs::write(&path, &buf[..buf.len()-1]).unwrap();
+    assert!(matches!(JournalReader::open(&path), Err(JournalError::HeaderError(_))));
+
+    //V9 TRANSACTION HEADERS HAVE NO RR COUNT
+    let mut old = buf[..begin].to_vec();
+    old[..16].copy_from_slice(b";BIND LOG V9\n\0\0\0");
+    let mut off = begin;
+    while off < buf.len() {
+        let size = u32::from_be_bytes([buf[off], buf[off+1], buf[off+2], buf[off+3]]) as usize;
+        old.extend_from_slice(&buf[off..off+4]);
+        old.extend_from_slice(&buf[off+8..off+16+size]);
+        off += 16+size;
     }
+    old.splice(28..32, (old.len() as u32).to_be_bytes());
+    old[64..begin].fill(0);
+    std::fs::write(&path, &old).unwrap();
+
+    let mut reader = JournalReader::open(&path).unwrap();
+    assert_eq!(reader.get_version(), JournalVersions::V9);
+    reader.seek_serial(40).unwrap();
+    let txns = reader.iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(txns.len(), 41);
+    assert_eq!(txns[0].get_records(TxnOpCodes::Add)[0].3.as_any().downcast_ref::<SoaRecord>().unwrap().get_serial(), 41);
+
+    std::fs::remove_file(&path).unwrap();
 }
@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use crate::journal::inter::txn_op_codes::TxnOpCodes;
+use crate::journal::journal_header::JournalHeader;
+use crate::journal::journal_reader::{JournalError, JournalVersions};
+use crate::journal::txn::Txn;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::utils::fqdn_utils::pack_fqdn;
+
+pub const JOURNAL_MAGIC: &[u8; 15] = b";BIND LOG V9.2\n";
+pub const JOURNAL_HEADER_SIZE: u32 = 64;
+pub const JOURNAL_INDEX_SIZE: u32 = 56;
+
+pub struct JournalWriter {
+    file: File,
+    begin_serial: u32,
+    begin_offset: u32,
+    end_serial: u32,
+    end_offset: u32,
+    index: Vec<(u32, u32)>,
+    source_serial: u32,
+    flags: u8
+}
+
+impl JournalWriter {
+
+    pub fn create<P: Into<PathBuf>>(file_path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(file_path.into())?;
+
+        let offset = JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8;
+
+        let mut writer = Self {
+            file,
+            begin_serial: 0,
+            begin_offset: offset,
+            end_serial: 0,
+            end_offset: offset,
+            index: vec![(0, 0); JOURNAL_INDEX_SIZE as usize],
+            source_serial: 0,
+            flags: 0
+        };
+
+        writer.file.set_len(offset as u64)?;
+        writer.write_header()?;
+
+        Ok(writer)
+    }
+
+    pub fn open<P: Into<PathBuf>>(file_path: P) -> Result<Self, JournalError> {
+        let io_error = |e: io::Error| JournalError::Io { offset: 0, reason: e.to_string() };
+
+        let mut file = OpenOptions::new().read(true).write(true).open(file_path.into()).map_err(io_error)?;
+        let file_len = file.metadata().map_err(io_error)?.len();
+
+        let header = JournalHeader::read(&mut file, file_len)?;
+        if header.version != JournalVersions::V92 {
+            return Err(JournalError::HeaderError("journal is not in the BIND LOG V9.2 format".to_string()));
+        }
+
+        Ok(Self {
+            file,
+            begin_serial: header.begin_serial,
+            begin_offset: header.begin_offset,
+            end_serial: header.end_serial,
+            end_offset: header.end_offset,
+            index: header.index,
+            source_serial: header.source_serial,
+            flags: header.flags
+        })
+    }
+
+    pub fn write_txn(&mut self, txn: &Txn) -> io::Result<()> {
+        self.append(txn.get_serial_0(), txn.get_serial_1(), &encode_txn(txn)?)
+    }
+
+    //THE TRANSACTION IS SYNCED BEFORE THE HEADER POINTS AT IT, A CRASH IN BETWEEN LEAVES THE OLD JOURNAL INTACT
+    fn append(&mut self, serial_0: u32, serial_1: u32, buf: &[u8]) -> io::Result<()> {
+        if !self.is_empty() && serial_0 != self.end_serial {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("transaction starts at serial {} but the journal ends at {}", serial_0, self.end_serial)));
+        }
+
+        if (self.end_offset as u64)+(buf.len() as u64) > u32::MAX as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "journal would exceed 4GB"));
+        }
+
+        self.file.seek(SeekFrom::Start(self.end_offset as u64))?;
+        self.file.write_all(buf)?;
+        self.file.set_len(self.end_offset as u64+buf.len() as u64)?;
+        self.file.sync_data()?;
+
+        if self.is_empty() {
+            self.begin_serial = serial_0;
+        }
+
+        self.add_index(serial_0, self.end_offset);
+
+        self.end_serial = serial_1;
+        self.end_offset += buf.len() as u32;
+
+        self.write_header()
+    }
+
+    //A FULL INDEX KEEPS ONLY ITS EVEN ENTRIES, THE FIRST INCLUDED, TO MAKE ROOM, THE SAME WAY BIND DOES
+    fn add_index(&mut self, serial: u32, offset: u32) {
+        if self.index.is_empty() {
+            return;
+        }
+
+        if self.index.iter().all(|(_, o)| *o != 0) {
+            let kept = self.index.iter().step_by(2).copied().collect::<Vec<_>>();
+            self.index.fill((0, 0));
+            self.index[..kept.len()].copy_from_slice(&kept);
+        }
+
+        if let Some(entry) = self.index.iter_mut().find(|(_, o)| *o == 0) {
+            *entry = (serial, offset);
+        }
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let mut buf = vec![0u8; JOURNAL_HEADER_SIZE as usize];
+
+        buf[0..15].copy_from_slice(JOURNAL_MAGIC);
+        buf.splice(16..20, self.begin_serial.to_be_bytes());
+        buf.splice(20..24, self.begin_offset.to_be_bytes());
+        buf.splice(24..28, self.end_serial.to_be_bytes());
+        buf.splice(28..32, self.end_offset.to_be_bytes());
+        buf.splice(32..36, (self.index.len() as u32).to_be_bytes());
+        buf.splice(36..40, self.source_serial.to_be_bytes());
+        buf[40] = self.flags;
+
+        for (serial, offset) in self.index.iter() {
+            buf.extend_from_slice(&serial.to_be_bytes());
+            buf.extend_from_slice(&offset.to_be_bytes());
+        }
+
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&buf)?;
+        self.file.sync_all()
+    }
+
+    //KEEPS ONLY THE TRANSACTIONS THAT END AFTER SERIAL
+    pub fn compact<P: AsRef<Path>>(file_path: P, serial: u32) -> io::Result<()> {
+        let mut writer = JournalWriter::open(file_path.as_ref())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let txns = read_raw_txns(&mut writer)?;
+        let start = txns.iter()
+            .position(|(_, serial_1, _)| serial_1.wrapping_sub(serial) as i32 > 0)
+            .unwrap_or(txns.len());
+
+        let (source_serial, flags) = (writer.source_serial, writer.flags);
+        drop(writer);
+
+        rewrite(file_path.as_ref(), source_serial, flags, &txns[start..])
+    }
+
+    //DROPS THE OLDEST TRANSACTIONS UNTIL THE WHOLE FILE FITS IN MAX_SIZE BYTES
+    pub fn compact_to_size<P: AsRef<Path>>(file_path: P, max_size: u64) -> io::Result<()> {
+        let mut writer = JournalWriter::open(file_path.as_ref())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let txns = read_raw_txns(&mut writer)?;
+
+        let mut size = (JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8) as u64+txns.iter().map(|(_, _, buf)| buf.len() as u64).sum::<u64>();
+        let mut start = 0;
+
+        while size > max_size && start < txns.len() {
+            size -= txns[start].2.len() as u64;
+            start += 1;
+        }
+
+        let (source_serial, flags) = (writer.source_serial, writer.flags);
+        drop(writer);
+
+        rewrite(file_path.as_ref(), source_serial, flags, &txns[start..])
+    }
+
+    //SWAPS IN AN EMPTY JOURNAL IN ONE RENAME, A FAILURE LEAVES THE OLD ONE AS IT WAS
+    pub fn reset<P: AsRef<Path>>(file_path: P) -> io::Result<()> {
+        rewrite(file_path.as_ref(), 0, 0, &[])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.begin_offset == self.end_offset
+    }
+
+    pub fn get_begin_serial(&self) -> u32 {
+        self.begin_serial
+    }
+
+    pub fn get_begin_offset(&self) -> u32 {
+        self.begin_offset
+    }
+
+    pub fn get_end_serial(&self) -> u32 {
+        self.end_serial
+    }
+
+    pub fn get_end_offset(&self) -> u32 {
+        self.end_offset
+    }
+
+    pub fn get_index_size(&self) -> u32 {
+        self.index.len() as u32
+    }
+
+    pub fn get_index(&self) -> &[(u32, u32)] {
+        self.index.as_ref()
+    }
+
+    pub fn get_source_serial(&self) -> u32 {
+        self.source_serial
+    }
+
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
+}
+
+fn read_raw_txns(writer: &mut JournalWriter) -> io::Result<Vec<(u32, u32, Vec<u8>)>> {
+    let mut txns = Vec::new();
+    let mut offset = writer.begin_offset;
+
+    writer.file.seek(SeekFrom::Start(offset as u64))?;
+
+    while offset < writer.end_offset {
+        let mut buf = vec![0u8; 16];
+        writer.file.read_exact(&mut buf)?;
+
+        let size = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        if offset as u64+16+size as u64 > writer.end_offset as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("transaction at offset {} runs past the end of the journal", offset)));
+        }
+
+        let serial_0 = u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]);
+        let serial_1 = u32::from_be_bytes([buf[12], buf[13], buf[14], buf[15]]);
+
+        buf.resize(16+size as usize, 0);
+        writer.file.read_exact(&mut buf[16..])?;
+
+        offset += 16+size;
+        txns.push((serial_0, serial_1, buf));
+    }
+
+    Ok(txns)
+}
+
+//WRITE A FRESH JOURNAL NEXT TO THE OLD ONE AND RENAME IT INTO PLACE, THE SOURCE SERIAL AND FLAGS CARRY OVER
+fn rewrite(file_path: &Path, source_serial: u32, flags: u8, txns: &[(u32, u32, Vec<u8>)]) -> io::Result<()> {
+    let mut temp_path = file_path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut writer = JournalWriter::create(&temp_path)?;
+    writer.source_serial = source_serial;
+    writer.flags = flags;
+
+    if let Err(e) = writer.write_header() {
+        drop(writer);
+        std::fs::remove_file(&temp_path)?;
+        return Err(e);
+    }
+
+    for (serial_0, serial_1, buf) in txns {
+        if let Err(e) = writer.append(*serial_0, *serial_1, buf) {
+            drop(writer);
+            std::fs::remove_file(&temp_path)?;
+            return Err(e);
+        }
+    }
+    drop(writer);
+
+    std::fs::rename(&temp_path, file_path)?;
+
+    if let Some(parent) = file_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        File::open(parent)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+//TRANSACTION HEADER IS SIZE, RR COUNT, SERIAL 0, SERIAL 1 - EACH RR IS PREFIXED WITH ITS OWN SIZE
+pub(crate) fn encode_txn(txn: &Txn) -> io::Result<Vec<u8>> {
+    let deletes = txn.get_records(TxnOpCodes::Delete);
+    let adds = txn.get_records(TxnOpCodes::Add);
+
+    if deletes.first().map(|(_, _, _, r)| r.get_type()) != Some(RRTypes::Soa) ||
+            adds.first().map(|(_, _, _, r)| r.get_type()) != Some(RRTypes::Soa) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "transaction must delete the old SOA and add the new SOA first"));
+    }
+
+    let mut buf = vec![0u8; 16];
+
+    for (name, class, ttl, record) in deletes.iter().chain(adds.iter()) {
+        let mut rr = pack_fqdn(name, &mut HashMap::new(), 0, false);
+        rr.extend_from_slice(&record.get_type().get_code().to_be_bytes());
+        rr.extend_from_slice(&class.get_code().to_be_bytes());
+        rr.extend_from_slice(&ttl.to_be_bytes());
+
+        //NOTHING PAST 0x3FFF IS EVER RECORDED AS A POINTER TARGET, SO THE RDATA IS WRITTEN UNCOMPRESSED
+        rr.extend_from_slice(&record.to_bytes(&mut HashMap::new(), 0x4000)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?);
+
+        buf.extend_from_slice(&(rr.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&rr);
+    }
+
+    buf.splice(0..4, ((buf.len()-16) as u32).to_be_bytes());
+    buf.splice(4..8, ((deletes.len()+adds.len()) as u32).to_be_bytes());
+    buf.splice(8..12, txn.get_serial_0().to_be_bytes());
+    buf.splice(12..16, txn.get_serial_1().to_be_bytes());
+
+    Ok(buf)
+}
+
+#[test]
+fn test() {
+    use crate::journal::journal_reader::JournalReader;
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::inter::record_base::RecordBase;
+    use crate::records::soa_record::SoaRecord;
+
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+
+    let mut txns = Vec::new();
+    for serial in 1..3 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, InARecord::new([10, 0, 0, serial as u8].into()).upcast());
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, InARecord::new([10, 0, 0, serial as u8+1].into()).upcast());
+        txns.push(txn);
+    }
+
+    let path = std::env::temp_dir().join("rlibdns.journal_writer.test.jnl");
+
+    let mut writer = JournalWriter::create(&path).unwrap();
+    assert!(writer.is_empty());
+    writer.write_txn(&txns[0]).unwrap();
+    drop(writer);
+
+    let mut writer = JournalWriter::open(&path).unwrap();
+    assert!(writer.write_txn(&txns[0]).is_err());
+    writer.write_txn(&txns[1]).unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), writer.get_end_offset() as u64);
+
+    let mut reader = JournalReader::open(&path).unwrap();
+    assert_eq!((reader.get_begin_serial(), reader.get_end_serial()), (1, 3));
+    assert_eq!(reader.get_begin_offset(), JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8);
+
+    let read = reader.iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+    //AN INDEX SIZE THAT RUNS INTO THE TRANSACTIONS OR PAST THE FILE IS REJECTED BEFORE ANYTHING IS READ
+    let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.seek(SeekFrom::Start(32)).unwrap();
+    file.write_all(&u32::MAX.to_be_bytes()).unwrap();
+    drop(file);
+    assert!(matches!(JournalWriter::open(&path), Err(JournalError::HeaderError(_))));
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.len(), 2);
+    for (txn, read) in txns.iter().zip(read.iter()) {
+        assert_eq!((read.get_serial_0(), read.get_serial_1()), (txn.get_serial_0(), txn.get_serial_1()));
+        assert_eq!(encode_txn(read).unwrap(), encode_txn(txn).unwrap());
+    }
+}
+
+#[test]
+fn compact() {
+    use crate::journal::journal_reader::JournalReader;
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::inter::record_base::RecordBase;
+    use crate::records::soa_record::SoaRecord;
+
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+    let path = std::env::temp_dir().join("rlibdns.journal_writer.compact.jnl");
+
+    let mut writer = JournalWriter::create(&path).unwrap();
+    for serial in 1..=60 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, InARecord::new([10, 0, 0, serial as u8].into()).upcast());
+        writer.write_txn(&txn).unwrap();
+    }
+
+    //FULL INDEX HALVED ONCE, EVERY ENTRY STILL POINTS AT A TRANSACTION HEADER
+    let index = writer.get_index().iter().filter(|(_, o)| *o != 0).copied().collect::<Vec<_>>();
+    assert_eq!(index.len(), 32);
+    assert_eq!(index[0], (1, JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8));
+    assert!(index.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+    let size = writer.get_end_offset()-writer.get_begin_offset();
+    drop(writer);
+
+    //A SOURCE SERIAL AND FLAGS SET BY ANOTHER WRITER SURVIVE A REWRITE
+    let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.seek(SeekFrom::Start(36)).unwrap();
+    file.write_all(&[0x0, 0x0, 0x0, 0x28, 0x1]).unwrap();
+    drop(file);
+
+    JournalWriter::compact(&path, 40).unwrap();
+    let writer = JournalWriter::open(&path).unwrap();
+    assert_eq!((writer.get_source_serial(), writer.get_flags()), (40, 1));
+    let mut reader = JournalReader::open(&path).unwrap();
+    assert_eq!((reader.get_begin_serial(), reader.get_end_serial()), (40, 61));
+    assert_eq!(reader.iter().count(), 21);
+
+    JournalWriter::compact_to_size(&path, (JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8+size/60*5) as u64).unwrap();
+    let writer = JournalWriter::open(&path).unwrap();
+    assert_eq!((writer.get_begin_serial(), writer.get_end_serial()), (56, 61));
+    assert!(std::fs::metadata(&path).unwrap().len() <= (JOURNAL_HEADER_SIZE+JOURNAL_INDEX_SIZE*8+size/60*5) as u64);
+    assert_eq!(writer.get_index().iter().filter(|(_, o)| *o != 0).count(), 5);
+    assert_eq!((writer.get_source_serial(), writer.get_flags()), (40, 1));
+
+    JournalWriter::compact(&path, 61).unwrap();
+    assert!(JournalWriter::open(&path).unwrap().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,4 +1,6 @@
 pub mod inter;
 pub mod journal;
+pub mod journal_header;
 pub mod journal_reader;
+pub mod journal_writer;
 pub mod txn;
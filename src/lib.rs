@@ -3,10 +3,13 @@ pub mod records;
 pub mod utils;
 pub mod zone;
 pub mod journal;
+pub mod tsig;
+pub mod dnssec;
 
 #[cfg(test)]
 mod tests {
-    use crate::messages::message::Message;
+    use crate::messages::inter::response_codes::ResponseCodes;
+    use crate::messages::message::{Message, MessageError};
     use crate::zone::zone_store::ZoneStore;
 
 
@@ -86,6 +89,43 @@ mod tests {
         //assert_eq!(x, message.to_bytes(512));
     }
 
+    #[test]
+    fn malformed() {
+        let x = vec![ 0xa7, 0xa2, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0x01, 0x00, 0x01, 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x23, 0x00, 0x04, 0x8e, 0xfa, 0x45, 0xee, 0x00, 0x00, 0x29, 0x04, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00 ];
+        assert!(Message::from_bytes(&x).is_ok());
+
+        for i in 0..x.len() {
+            assert!(Message::from_bytes(&x[..i]).is_err());
+        }
+
+        let mut looped = x.clone();
+        looped[28..30].copy_from_slice(&[0xc0, 0x1c]);
+        assert!(matches!(Message::from_bytes(&looped), Err(MessageError::RecordError { section: 0, index: 0, offset: 28, .. })));
+    }
+
+    #[test]
+    fn edns() {
+        let x = vec![ 0xa7, 0xa2, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0x01, 0x00, 0x01, 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x23, 0x00, 0x04, 0x8e, 0xfa, 0x45, 0xee, 0x00, 0x00, 0x29, 0x04, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00 ];
+
+        let mut message = Message::from_bytes(&x).unwrap();
+        assert_eq!(message.total_section(2), 0);
+        assert_eq!(message.get_opt().unwrap().get_payload_size(), 1232);
+        assert!(!message.get_opt().unwrap().is_dnssec_ok());
+        assert_eq!(x, message.to_bytes(512));
+        assert_eq!(x, message.wire_chunks(512).next().unwrap());
+
+        message.set_response_code(ResponseCodes::BadVers);
+        let buf = message.to_bytes(512);
+        assert_eq!(buf[3] & 0x0F, 0);
+        assert_eq!(buf[x.len()-6], 1);
+        assert_eq!(Message::from_bytes(&buf).unwrap().get_response_code(), ResponseCodes::BadVers);
+
+        let mut duplicated = x.clone();
+        duplicated[11] = 2;
+        duplicated.extend_from_slice(&x[x.len()-11..]);
+        assert!(matches!(Message::from_bytes(&duplicated), Err(MessageError::RecordError { section: 2, index: 1, .. })));
+    }
+
     #[test]
     fn parsing() {
         let mut store = ZoneStore::new();
@@ -11,14 +11,25 @@ pub enum ResponseCodes {
     NotImp,
     Refused,
     YxDomain,
-    XrrSet,
+    YxRRSet,
+    NxRRSet,
     NotAuth,
-    NotZone
+    NotZone,
+    DsoTypeNi,
+    BadVers,
+    BadSig,
+    BadKey,
+    BadTime,
+    BadMode,
+    BadName,
+    BadAlg,
+    BadTrunc,
+    BadCookie
 }
 
 impl ResponseCodes {
 
-    pub fn get_code(&self) -> u8 {
+    pub fn get_code(&self) -> u16 {
         match self {
             Self::NoError => 0,
             Self::FormErr => 1,
@@ -27,15 +38,27 @@ impl ResponseCodes {
             Self::NotImp => 4,
             Self::Refused => 5,
             Self::YxDomain => 6,
-            Self::XrrSet => 7,
-            Self::NotAuth => 8,
-            Self::NotZone => 9
+            Self::YxRRSet => 7,
+            Self::NxRRSet => 8,
+            Self::NotAuth => 9,
+            Self::NotZone => 10,
+            Self::DsoTypeNi => 11,
+            Self::BadVers => 16,
+            //RFC 8945 S3 - SHARES 16 WITH BADVERS, ONLY EVER CARRIED IN THE TSIG ERROR FIELD
+            Self::BadSig => 16,
+            Self::BadKey => 17,
+            Self::BadTime => 18,
+            Self::BadMode => 19,
+            Self::BadName => 20,
+            Self::BadAlg => 21,
+            Self::BadTrunc => 22,
+            Self::BadCookie => 23
         }
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub struct ResponseCodeParseError(pub u8);
+pub struct ResponseCodeParseError(pub u16);
 
 impl fmt::Display for ResponseCodeParseError {
 
@@ -44,11 +67,11 @@ impl fmt::Display for ResponseCodeParseError {
     }
 }
 
-impl TryFrom<u8> for ResponseCodes {
+impl TryFrom<u16> for ResponseCodes {
 
     type Error = ResponseCodeParseError;
 
-    fn try_from(v: u8) -> Result<Self, Self::Error> {
+    fn try_from(v: u16) -> Result<Self, Self::Error> {
         Ok(match v {
             0 => Self::NoError,
             1 => Self::FormErr,
@@ -57,9 +80,19 @@ impl TryFrom<u8> for ResponseCodes {
             4 => Self::NotImp,
             5 => Self::Refused,
             6 => Self::YxDomain,
-            7 => Self::XrrSet,
-            8 => Self::NotAuth,
-            9 => Self::NotZone,
+            7 => Self::YxRRSet,
+            8 => Self::NxRRSet,
+            9 => Self::NotAuth,
+            10 => Self::NotZone,
+            11 => Self::DsoTypeNi,
+            16 => Self::BadVers,
+            17 => Self::BadKey,
+            18 => Self::BadTime,
+            19 => Self::BadMode,
+            20 => Self::BadName,
+            21 => Self::BadAlg,
+            22 => Self::BadTrunc,
+            23 => Self::BadCookie,
             _  => return Err(ResponseCodeParseError(v))
         })
     }
@@ -76,9 +109,20 @@ impl fmt::Display for ResponseCodes {
             Self::NotImp => "NOTIMP",
             Self::Refused => "REFUSED",
             Self::YxDomain => "YXDOMAIN",
-            Self::XrrSet => "XRRSET",
+            Self::YxRRSet => "YXRRSET",
+            Self::NxRRSet => "NXRRSET",
             Self::NotAuth => "NOTAUTH",
-            Self::NotZone => "NOTZONE"
+            Self::NotZone => "NOTZONE",
+            Self::DsoTypeNi => "DSOTYPENI",
+            Self::BadVers => "BADVERS",
+            Self::BadSig => "BADSIG",
+            Self::BadKey => "BADKEY",
+            Self::BadTime => "BADTIME",
+            Self::BadMode => "BADMODE",
+            Self::BadName => "BADNAME",
+            Self::BadAlg => "BADALG",
+            Self::BadTrunc => "BADTRUNC",
+            Self::BadCookie => "BADCOOKIE"
         })
     }
 }
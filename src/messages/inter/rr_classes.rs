@@ -9,7 +9,8 @@ pub enum RRClasses {
     Ch,
     Hs,
     None,
-    Any
+    Any,
+    Unknown(u16)
 }
 
 impl RRClasses {
@@ -20,7 +21,8 @@ impl RRClasses {
             Self::Ch => 3,
             Self::Hs => 4,
             Self::None => 254,
-            Self::Any => 255
+            Self::Any => 255,
+            Self::Unknown(code) => *code
         }
     }
 }
@@ -52,7 +54,7 @@ impl TryFrom<u16> for RRClasses {
             4 => Self::Hs,
             254 => Self::None,
             255 => Self::Any,
-            _  => return Err(RRClassParseError::UnknownCode(v))
+            _  => Self::Unknown(v)
         })
     }
 }
@@ -68,7 +70,12 @@ impl FromStr for RRClasses {
             "HS" => Self::Hs,
             "NONE" => Self::None,
             "ANY" => Self::Any,
-            _  => return Err(RRClassParseError::UnknownName(s.to_string()))
+            _  => {
+                let code = s.strip_prefix("CLASS")
+                    .and_then(|code| code.parse::<u16>().ok())
+                    .ok_or_else(|| RRClassParseError::UnknownName(s.to_string()))?;
+                return Self::try_from(code);
+            }
         })
     }
 }
@@ -81,7 +88,8 @@ impl fmt::Display for RRClasses {
             Self::Ch => "CH",
             Self::Hs => "HS",
             Self::None => "NONE",
-            Self::Any => "ANY"
+            Self::Any => "ANY",
+            Self::Unknown(code) => return write!(f, "CLASS{}", code)
         })
     }
 }
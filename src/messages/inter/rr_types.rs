@@ -19,12 +19,20 @@ pub enum RRTypes {
     Loc,
     Srv,
     Naptr,
+    DName,
     Opt,
+    Ds,
     SshFp,
     RRSig,
     Nsec,
     DnsKey,
+    Nsec3,
+    Nsec3Param,
+    Tlsa,
     Smimea,
+    Cds,
+    CdnsKey,
+    OpenPgpKey,
     Svcb,
     Https,
     Spf,
@@ -34,7 +42,8 @@ pub enum RRTypes {
     Ixfr,
     Axfr,
     Uri,
-    Caa
+    Caa,
+    Unknown(u16)
 }
 
 impl RRTypes {
@@ -53,12 +62,20 @@ impl RRTypes {
             Self::Loc => 29,
             Self::Srv => 33,
             Self::Naptr => 35,
+            Self::DName => 39,
             Self::Opt => 41,
+            Self::Ds => 43,
             Self::SshFp => 44,
             Self::RRSig => 46,
             Self::Nsec => 47,
             Self::DnsKey => 48,
+            Self::Nsec3 => 50,
+            Self::Nsec3Param => 51,
+            Self::Tlsa => 52,
             Self::Smimea => 53,
+            Self::Cds => 59,
+            Self::CdnsKey => 60,
+            Self::OpenPgpKey => 61,
             Self::Svcb => 64,
             Self::Https => 65,
             Self::Spf => 99,
@@ -68,7 +85,8 @@ impl RRTypes {
             Self::Axfr => 252,
             Self::Any => 255,
             Self::Uri => 256,
-            Self::Caa => 257
+            Self::Caa => 257,
+            Self::Unknown(code) => *code
         }
     }
 }
@@ -107,12 +125,20 @@ impl TryFrom<u16> for RRTypes {
             29 => Self::Loc,
             33 => Self::Srv,
             35 => Self::Naptr,
+            39 => Self::DName,
             41 => Self::Opt,
+            43 => Self::Ds,
             44 => Self::SshFp,
             46 => Self::RRSig,
             47 => Self::Nsec,
             48 => Self::DnsKey,
+            50 => Self::Nsec3,
+            51 => Self::Nsec3Param,
+            52 => Self::Tlsa,
             53 => Self::Smimea,
+            59 => Self::Cds,
+            60 => Self::CdnsKey,
+            61 => Self::OpenPgpKey,
             64 => Self::Svcb,
             65 => Self::Https,
             99 => Self::Spf,
@@ -123,7 +149,7 @@ impl TryFrom<u16> for RRTypes {
             255 => Self::Any,
             256 => Self::Uri,
             257 => Self::Caa,
-            _  => return Err(RRTypeParseError::UnknownCode(v))
+            _  => Self::Unknown(v)
         })
     }
 }
@@ -146,12 +172,20 @@ impl FromStr for RRTypes {
             "LOC" => Self::Loc,
             "SRV" => Self::Srv,
             "NAPTR" => Self::Naptr,
+            "DNAME" => Self::DName,
             "OPT" => Self::Opt,
+            "DS" => Self::Ds,
             "SSHFP" => Self::SshFp,
             "RRSIG" => Self::RRSig,
             "NSEC" => Self::Nsec,
             "DNSKEY" => Self::DnsKey,
+            "NSEC3" => Self::Nsec3,
+            "NSEC3PARAM" => Self::Nsec3Param,
+            "TLSA" => Self::Tlsa,
             "SMIMEA" => Self::Smimea,
+            "CDS" => Self::Cds,
+            "CDNSKEY" => Self::CdnsKey,
+            "OPENPGPKEY" => Self::OpenPgpKey,
             "SVCB" => Self::Svcb,
             "HTTPS" => Self::Https,
             "SPF" => Self::Spf,
@@ -162,7 +196,12 @@ impl FromStr for RRTypes {
             "ANY" => Self::Any,
             "URI" => Self::Uri,
             "CAA" => Self::Caa,
-            _  => return Err(RRTypeParseError::UnknownName(s.to_string()))
+            _  => {
+                let code = s.strip_prefix("TYPE")
+                    .and_then(|code| code.parse::<u16>().ok())
+                    .ok_or_else(|| RRTypeParseError::UnknownName(s.to_string()))?;
+                return Self::try_from(code);
+            }
         })
     }
 }
@@ -183,12 +222,20 @@ impl fmt::Display for RRTypes {
             Self::Loc => "LOC",
             Self::Srv => "SRV",
             Self::Naptr => "NAPTR",
+            Self::DName => "DNAME",
             Self::Opt => "OPT",
+            Self::Ds => "DS",
             Self::SshFp => "SSHFP",
             Self::RRSig => "RRSIG",
             Self::Nsec => "NSEC",
             Self::DnsKey => "DNSKEY",
+            Self::Nsec3 => "NSEC3",
+            Self::Nsec3Param => "NSEC3PARAM",
+            Self::Tlsa => "TLSA",
             Self::Smimea => "SMIMEA",
+            Self::Cds => "CDS",
+            Self::CdnsKey => "CDNSKEY",
+            Self::OpenPgpKey => "OPENPGPKEY",
             Self::Svcb => "SVCB",
             Self::Https => "HTTPS",
             Self::Spf => "SPF",
@@ -198,7 +245,8 @@ impl fmt::Display for RRTypes {
             Self::Axfr => "AXFR",
             Self::Any => "ANY",
             Self::Uri => "URI",
-            Self::Caa => "CAA"
+            Self::Caa => "CAA",
+            Self::Unknown(code) => return write!(f, "TYPE{}", code)
         })
     }
 }
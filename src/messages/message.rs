@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
 use std::net::SocketAddr;
+use std::str::FromStr;
 use crate::messages::inter::op_codes::OpCodes;
 use crate::messages::inter::response_codes::ResponseCodes;
 use crate::messages::inter::rr_classes::RRClasses;
@@ -9,7 +10,13 @@ use crate::records::inter::record_base::RecordBase;
 use crate::messages::rr_query::RRQuery;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::opt_record::OptRecord;
+use crate::records::tsig_record::TSigRecord;
+use crate::records::unknown_record::UnknownRecord;
+use crate::tsig::inter::tsig_algorithms::TsigAlgorithms;
+use crate::tsig::tsig_key::TsigKey;
+use crate::tsig::tsig_keyring::TsigKeyring;
 use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
+use crate::utils::hmac;
 /*
                                1  1  1  1  1  1
  0  1  2  3  4  5  6  7  8  9  0  1  2  3  4  5
@@ -29,6 +36,7 @@ use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
 */
 
 pub const DNS_HEADER_LEN: usize = 12;
+pub const TSIG_FUDGE: u16 = 300;
 
 pub type MessageRecord = (String, RRClasses, u32, Box<dyn RecordBase>);
 
@@ -48,7 +56,9 @@ pub struct Message {
     destination: Option<SocketAddr>,
     queries: Vec<RRQuery>,
     sections: [Vec<MessageRecord>; 3],
-    option: Option<OpCodes>
+    opt: Option<OptRecord>,
+    tsig: Option<(String, TSigRecord)>,
+    tsig_wire: Option<Vec<u8>>
 }
 
 impl Default for Message {
@@ -69,7 +79,9 @@ impl Default for Message {
             destination: None,
             queries: Vec::new(),
             sections: Default::default(),
-            option: None
+            opt: None,
+            tsig: None,
+            tsig_wire: None
         }
     }
 }
@@ -77,7 +89,32 @@ impl Default for Message {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum MessageError {
     HeaderError(String),
-    RecordError(String)
+    QueryError {
+        index: usize,
+        offset: usize,
+        reason: String
+    },
+    RecordError {
+        section: usize,
+        index: usize,
+        offset: usize,
+        reason: String
+    }
+}
+
+impl fmt::Display for MessageError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HeaderError(reason) => write!(f, "header error: {}", reason),
+            Self::QueryError { index, offset, reason } => write!(f, "query {} at offset {}: {}", index, offset, reason),
+            Self::RecordError { section, index, offset, reason } => write!(f, "{} record {} at offset {}: {}", match section {
+                0 => "answer",
+                1 => "authority",
+                _ => "additional"
+            }, index, offset, reason)
+        }
+    }
 }
 
 impl Message {
@@ -90,6 +127,10 @@ impl Message {
     }
 
     pub fn from_bytes(buf: &[u8]) -> Result<Self, MessageError> {
+        if buf.len() < DNS_HEADER_LEN {
+            return Err(MessageError::HeaderError(format!("message is {} bytes, header requires {}", buf.len(), DNS_HEADER_LEN)));
+        }
+
         let id = u16::from_be_bytes([buf[0], buf[1]]);
 
         let flags = u16::from_be_bytes([buf[2], buf[3]]);
@@ -103,23 +144,47 @@ impl Message {
         //let z = (flags & 0x0040) != 0;
         let authenticated_data = (flags & 0x0020) != 0;
         let checking_disabled = (flags & 0x0010) != 0;
-        let response_code = ResponseCodes::try_from((flags & 0x000F) as u8).map_err(|e| MessageError::HeaderError(e.to_string()))?;
 
         let qd_count = u16::from_be_bytes([buf[4], buf[5]]);
 
         let mut queries = Vec::new();
         let mut off = DNS_HEADER_LEN;
 
-        for _ in 0..qd_count {
-            queries.push(RRQuery::from_bytes(buf, &mut off)?);
+        for i in 0..qd_count as usize {
+            let offset = off;
+            queries.push(RRQuery::from_bytes(buf, &mut off).map_err(|e| MessageError::QueryError {
+                index: i,
+                offset,
+                reason: e.to_string()
+            })?);
         }
 
+        let mut opt = None;
+        let mut tsig = None;
+
         let sections = [
-            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[6], buf[7]]))?,
-            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[8], buf[9]]))?,
-            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[10], buf[11]]))?
+            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[6], buf[7]]), 0, &mut opt, &mut tsig)?,
+            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[8], buf[9]]), 1, &mut opt, &mut tsig)?,
+            records_from_bytes(buf, &mut off, u16::from_be_bytes([buf[10], buf[11]]), 2, &mut opt, &mut tsig)?
         ];
 
+        //RFC 8945 S4.3.3 - KEEP THE BYTES THE MAC WAS COMPUTED OVER, RE-ENCODING MAY COMPRESS DIFFERENTLY,
+        //AN UNSIGNED MESSAGE IN A STREAM GOES INTO THE NEXT MAC WHOLE
+        let tsig_wire = match tsig.as_ref() {
+            Some((_, _, offset)) => {
+                let mut wire = buf[..*offset].to_vec();
+                wire.splice(10..12, (u16::from_be_bytes([buf[10], buf[11]])-1).to_be_bytes());
+                wire
+            }
+            None => buf[..off].to_vec()
+        };
+
+        let response_code = match opt {
+            Some(ref opt) => ((opt.get_ext_rcode() as u16) << 4) | (flags & 0x000F),
+            None => flags & 0x000F
+        };
+        let response_code = ResponseCodes::try_from(response_code).map_err(|e| MessageError::HeaderError(e.to_string()))?;
+
         Ok(Self {
             id,
             op_code,
@@ -135,18 +200,29 @@ impl Message {
             destination: None,
             queries,
             sections,
-            option: None
+            opt,
+            tsig: tsig.map(|(name, record, _)| (name, record)),
+            tsig_wire: Some(tsig_wire)
         })
     }
 
     //TRUNCATE WILL BE HANDLED BY ITERATOR...
     pub fn to_bytes(&self, max_payload_len: usize) -> Vec<u8> {
+        self.encode(max_payload_len, true)
+    }
+
+    fn encode(&self, max_payload_len: usize, with_tsig: bool) -> Vec<u8> {
         let mut buf = vec![0u8; DNS_HEADER_LEN];
 
         buf.splice(0..2, self.id.to_be_bytes());
 
         buf.splice(4..6, (self.queries.len() as u16).to_be_bytes());
 
+        let opt = self.opt_to_bytes();
+        let tsig = self.tsig_to_bytes().filter(|_| with_tsig);
+        let max_payload_len = max_payload_len.saturating_sub(opt.as_ref().map(|o| o.len()).unwrap_or(0))
+            .saturating_sub(tsig.as_ref().map(|t| t.len()).unwrap_or(0));
+
         let mut compression_data = HashMap::new();
         let mut off = DNS_HEADER_LEN;
         let mut truncated = false;
@@ -166,6 +242,7 @@ impl Message {
             for (i, section) in self.sections.iter().enumerate() {
                 let (records, count, t) = records_to_bytes(off, section, &mut compression_data, max_payload_len);
                 buf.extend_from_slice(&records);
+                off += records.len();
                 buf.splice(i*2+6..i*2+8, count.to_be_bytes());
 
                 if t {
@@ -175,6 +252,19 @@ impl Message {
             }
         }
 
+        if let Some(opt) = opt {
+            buf.extend_from_slice(&opt);
+            let count = u16::from_be_bytes([buf[10], buf[11]])+1;
+            buf.splice(10..12, count.to_be_bytes());
+        }
+
+        //RFC 8945 S4.2 - TSIG IS ALWAYS THE LAST RECORD
+        if let Some(tsig) = tsig {
+            buf.extend_from_slice(&tsig);
+            let count = u16::from_be_bytes([buf[10], buf[11]])+1;
+            buf.splice(10..12, count.to_be_bytes());
+        }
+
         let flags = (if self.qr { 0x8000 } else { 0 }) |  // QR bit
             ((self.op_code.get_code() as u16 & 0x0F) << 11) |  // Opcode
             (if self.authoritative { 0x0400 } else { 0 }) |  // AA bit
@@ -184,7 +274,7 @@ impl Message {
             //(if self.z { 0x0040 } else { 0 }) |  // Z bit (always 0)
             (if self.authenticated_data { 0x0020 } else { 0 }) |  // AD bit
             (if self.checking_disabled { 0x0010 } else { 0 }) |  // CD bit
-            (self.response_code.get_code() as u16 & 0x000F);  // RCODE
+            (self.response_code.get_code() & 0x000F);  // RCODE
 
         buf.splice(2..4, flags.to_be_bytes());
 
@@ -201,6 +291,7 @@ impl Message {
 
     pub fn set_id(&mut self, id: u16) {
         self.id = id;
+        self.tsig_wire = None;
     }
 
     pub fn get_id(&self) -> u16 {
@@ -209,6 +300,7 @@ impl Message {
 
     pub fn set_qr(&mut self, qr: bool) {
         self.qr = qr;
+        self.tsig_wire = None;
     }
 
     pub fn is_qr(&self) -> bool {
@@ -217,6 +309,7 @@ impl Message {
 
     pub fn set_op_code(&mut self, op_code: OpCodes) {
         self.op_code = op_code;
+        self.tsig_wire = None;
     }
 
     pub fn get_op_code(&self) -> OpCodes {
@@ -241,6 +334,7 @@ impl Message {
 
     pub fn set_authoritative(&mut self, authoritative: bool) {
         self.authoritative = authoritative;
+        self.tsig_wire = None;
     }
 
     pub fn is_authoritative(&self) -> bool {
@@ -249,6 +343,7 @@ impl Message {
 
     pub fn set_truncated(&mut self, truncated: bool) {
         self.truncated = truncated;
+        self.tsig_wire = None;
     }
 
     pub fn is_truncated(&self) -> bool {
@@ -257,6 +352,7 @@ impl Message {
 
     pub fn set_recursion_desired(&mut self, recursion_desired: bool) {
         self.recursion_desired = recursion_desired;
+        self.tsig_wire = None;
     }
 
     pub fn is_recursion_desired(&self) -> bool {
@@ -265,6 +361,7 @@ impl Message {
 
     pub fn set_recursion_available(&mut self, recursion_available: bool) {
         self.recursion_available = recursion_available;
+        self.tsig_wire = None;
     }
 
     pub fn is_recursion_available(&self) -> bool {
@@ -273,6 +370,7 @@ impl Message {
 
     pub fn set_response_code(&mut self, response_code: ResponseCodes) {
         self.response_code = response_code;
+        self.tsig_wire = None;
     }
 
     pub fn get_response_code(&self) -> ResponseCodes {
@@ -285,6 +383,7 @@ impl Message {
 
     pub fn add_query(&mut self, query: RRQuery) {
         self.queries.push(query);
+        self.tsig_wire = None;
     }
 
     pub fn get_queries(&self) -> &Vec<RRQuery> {
@@ -292,6 +391,7 @@ impl Message {
     }
 
     pub fn get_queries_mut(&mut self) -> &mut Vec<RRQuery> {
+        self.tsig_wire = None;
         self.queries.as_mut()
     }
 
@@ -301,10 +401,12 @@ impl Message {
 
     pub fn set_section(&mut self, index: usize, section: Vec<MessageRecord>) {
         self.sections[index] = section;
+        self.tsig_wire = None;
     }
 
     pub fn add_section(&mut self, index: usize, query: &str, class: RRClasses, ttl: u32, record: Box<dyn RecordBase>) {
         self.sections[index].push((query.to_string(), class, ttl, record));
+        self.tsig_wire = None;
     }
 
     pub fn get_section(&self, index: usize) -> &Vec<MessageRecord> {
@@ -312,6 +414,7 @@ impl Message {
     }
 
     pub fn get_section_mut(&mut self, index: usize) -> &mut Vec<MessageRecord> {
+        self.tsig_wire = None;
         self.sections[index].as_mut()
     }
 
@@ -321,6 +424,7 @@ impl Message {
 
     pub fn set_sections(&mut self, section: [Vec<MessageRecord>; 3]) {
         self.sections = section;
+        self.tsig_wire = None;
     }
 
     pub fn get_sections(&self) -> &[Vec<MessageRecord>; 3] {
@@ -328,9 +432,211 @@ impl Message {
     }
 
     pub fn get_sections_mut(&mut self) -> &mut [Vec<MessageRecord>; 3] {
+        self.tsig_wire = None;
         &mut self.sections
     }
 
+    pub fn set_opt(&mut self, opt: OptRecord) {
+        self.opt = Some(opt);
+        self.tsig_wire = None;
+    }
+
+    pub fn get_opt(&self) -> Option<&OptRecord> {
+        self.opt.as_ref()
+    }
+
+    pub fn get_opt_mut(&mut self) -> Option<&mut OptRecord> {
+        self.tsig_wire = None;
+        self.opt.as_mut()
+    }
+
+    pub fn take_opt(&mut self) -> Option<OptRecord> {
+        self.tsig_wire = None;
+        self.opt.take()
+    }
+
+    pub fn set_tsig(&mut self, key_name: &str, tsig: TSigRecord) {
+        self.tsig = Some((key_name.to_string(), tsig));
+        self.tsig_wire = None;
+    }
+
+    pub fn get_tsig(&self) -> Option<&(String, TSigRecord)> {
+        self.tsig.as_ref()
+    }
+
+    pub fn take_tsig(&mut self) -> Option<(String, TSigRecord)> {
+        self.tsig_wire = None;
+        self.tsig.take()
+    }
+
+    //RFC 8945 S5.3 - SIGN AFTER THE MESSAGE HAS ITS FINAL CONTENT, REQUEST_MAC IS THE REQUEST'S MAC WHEN SIGNING A RESPONSE,
+    //RETURNS THE NEW MAC
+    pub fn sign_tsig(&mut self, key: &TsigKey, time_signed: u64, request_mac: Option<&[u8]>) -> Vec<u8> {
+        self.sign_tsig_with(key, time_signed, &mac_prefix(request_mac), false, u16::MAX as usize)
+    }
+
+    //RFC 8945 S5.2 / S5.4 - CHECKS THE KEY, MAC AND TIME IN THAT ORDER, RETURNS THE MAC OR THE TSIG ERROR TO ANSWER WITH
+    pub fn verify_tsig(&self, keyring: &TsigKeyring, now: u64, request_mac: Option<&[u8]>) -> Result<Vec<u8>, ResponseCodes> {
+        let (key_name, tsig) = self.tsig.as_ref().ok_or(ResponseCodes::FormErr)?;
+
+        let key = tsig.get_algorithm_name()
+            .and_then(|name| TsigAlgorithms::from_str(name).ok())
+            .and_then(|algorithm| keyring.get_key(key_name, algorithm))
+            .ok_or(ResponseCodes::BadKey)?;
+
+        self.verify_tsig_with(key, now, &mac_prefix(request_mac), false)
+    }
+
+    //RFC 8945 S5.2.3 - BADKEY AND BADSIG ANSWERS ARE UNSIGNED, BADTIME IS SIGNED AND CARRIES OUR TIME IN OTHER DATA
+    pub fn tsig_error_response(&self, error: ResponseCodes, keyring: &TsigKeyring, now: u64) -> Message {
+        let mut response = Message::new(self.id);
+        response.set_qr(true);
+        response.set_op_code(self.op_code);
+        response.set_response_code(ResponseCodes::NotAuth);
+
+        for q in &self.queries {
+            response.add_query(q.clone());
+        }
+
+        let Some((key_name, request)) = self.tsig.as_ref() else {
+            return response;
+        };
+
+        let algorithm_name = request.get_algorithm_name().cloned().unwrap_or_default();
+        let key = TsigAlgorithms::from_str(&algorithm_name).ok().and_then(|a| keyring.get_key(key_name, a));
+
+        match (error, key) {
+            (ResponseCodes::BadTime, Some(key)) => {
+                let other = (now & 0xFFFF_FFFF_FFFF).to_be_bytes()[2..].to_vec();
+                response.tsig = Some((key_name.clone(), TSigRecord::new(&algorithm_name, request.get_time_signed(), TSIG_FUDGE, Vec::new(), self.id, error.get_code(), other)));
+                let mac = key.sign(&response.tsig_digest(&mac_prefix(Some(request.get_mac())), false));
+                response.tsig.as_mut().unwrap().1.set_mac(&mac);
+            }
+            _ => {
+                let error = match error {
+                    ResponseCodes::BadTime => ResponseCodes::BadKey,
+                    error => error
+                };
+                response.tsig = Some((key_name.clone(), TSigRecord::new(&algorithm_name, now, TSIG_FUDGE, Vec::new(), self.id, error.get_code(), Vec::new())));
+            }
+        }
+
+        response
+    }
+
+    //PREFIX IS EVERYTHING THE DIGEST COVERS BEFORE THIS MESSAGE, THE PRIOR MAC AND ANY UNSIGNED MESSAGES IN A STREAM,
+    //MAX_PAYLOAD_LEN IS WHAT THE MESSAGE WILL BE SENT WITH SO THE MAC COVERS THE EXACT BYTES THAT GO OUT
+    pub(crate) fn sign_tsig_with(&mut self, key: &TsigKey, time_signed: u64, prefix: &[u8], timers_only: bool, max_payload_len: usize) -> Vec<u8> {
+        let digest_len = key.get_algorithm().get_digest_len();
+        self.tsig = Some((key.get_name().to_string(), TSigRecord::new(key.get_algorithm().get_name(), time_signed, TSIG_FUDGE, vec![0; digest_len], self.id, 0, Vec::new())));
+
+        //THE PLACEHOLDER MAC IS AS LONG AS THE REAL ONE, SO WHATEVER FITS OR IS TRUNCATED NOW IS WHAT TO_BYTES SENDS
+        let mut wire = self.encode(max_payload_len, true);
+        let tsig_len = self.tsig_to_bytes().map(|tsig| tsig.len()).unwrap_or(0);
+        wire.truncate(wire.len()-tsig_len);
+        let count = u16::from_be_bytes([wire[10], wire[11]])-1;
+        wire.splice(10..12, count.to_be_bytes());
+        self.tsig_wire = Some(wire);
+
+        let mac = key.sign(&self.tsig_digest(prefix, timers_only));
+        self.tsig.as_mut().unwrap().1.set_mac(&mac);
+        mac
+    }
+
+    //THE BYTES A MAC COVERS FOR THIS MESSAGE WITHOUT ITS TSIG, AS THEY CAME OFF THE WIRE IF THEY DID
+    pub(crate) fn unsigned_wire(&self) -> Vec<u8> {
+        match self.tsig_wire.as_ref() {
+            Some(wire) => wire.clone(),
+            None => self.encode(u16::MAX as usize, false)
+        }
+    }
+
+    pub(crate) fn verify_tsig_with(&self, key: &TsigKey, now: u64, prefix: &[u8], timers_only: bool) -> Result<Vec<u8>, ResponseCodes> {
+        let (key_name, tsig) = self.tsig.as_ref().ok_or(ResponseCodes::FormErr)?;
+
+        if !key_name.trim_end_matches('.').eq_ignore_ascii_case(key.get_name())
+                || tsig.get_algorithm_name().and_then(|name| TsigAlgorithms::from_str(name).ok()) != Some(key.get_algorithm()) {
+            return Err(ResponseCodes::BadKey);
+        }
+
+        //RFC 8945 S5.2.2.1 - A TRUNCATED MAC MUST KEEP AT LEAST HALF THE DIGEST AND NEVER LESS THAN 10 BYTES
+        let digest_len = key.get_algorithm().get_digest_len();
+        let mac = tsig.get_mac();
+        if mac.len() > digest_len || mac.len() < (digest_len/2).max(10) {
+            return Err(ResponseCodes::FormErr);
+        }
+
+        let expected = key.sign(&self.tsig_digest(prefix, timers_only));
+        if !hmac::verify(&expected[..mac.len()], mac) {
+            return Err(ResponseCodes::BadSig);
+        }
+
+        if now.abs_diff(tsig.get_time_signed()) > tsig.get_fudge() as u64 {
+            return Err(ResponseCodes::BadTime);
+        }
+
+        Ok(mac.to_vec())
+    }
+
+    //RFC 8945 S4.3.3 - PRIOR MAC, THE MESSAGE WITHOUT TSIG UNDER ITS ORIGINAL ID, THEN THE TSIG VARIABLES,
+    //EVERY MESSAGE AFTER THE FIRST IN A STREAM ONLY COVERS THE TIMERS
+    fn tsig_digest(&self, prefix: &[u8], timers_only: bool) -> Vec<u8> {
+        let mut buf = prefix.to_vec();
+
+        let Some((key_name, tsig)) = self.tsig.as_ref() else {
+            buf.extend_from_slice(&self.encode(u16::MAX as usize, false));
+            return buf;
+        };
+
+        let mut wire = match self.tsig_wire.as_ref() {
+            Some(wire) => wire.clone(),
+            None => self.encode(u16::MAX as usize, false)
+        };
+        wire.splice(0..2, tsig.get_original_id().to_be_bytes());
+        buf.extend_from_slice(&wire);
+
+        if !timers_only {
+            buf.extend_from_slice(&pack_fqdn(&key_name.to_lowercase(), &mut HashMap::new(), 0, false));
+            buf.extend_from_slice(&RRClasses::Any.get_code().to_be_bytes());
+            buf.extend_from_slice(&0u32.to_be_bytes());
+            buf.extend_from_slice(&pack_fqdn(&tsig.get_algorithm_name().map(|n| n.to_lowercase()).unwrap_or_default(), &mut HashMap::new(), 0, false));
+        }
+
+        buf.extend_from_slice(&tsig.get_time_signed().to_be_bytes()[2..]);
+        buf.extend_from_slice(&tsig.get_fudge().to_be_bytes());
+
+        if !timers_only {
+            buf.extend_from_slice(&tsig.get_error().to_be_bytes());
+            buf.extend_from_slice(&(tsig.get_data().len() as u16).to_be_bytes());
+            buf.extend_from_slice(tsig.get_data());
+        }
+
+        buf
+    }
+
+    fn tsig_to_bytes(&self) -> Option<Vec<u8>> {
+        let (key_name, tsig) = self.tsig.as_ref()?;
+
+        let mut buf = pack_fqdn(key_name, &mut HashMap::new(), 0, false);
+        buf.extend_from_slice(&RRTypes::TSig.get_code().to_be_bytes());
+        buf.extend_from_slice(&RRClasses::Any.get_code().to_be_bytes());
+        buf.extend_from_slice(&0u32.to_be_bytes());
+        buf.extend_from_slice(&tsig.to_bytes(&mut HashMap::new(), 0).ok()?);
+
+        Some(buf)
+    }
+
+    fn opt_to_bytes(&self) -> Option<Vec<u8>> {
+        let mut opt = self.opt.clone()?;
+        opt.set_ext_rcode((self.response_code.get_code() >> 4) as u8);
+
+        let mut buf = vec![0u8];
+        buf.extend_from_slice(&RRTypes::Opt.get_code().to_be_bytes());
+        buf.extend_from_slice(&opt.to_bytes(&mut HashMap::new(), 0).ok()?);
+
+        Some(buf)
+    }
+
     pub fn as_ref(&self) -> &Self {
         self
     }
@@ -360,20 +666,17 @@ impl fmt::Display for Message {
                 self.queries.len(),
                 self.sections[0].len(),
                 self.sections[1].len(),
-                self.sections[2].len())?;
-
-        /*
-        if let Some(r) = self.additional_records.get(&String::new()) {
-            for r in r {
-                if r.get_type().eq(&RRTypes::Opt) {
-                    writeln!(f, "\r\n;; OPT PSEUDOSECTION:")?;
-                    writeln!(f, "{}", self.additional_records.get(&String::new()).unwrap().get(0).unwrap())?;
-                }
-            }
-        }
-        */
+                self.sections[2].len()+self.opt.iter().len()+self.tsig.iter().len())?;
 
+        if let Some(opt) = &self.opt {
+            writeln!(f, "\r\n;; OPT PSEUDOSECTION:")?;
+            writeln!(f, "{}", opt)?;
+        }
 
+        if let Some((key_name, tsig)) = &self.tsig {
+            writeln!(f, "\r\n;; TSIG PSEUDOSECTION:")?;
+            writeln!(f, "{:<24}{:<8}{:<8}{}", format!("{}.", key_name), 0, RRClasses::Any.to_string(), tsig)?;
+        }
 
         writeln!(f, "\r\n;; QUESTION SECTION:")?;
         for q in &self.queries {
@@ -436,19 +739,22 @@ impl<'a> Iterator for WireIter<'a> {
             //(if self.z { 0x0040 } else { 0 }) |  // Z bit (always 0)
             (if self.message.authenticated_data { 0x0020 } else { 0 }) |  // AD bit
             (if self.message.checking_disabled { 0x0010 } else { 0 }) |  // CD bit
-            (self.message.response_code.get_code() as u16 & 0x000F);  // RCODE
+            (self.message.response_code.get_code() & 0x000F);  // RCODE
 
         buf.splice(2..4, flags.to_be_bytes());
 
         buf.splice(4..6, (self.message.queries.len() as u16).to_be_bytes());
 
+        let opt = self.message.opt_to_bytes();
+        let max_payload_len = self.max_payload_len.saturating_sub(opt.as_ref().map(|o| o.len()).unwrap_or(0));
+
         let mut compression_data = HashMap::new();
         let mut off = DNS_HEADER_LEN;
         let mut truncated = false;
 
         for query in &self.message.queries {
             let q = query.to_bytes(&mut compression_data, off);
-            if off+q.len() > self.max_payload_len {
+            if off+q.len() > max_payload_len {
                 truncated = true;
                 break;
             }
@@ -464,11 +770,13 @@ impl<'a> Iterator for WireIter<'a> {
                 total += self.message.sections[i].len();
 
                 if self.position < total {
-                    let (records, count, t) = records_to_bytes(off, &records[self.position - before..], &mut compression_data, self.max_payload_len);
+                    let (records, count, t) = records_to_bytes(off, &records[self.position - before..], &mut compression_data, max_payload_len);
                     buf.extend_from_slice(&records);
                     buf.splice(i*2+6..i*2+8, count.to_be_bytes());
                     self.position += count as usize;
 
+                    off += records.len();
+
                     if t {
                         break;
                     }
@@ -476,37 +784,93 @@ impl<'a> Iterator for WireIter<'a> {
             }
         }
 
+        if let Some(opt) = opt {
+            buf.extend_from_slice(&opt);
+            let count = u16::from_be_bytes([buf[10], buf[11]])+1;
+            buf.splice(10..12, count.to_be_bytes());
+        }
+
         Some(buf)
     }
 }
 
-fn records_from_bytes(buf: &[u8], off: &mut usize, count: u16) -> Result<Vec<MessageRecord>, MessageError> {
+//RFC 8945 S4.3.1 - A MAC IS DIGESTED WITH ITS LENGTH IN FRONT
+pub(crate) fn mac_prefix(mac: Option<&[u8]>) -> Vec<u8> {
+    let mut buf = Vec::new();
+
+    if let Some(mac) = mac {
+        buf.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+        buf.extend_from_slice(mac);
+    }
+
+    buf
+}
+
+fn records_from_bytes(buf: &[u8], off: &mut usize, count: u16, section_index: usize, opt: &mut Option<OptRecord>, tsig: &mut Option<(String, TSigRecord, usize)>) -> Result<Vec<MessageRecord>, MessageError> {
     let mut section = Vec::new();
 
-    for _ in 0..count {
-        let (fqdn, length) = unpack_fqdn(buf, *off);
+    for i in 0..count as usize {
+        let offset = *off;
+        let error = |reason: String| MessageError::RecordError {
+            section: section_index,
+            index: i,
+            offset,
+            reason
+        };
+
+        let (fqdn, length) = unpack_fqdn(buf, *off).map_err(|e| error(e.to_string()))?;
         *off += length;
 
-        let _type = RRTypes::try_from(u16::from_be_bytes([buf[*off], buf[*off+1]])).map_err(|e| MessageError::RecordError(e.to_string()))?;
+        if *off+10 > buf.len() {
+            return Err(error("truncated record header".to_string()));
+        }
+
+        let _type = RRTypes::try_from(u16::from_be_bytes([buf[*off], buf[*off+1]])).map_err(|e| error(e.to_string()))?;
+        let data_length = u16::from_be_bytes([buf[*off+8], buf[*off+9]]) as usize;
+
+        if *off+10+data_length > buf.len() {
+            return Err(error("record data runs past end of buffer".to_string()));
+        }
 
         match _type {
-            RRTypes::TKey => {}
-            RRTypes::TSig => {}
             RRTypes::Opt => {
-                let record = OptRecord::from_bytes(buf, *off+2).map_err(|e| MessageError::RecordError(e.to_string()))?;
-                *off += 5+u16::from_be_bytes([buf[*off+3], buf[*off+4]]) as usize;
+                if section_index != 2 {
+                    return Err(error("opt record outside of additional section".to_string()));
+                }
+
+                if opt.is_some() {
+                    return Err(error("more than one opt record".to_string()));
+                }
+
+                if !fqdn.is_empty() {
+                    return Err(error("opt record owner is not the root".to_string()));
+                }
+
+                *opt = Some(OptRecord::from_bytes(buf, *off+2).map_err(|e| error(e.to_string()))?);
+            }
+            RRTypes::TSig => {
+                if section_index != 2 || i+1 != count as usize {
+                    return Err(error("tsig record is not the last record".to_string()));
+                }
+
+                *tsig = Some((fqdn, TSigRecord::from_bytes(buf, *off+8).map_err(|e| error(e.to_string()))?, offset));
             }
             _ => {
                 let class = u16::from_be_bytes([buf[*off+2], buf[*off+3]]);
-                let cache_flush = (class & 0x8000) != 0;
-                let class = RRClasses::try_from(class & 0x7FFF).map_err(|e| MessageError::RecordError(e.to_string()))?;
+                //let cache_flush = (class & 0x8000) != 0;
+                let class = RRClasses::try_from(class & 0x7FFF).map_err(|e| error(e.to_string()))?;
                 let ttl = u32::from_be_bytes([buf[*off+4], buf[*off+5], buf[*off+6], buf[*off+7]]);
 
-                let record = <dyn RecordBase>::from_wire(_type, &class, buf, *off+8).map_err(|e| MessageError::RecordError(e.to_string()))?;
+                //RFC 2136 S2.5 - ANY AND NONE CLASS RECORDS WITHOUT RDATA ARE DELETE / PREREQUISITE MARKERS
+                let record = match data_length == 0 && (class == RRClasses::Any || class == RRClasses::None) {
+                    true => UnknownRecord::new(_type, Vec::new()).upcast(),
+                    false => <dyn RecordBase>::from_wire(_type, &class, buf, *off+8).map_err(|e| error(e.to_string()))?
+                };
                 section.push((fqdn, class, ttl, record));
-                *off += 10+u16::from_be_bytes([buf[*off+8], buf[*off+9]]) as usize;
             }
         }
+
+        *off += 10+data_length;
     }
 
     Ok(section)
@@ -520,6 +884,7 @@ fn records_to_bytes(off: usize, section: &[MessageRecord], compression_data: &mu
     let mut off = off;
 
     for (fqdn, class, ttl, record) in section.iter() {
+        let start = off;
         let fqdn = pack_fqdn(&fqdn, compression_data, off, true);
 
         off += fqdn.len()+8;
@@ -527,6 +892,7 @@ fn records_to_bytes(off: usize, section: &[MessageRecord], compression_data: &mu
         match record.to_bytes(compression_data, off) {
             Ok(r) => {
                 if off+r.len() > max_payload_len {
+                    compression_data.retain(|_, p| *p < start);
                     truncated = true;
                     break;
                 }
@@ -541,7 +907,10 @@ fn records_to_bytes(off: usize, section: &[MessageRecord], compression_data: &mu
                 off += r.len();
                 i += 1;
             }
-            Err(_) => continue
+            Err(_) => {
+                compression_data.retain(|_, p| *p < start);
+                off = start;
+            }
         }
     }
 
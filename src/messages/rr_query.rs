@@ -3,7 +3,7 @@ use std::fmt;
 use std::fmt::Formatter;
 use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
-use crate::messages::message::MessageError;
+use crate::records::inter::record_base::RecordError;
 use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
 
 #[derive(Debug, Clone)]
@@ -23,12 +23,17 @@ impl RRQuery {
         }
     }
 
-    pub fn from_bytes(buf: &[u8], off: &mut usize) -> Result<Self, MessageError> {
-        let (fqdn, len) = unpack_fqdn(buf, *off);
+    pub fn from_bytes(buf: &[u8], off: &mut usize) -> Result<Self, RecordError> {
+        let (fqdn, len) = unpack_fqdn(buf, *off).map_err(|e| RecordError(e.to_string()))?;
+
+        if *off+len+4 > buf.len() {
+            return Err(RecordError("truncated query".to_string()));
+        }
+
         *off += len;
 
-        let _type = RRTypes::try_from(u16::from_be_bytes([buf[*off], buf[*off+1]])).map_err(|e| MessageError::RecordError(e.to_string()))?;
-        let class = RRClasses::try_from(u16::from_be_bytes([buf[*off+2], buf[*off+3]])).map_err(|e| MessageError::RecordError(e.to_string()))?;
+        let _type = RRTypes::try_from(u16::from_be_bytes([buf[*off], buf[*off+1]])).map_err(|e| RecordError(e.to_string()))?;
+        let class = RRClasses::try_from(u16::from_be_bytes([buf[*off+2], buf[*off+3]])).map_err(|e| RecordError(e.to_string()))?;
         *off += 4;
 
         Ok(Self {
@@ -23,11 +23,19 @@ impl Default for AaaaRecord {
 impl RecordBase for AaaaRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
         let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        if off+2+length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
         let address = match length {
             16 => {
                 let mut octets = [0u8; 16];
@@ -0,0 +1,194 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+
+#[derive(Clone, Debug)]
+pub struct CaaRecord {
+    pub(crate) flags: u8,
+    pub(crate) tag: Option<String>,
+    pub(crate) value: Vec<u8>
+}
+
+impl Default for CaaRecord {
+
+    fn default() -> Self {
+        Self {
+            flags: 0,
+            tag: None,
+            value: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for CaaRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+4 > length {
+            return Err(RecordError("truncated caa record".to_string()));
+        }
+
+        let flags = buf[off+2];
+
+        let tag_length = buf[off+3] as usize;
+        if tag_length == 0 {
+            return Err(RecordError("tag must not be empty".to_string()));
+        }
+
+        if off+4+tag_length > length {
+            return Err(RecordError("tag runs past record length".to_string()));
+        }
+
+        let tag = &buf[off+4..off+4+tag_length];
+        if !tag.iter().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(RecordError("tag must be alphanumeric".to_string()));
+        }
+
+        let tag = String::from_utf8(tag.to_vec()).map_err(|e| RecordError(e.to_string()))?;
+        let value = buf[off+4+tag_length..length].to_vec();
+
+        Ok(Self {
+            flags,
+            tag: Some(tag),
+            value
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 3];
+
+        buf[2] = self.flags;
+
+        let tag = self.tag.as_ref().ok_or_else(|| RecordError("tag param was not set".to_string()))?;
+        if tag.is_empty() || tag.len() > 255 {
+            return Err(RecordError("tag must be between 1 and 255 bytes".to_string()));
+        }
+
+        buf.push(tag.len() as u8);
+        buf.extend_from_slice(tag.as_bytes());
+        buf.extend_from_slice(&self.value);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::Caa
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl CaaRecord {
+
+    pub fn new(flags: u8, tag: &str, value: &[u8]) -> Self {
+        Self {
+            flags,
+            tag: Some(tag.to_string()),
+            value: value.to_vec()
+        }
+    }
+
+    pub fn set_flags(&mut self, flags: u8) {
+        self.flags = flags;
+    }
+
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn set_issuer_critical(&mut self, issuer_critical: bool) {
+        match issuer_critical {
+            true => self.flags |= 0x80,
+            false => self.flags &= !0x80
+        }
+    }
+
+    pub fn is_issuer_critical(&self) -> bool {
+        (self.flags & 0x80) != 0
+    }
+
+    pub fn set_tag(&mut self, tag: &str) {
+        self.tag = Some(tag.to_string());
+    }
+
+    pub fn get_tag(&self) -> Option<&String> {
+        self.tag.as_ref()
+    }
+
+    pub fn set_value(&mut self, value: &[u8]) {
+        self.value = value.to_vec();
+    }
+
+    pub fn get_value(&self) -> &[u8] {
+        self.value.as_ref()
+    }
+
+    //RFC 8659 S4.1 - A CA MUST NOT ISSUE IF IT FINDS A CRITICAL PROPERTY IT DOES NOT UNDERSTAND
+    pub fn is_understood(&self) -> bool {
+        !self.is_issuer_critical() || matches!(self.tag.as_ref().map(|t| t.to_lowercase()).as_deref(),
+            Some("issue") | Some("issuewild") | Some("iodef") | Some("issuemail") | Some("issuevmc") | Some("contactemail") | Some("contactphone"))
+    }
+}
+
+impl fmt::Display for CaaRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut value = String::new();
+
+        for &c in &self.value {
+            match c {
+                b'"' | b'\\' => {
+                    value.push('\\');
+                    value.push(c as char);
+                }
+                0x20..=0x7e => value.push(c as char),
+                _ => value.push_str(&format!("\\{:03}", c))
+            }
+        }
+
+        write!(f, "{:<8}{} {} \"{}\"", self.get_type().to_string(),
+               self.flags,
+               self.tag.as_ref().unwrap_or(&String::new()),
+               value)
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x16, 0x0, 0x5, 0x69, 0x73, 0x73, 0x75, 0x65, 0x6c, 0x65, 0x74, 0x73, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x2e, 0x6f, 0x72, 0x67 ];
+    let record = CaaRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert_eq!(record.to_string(), "CAA     0 issue \"letsencrypt.org\"");
+}
@@ -0,0 +1,167 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::base64;
+
+#[derive(Clone, Debug)]
+pub struct CdnsKeyRecord {
+    pub(crate) flags: u16,
+    pub(crate) protocol: u8,
+    pub(crate) algorithm: u8,
+    pub(crate) public_key: Vec<u8>
+}
+
+impl Default for CdnsKeyRecord {
+
+    fn default() -> Self {
+        Self {
+            flags: 0,
+            protocol: 0,
+            algorithm: 0,
+            public_key: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for CdnsKeyRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+6 > length {
+            return Err(RecordError("truncated cdnskey record".to_string()));
+        }
+
+        let flags = u16::from_be_bytes([buf[off+2], buf[off+3]]);
+        /*
+        Flags: 0x0100
+            .... ...1 .... .... = Zone Key: This is the zone key for specified zone
+            .... .... 0... .... = Key Revoked: No
+            .... .... .... ...0 = Key Signing Key: No
+            0000 000. .000 000. = Key Signing Key: 0x0000
+        */
+
+        let protocol = buf[off+4];
+        let algorithm = buf[off+5];
+
+        let public_key = buf[off+6..length].to_vec();
+
+        Ok(Self {
+            flags,
+            protocol,
+            algorithm,
+            public_key
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 6];
+
+        buf.splice(2..4, self.flags.to_be_bytes());
+        buf[4] = self.protocol;
+        buf[5] = self.algorithm;
+
+        buf.extend_from_slice(&self.public_key);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::CdnsKey
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl CdnsKeyRecord {
+
+    pub fn new(flags: u16, protocol: u8, algorithm: u8, public_key: Vec<u8>) -> Self {
+        Self {
+            flags,
+            protocol,
+            algorithm,
+            public_key
+        }
+    }
+
+    pub fn set_flags(&mut self, flags: u16) {
+        self.flags = flags;
+    }
+
+    pub fn get_flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.protocol = protocol;
+    }
+
+    pub fn get_protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: u8) {
+        self.algorithm = algorithm;
+    }
+
+    pub fn get_algorithm(&self) -> u8 {
+        self.algorithm
+    }
+
+    pub fn set_public_key(&mut self, public_key: &[u8]) {
+        self.public_key = public_key.to_vec();
+    }
+
+    pub fn get_public_key(&self) -> &[u8] {
+        self.public_key.as_ref()
+    }
+}
+
+impl fmt::Display for CdnsKeyRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<8}{} {} {} {}", self.get_type().to_string(),
+               self.flags,
+               self.protocol,
+               self.algorithm,
+               base64::encode(&self.public_key))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x8, 0x1, 0x1, 0x3, 0xd, 0xde, 0xad, 0xbe, 0xef ];
+    let record = CdnsKeyRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
@@ -25,14 +25,26 @@ impl Default for ChARecord {
 impl RecordBase for ChARecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
         let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
-        let (network, length) = unpack_fqdn(buf, off+2);
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let (network, network_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        if off+4+network_length != length {
+            return Err(RecordError("network does not match record length".to_string()));
+        }
 
-        let address = u16::from_be_bytes([buf[off+2+length], buf[off+3+length]]);
+        let address = u16::from_be_bytes([buf[off+2+network_length], buf[off+3+network_length]]);
 
         Ok(Self {
             network: Some(network),
@@ -23,12 +23,24 @@ impl Default for CNameRecord {
 impl RecordBase for CNameRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
-        let (target, _) = unpack_fqdn(buf, off+2);
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let (target, target_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        if off+2+target_length != length {
+            return Err(RecordError("target does not match record length".to_string()));
+        }
 
         Ok(Self {
             target: Some(target)
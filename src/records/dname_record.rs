@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn, MAX_FQDN_LEN};
+
+#[derive(Clone, Debug)]
+pub struct DNameRecord {
+    pub(crate) target: Option<String>
+}
+
+impl Default for DNameRecord {
+
+    fn default() -> Self {
+        Self {
+            target: None
+        }
+    }
+}
+
+impl RecordBase for DNameRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let (target, target_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        if off+2+target_length != length {
+            return Err(RecordError("target does not match record length".to_string()));
+        }
+
+        Ok(Self {
+            target: Some(target)
+        })
+    }
+
+    fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 2];
+
+        buf.extend_from_slice(&pack_fqdn(self.target.as_ref()
+            .ok_or_else(|| RecordError("target param was not set".to_string()))?, compression_data, off+2, false));
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::DName
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl DNameRecord {
+
+    pub fn new(target: &str) -> Self {
+        Self {
+            target: Some(target.to_string())
+        }
+    }
+
+    pub fn set_target(&mut self, target: &str) {
+        self.target = Some(target.to_string());
+    }
+
+    pub fn get_target(&self) -> Option<&String> {
+        self.target.as_ref()
+    }
+
+    //RFC 6672 S2.2 - REPLACE THE OWNER SUFFIX OF NAME WITH THE TARGET, ERR IF THE RESULT IS TOO LONG
+    pub fn substitute(&self, owner: &str, name: &str) -> Option<Result<String, RecordError>> {
+        let target = self.target.as_ref()?;
+        let prefix = match owner.is_empty() {
+            true => name,
+            false => name.strip_suffix(owner)?.strip_suffix('.')?
+        };
+
+        if prefix.is_empty() {
+            return None;
+        }
+
+        let substituted = match target.is_empty() {
+            true => prefix.to_string(),
+            false => format!("{}.{}", prefix, target)
+        };
+
+        if substituted.split('.').map(|label| label.len()+1).sum::<usize>()+1 > MAX_FQDN_LEN {
+            return Some(Err(RecordError(format!("substituted name exceeds {} bytes", MAX_FQDN_LEN))));
+        }
+
+        Some(Ok(substituted))
+    }
+}
+
+impl fmt::Display for DNameRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<8}{}", self.get_type().to_string(),
+               format!("{}.", self.target.as_ref().unwrap_or(&String::new())))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0xe, 0x2, 0x78, 0x32, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0 ];
+    let record = DNameRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+
+    let mut compression_data = HashMap::new();
+    compression_data.insert("find9.net".to_string(), 12);
+    assert_eq!(buf, record.to_bytes(&mut compression_data, 0).unwrap());
+
+    assert_eq!(record.substitute("old.net", "www.a.old.net").unwrap().unwrap(), "www.a.x2.find9.net");
+    assert!(record.substitute("old.net", "old.net").is_none());
+    assert!(record.substitute("old.net", "www.bold.net").is_none());
+    assert!(record.substitute("old.net", &format!("{}.old.net", vec!["a".repeat(63); 4].join("."))).unwrap().is_err());
+}
@@ -2,14 +2,13 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::{base64, sha1, sha256, sha512};
+use crate::utils::fqdn_utils::pack_fqdn;
 
 #[derive(Clone, Debug)]
 pub struct DnsKeyRecord {
-    class: RRClasses,
-    ttl: u32,
     pub(crate) flags: u16,
     pub(crate) protocol: u8,
     pub(crate) algorithm: u8,
@@ -20,8 +19,6 @@ impl Default for DnsKeyRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            ttl: 0,
             flags: 0,
             protocol: 0,
             algorithm: 0,
@@ -33,12 +30,25 @@ impl Default for DnsKeyRecord {
 impl RecordBase for DnsKeyRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut off = off;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
 
-        let class = RRClasses::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
+        if off+6 > length {
+            return Err(RecordError("truncated dnskey record".to_string()));
+        }
 
-        let flags = u16::from_be_bytes([buf[off+8], buf[off+9]]);
+        let flags = u16::from_be_bytes([buf[off+2], buf[off+3]]);
         /*
         Flags: 0x0100
             .... ...1 .... .... = Zone Key: This is the zone key for specified zone
@@ -47,17 +57,12 @@ impl RecordBase for DnsKeyRecord {
             0000 000. .000 000. = Key Signing Key: 0x0000
         */
 
-        let protocol = buf[off+10];
-        let algorithm = buf[off+11];
-
-        let data_length = off+8+u16::from_be_bytes([buf[off+6], buf[off+7]]) as usize;
-        off += 12;
+        let protocol = buf[off+4];
+        let algorithm = buf[off+5];
 
-        let public_key = buf[off..data_length].to_vec();
+        let public_key = buf[off+6..length].to_vec();
 
         Ok(Self {
-            class,
-            ttl,
             flags,
             protocol,
             algorithm,
@@ -66,18 +71,15 @@ impl RecordBase for DnsKeyRecord {
     }
 
     fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 12];
-
-        buf.splice(0..2, self.class.get_code().to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
+        let mut buf = vec![0u8; 6];
 
-        buf.splice(8..10, self.flags.to_be_bytes());
-        buf[10] = self.protocol;
-        buf[11] = self.algorithm;
+        buf.splice(2..4, self.flags.to_be_bytes());
+        buf[4] = self.protocol;
+        buf[5] = self.algorithm;
 
         buf.extend_from_slice(&self.public_key);
 
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -105,34 +107,121 @@ impl RecordBase for DnsKeyRecord {
 
 impl DnsKeyRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(flags: u16, protocol: u8, algorithm: u8, public_key: Vec<u8>) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            flags,
+            protocol,
+            algorithm,
+            public_key
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
+    pub fn set_flags(&mut self, flags: u16) {
+        self.flags = flags;
+    }
+
+    pub fn get_flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.protocol = protocol;
+    }
+
+    pub fn get_protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: u8) {
+        self.algorithm = algorithm;
     }
 
-    pub fn get_class(&self) -> RRClasses {
-        self.class
+    pub fn get_algorithm(&self) -> u8 {
+        self.algorithm
     }
 
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
+    pub fn set_public_key(&mut self, public_key: &[u8]) {
+        self.public_key = public_key.to_vec();
     }
 
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
+    pub fn get_public_key(&self) -> &[u8] {
+        self.public_key.as_ref()
+    }
+
+    pub fn is_zone_key(&self) -> bool {
+        (self.flags & 0x0100) != 0
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        (self.flags & 0x0080) != 0
+    }
+
+    pub fn is_secure_entry_point(&self) -> bool {
+        (self.flags & 0x0001) != 0
+    }
+
+    //RFC 4034 APPENDIX B
+    pub fn get_key_tag(&self) -> u16 {
+        if self.algorithm == 1 {
+            let len = self.public_key.len();
+            if len < 3 {
+                return 0;
+            }
+
+            return u16::from_be_bytes([self.public_key[len-3], self.public_key[len-2]]);
+        }
+
+        let mut rdata = vec![0u8; 4];
+        rdata.splice(0..2, self.flags.to_be_bytes());
+        rdata[2] = self.protocol;
+        rdata[3] = self.algorithm;
+        rdata.extend_from_slice(&self.public_key);
+
+        let mut ac = 0u32;
+        for (i, &byte) in rdata.iter().enumerate() {
+            ac += match i & 1 {
+                0 => (byte as u32) << 8,
+                _ => byte as u32
+            };
+        }
+
+        ac += (ac >> 16) & 0xFFFF;
+        (ac & 0xFFFF) as u16
+    }
+
+    //RFC 4034 S5.1.4 - digest = digest_algorithm( DNSKEY owner name | DNSKEY RDATA)
+    pub fn get_digest(&self, owner: &str, digest_type: u8) -> Result<Vec<u8>, RecordError> {
+        let mut buf = pack_fqdn(&owner.trim_end_matches('.').to_lowercase(), &mut HashMap::new(), 0, false);
+        buf.extend_from_slice(&self.to_bytes(&mut HashMap::new(), 0)?[2..]);
+
+        Ok(match digest_type {
+            1 => sha1::hash(&buf).to_vec(),
+            2 => sha256::hash(&buf).to_vec(),
+            4 => sha512::hash_384(&buf).to_vec(),
+            _ => return Err(RecordError(format!("unsupported digest type: {}", digest_type)))
+        })
     }
 }
 
 impl fmt::Display for DnsKeyRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "type {:?}, class {:?}", self.get_type(), self.class)
+        write!(f, "{:<8}{} {} {} {}", self.get_type().to_string(),
+               self.flags,
+               self.protocol,
+               self.algorithm,
+               base64::encode(&self.public_key))
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x8, 0x1, 0x1, 0x3, 0xd, 0xde, 0xad, 0xbe, 0xef ];
+    let record = DnsKeyRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+
+    //RFC 4034 S5.4
+    let record = DnsKeyRecord::new(256, 3, 5, base64::decode("AQOeiiR0GOMYkDshWoSKz9XzfwJr1AYtsmx3TGkJaNXVbfi/2pHm822aJ5iI9BMzNXxeYCmZDRD99WYwYqUSdjMmmAphXdvxegXd/M5+X7OrzKBaMbCVdFLUUh6DhweJBjEVv5f2wwjM9XzcnOf+EPbtG9DMBmADjFDc2w/rljwvFw==").unwrap());
+    assert_eq!(record.get_key_tag(), 60485);
+    assert_eq!(crate::utils::hex::encode(&record.get_digest("dskey.example.com", 1).unwrap()), "2bb183af5f22588179a53b0a98631fad1a292118");
+}
@@ -0,0 +1,174 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::dnskey_record::DnsKeyRecord;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::hex;
+
+#[derive(Clone, Debug)]
+pub struct DsRecord {
+    pub(crate) key_tag: u16,
+    pub(crate) algorithm: u8,
+    pub(crate) digest_type: u8,
+    pub(crate) digest: Vec<u8>
+}
+
+impl Default for DsRecord {
+
+    fn default() -> Self {
+        Self {
+            key_tag: 0,
+            algorithm: 0,
+            digest_type: 0,
+            digest: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for DsRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+6 > length {
+            return Err(RecordError("truncated ds record".to_string()));
+        }
+
+        let key_tag = u16::from_be_bytes([buf[off+2], buf[off+3]]);
+        let algorithm = buf[off+4];
+        let digest_type = buf[off+5];
+        let digest = buf[off+6..length].to_vec();
+
+        Ok(Self {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 6];
+
+        buf.splice(2..4, self.key_tag.to_be_bytes());
+        buf[4] = self.algorithm;
+        buf[5] = self.digest_type;
+
+        buf.extend_from_slice(&self.digest);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::Ds
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl DsRecord {
+
+    pub fn new(key_tag: u16, algorithm: u8, digest_type: u8, digest: Vec<u8>) -> Self {
+        Self {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest
+        }
+    }
+
+    pub fn from_dnskey(owner: &str, dnskey: &DnsKeyRecord, digest_type: u8) -> Result<Self, RecordError> {
+        Ok(Self {
+            key_tag: dnskey.get_key_tag(),
+            algorithm: dnskey.get_algorithm(),
+            digest_type,
+            digest: dnskey.get_digest(owner, digest_type)?
+        })
+    }
+
+    pub fn matches(&self, owner: &str, dnskey: &DnsKeyRecord) -> bool {
+        self.key_tag == dnskey.get_key_tag() &&
+            self.algorithm == dnskey.get_algorithm() &&
+            dnskey.get_digest(owner, self.digest_type).map(|d| d.eq(&self.digest)).unwrap_or(false)
+    }
+
+    pub fn set_key_tag(&mut self, key_tag: u16) {
+        self.key_tag = key_tag;
+    }
+
+    pub fn get_key_tag(&self) -> u16 {
+        self.key_tag
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: u8) {
+        self.algorithm = algorithm;
+    }
+
+    pub fn get_algorithm(&self) -> u8 {
+        self.algorithm
+    }
+
+    pub fn set_digest_type(&mut self, digest_type: u8) {
+        self.digest_type = digest_type;
+    }
+
+    pub fn get_digest_type(&self) -> u8 {
+        self.digest_type
+    }
+
+    pub fn set_digest(&mut self, digest: &[u8]) {
+        self.digest = digest.to_vec();
+    }
+
+    pub fn get_digest(&self) -> &[u8] {
+        self.digest.as_ref()
+    }
+}
+
+impl fmt::Display for DsRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<8}{} {} {} {}", self.get_type().to_string(),
+               self.key_tag,
+               self.algorithm,
+               self.digest_type,
+               hex::encode(&self.digest))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x18, 0xec, 0x45, 0x5, 0x1, 0x2b, 0xb1, 0x83, 0xaf, 0x5f, 0x22, 0x58, 0x81, 0x79, 0xa5, 0x3b, 0xa, 0x98, 0x63, 0x1f, 0xad, 0x1a, 0x29, 0x21, 0x18 ];
+    let record = DsRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
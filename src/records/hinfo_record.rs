@@ -24,17 +24,36 @@ impl Default for HInfoRecord {
 impl RecordBase for HInfoRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
         let data_length = buf[off+2] as usize;
-        let cpu = String::from_utf8(buf[off+3..off+3+data_length].to_vec()).unwrap();
+        if off+4+data_length > length {
+            return Err(RecordError("cpu runs past record length".to_string()));
+        }
+
+        let cpu = String::from_utf8(buf[off+3..off+3+data_length].to_vec())
+            .map_err(|e| RecordError(e.to_string()))?;
         let off = off+3+data_length;
 
         let data_length = buf[off] as usize;
-        let os = String::from_utf8(buf[off+1..off+1+data_length].to_vec()).unwrap();
+        if off+1+data_length != length {
+            return Err(RecordError("os does not match record length".to_string()));
+        }
+
+        let os = String::from_utf8(buf[off+1..off+1+data_length].to_vec())
+            .map_err(|e| RecordError(e.to_string()))?;
 
         Ok(Self {
             cpu: Some(cpu),
@@ -45,11 +64,13 @@ impl RecordBase for HInfoRecord {
     fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
         let mut buf = vec![0u8; 2];
 
-        let cpu = self.cpu.as_ref().unwrap().as_bytes();
+        let cpu = self.cpu.as_ref()
+            .ok_or_else(|| RecordError("cpu param was not set".to_string()))?.as_bytes();
         buf.push(cpu.len() as u8);
         buf.extend_from_slice(cpu);
 
-        let os = self.os.as_ref().unwrap().as_bytes();
+        let os = self.os.as_ref()
+            .ok_or_else(|| RecordError("os param was not set".to_string()))?.as_bytes();
         buf.push(os.len() as u8);
         buf.extend_from_slice(os);
 
@@ -0,0 +1,386 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::records::inter::opt_codes::OptCodes;
+use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
+use crate::utils::hex;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EdnsOption {
+    Llq {
+        version: u16,
+        opcode: u16,
+        error: u16,
+        id: u64,
+        lease: u32
+    },
+    Ul {
+        lease: u32,
+        key_lease: Option<u32>
+    },
+    Nsid(Vec<u8>),
+    Dau(Vec<u8>),
+    Dhu(Vec<u8>),
+    N3u(Vec<u8>),
+    Ecs {
+        address: IpAddr,
+        source_prefix: u8,
+        scope_prefix: u8
+    },
+    Expire(Option<u32>),
+    Cookie {
+        client: [u8; 8],
+        server: Option<Vec<u8>>
+    },
+    TcpKeepalive(Option<u16>),
+    Padding(u16),
+    Chain(String),
+    KeyTag(Vec<u16>),
+    Ede {
+        info_code: u16,
+        extra_text: String
+    },
+    DnsSecTrustedKey(Vec<u8>),
+    DnsSecValidated(Vec<u8>),
+    AdaptiveDnsDiscovery(Vec<u8>),
+    DoH(Vec<u8>),
+    MultiUserClientSubnet(Vec<u8>),
+    Unknown(u16, Vec<u8>)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EdnsOptionParseError(pub String);
+
+impl fmt::Display for EdnsOptionParseError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl EdnsOption {
+
+    pub fn from_bytes(code: OptCodes, buf: &[u8]) -> Result<Self, EdnsOptionParseError> {
+        Ok(match code {
+            OptCodes::Llq => {
+                if buf.len() != 18 {
+                    return Err(EdnsOptionParseError("llq must be 18 bytes".to_string()));
+                }
+
+                Self::Llq {
+                    version: u16::from_be_bytes([buf[0], buf[1]]),
+                    opcode: u16::from_be_bytes([buf[2], buf[3]]),
+                    error: u16::from_be_bytes([buf[4], buf[5]]),
+                    id: u64::from_be_bytes([buf[6], buf[7], buf[8], buf[9], buf[10], buf[11], buf[12], buf[13]]),
+                    lease: u32::from_be_bytes([buf[14], buf[15], buf[16], buf[17]])
+                }
+            }
+            OptCodes::Ul => {
+                match buf.len() {
+                    4 => Self::Ul {
+                        lease: u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]),
+                        key_lease: None
+                    },
+                    8 => Self::Ul {
+                        lease: u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]),
+                        key_lease: Some(u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]))
+                    },
+                    _ => return Err(EdnsOptionParseError("update lease must be 4 or 8 bytes".to_string()))
+                }
+            }
+            OptCodes::Nsid => Self::Nsid(buf.to_vec()),
+            OptCodes::Dau => Self::Dau(buf.to_vec()),
+            OptCodes::Dhu => Self::Dhu(buf.to_vec()),
+            OptCodes::N3u => Self::N3u(buf.to_vec()),
+            OptCodes::Ecs => {
+                if buf.len() < 4 {
+                    return Err(EdnsOptionParseError("client subnet must be at least 4 bytes".to_string()));
+                }
+
+                let family = u16::from_be_bytes([buf[0], buf[1]]);
+                let source_prefix = buf[2];
+                let scope_prefix = buf[3];
+
+                let max_prefix = match family {
+                    1 => 32,
+                    2 => 128,
+                    _ => return Err(EdnsOptionParseError(format!("unknown client subnet family: {}", family)))
+                };
+
+                if source_prefix > max_prefix || scope_prefix > max_prefix {
+                    return Err(EdnsOptionParseError("client subnet prefix is longer than the address".to_string()));
+                }
+
+                let addr = &buf[4..];
+                if addr.len() != (source_prefix as usize).div_ceil(8) {
+                    return Err(EdnsOptionParseError("client subnet address does not match source prefix".to_string()));
+                }
+
+                if !source_prefix.is_multiple_of(8) && addr[addr.len()-1] & (0xff >> (source_prefix % 8)) != 0 {
+                    return Err(EdnsOptionParseError("client subnet address has bits set past source prefix".to_string()));
+                }
+
+                let address = match family {
+                    1 => {
+                        let mut octets = [0u8; 4];
+                        octets[..addr.len()].copy_from_slice(addr);
+                        IpAddr::V4(Ipv4Addr::from(octets))
+                    }
+                    _ => {
+                        let mut octets = [0u8; 16];
+                        octets[..addr.len()].copy_from_slice(addr);
+                        IpAddr::V6(Ipv6Addr::from(octets))
+                    }
+                };
+
+                Self::Ecs {
+                    address,
+                    source_prefix,
+                    scope_prefix
+                }
+            }
+            OptCodes::Expire => {
+                match buf.len() {
+                    0 => Self::Expire(None),
+                    4 => Self::Expire(Some(u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]))),
+                    _ => return Err(EdnsOptionParseError("expire must be 0 or 4 bytes".to_string()))
+                }
+            }
+            OptCodes::Cookie => {
+                match buf.len() {
+                    8 => Self::Cookie {
+                        client: [buf[0], buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7]],
+                        server: None
+                    },
+                    16..=40 => Self::Cookie {
+                        client: [buf[0], buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7]],
+                        server: Some(buf[8..].to_vec())
+                    },
+                    _ => return Err(EdnsOptionParseError("cookie must be 8 or 16 to 40 bytes".to_string()))
+                }
+            }
+            OptCodes::TcpKeepalive => {
+                match buf.len() {
+                    0 => Self::TcpKeepalive(None),
+                    2 => Self::TcpKeepalive(Some(u16::from_be_bytes([buf[0], buf[1]]))),
+                    _ => return Err(EdnsOptionParseError("tcp keepalive must be 0 or 2 bytes".to_string()))
+                }
+            }
+            OptCodes::Padding => Self::Padding(buf.len() as u16),
+            OptCodes::Chain => {
+                let (fqdn, length) = unpack_fqdn(buf, 0).map_err(|e| EdnsOptionParseError(e.to_string()))?;
+                if length != buf.len() {
+                    return Err(EdnsOptionParseError("chain does not match option length".to_string()));
+                }
+
+                Self::Chain(fqdn)
+            }
+            OptCodes::KeyTag => {
+                if !buf.len().is_multiple_of(2) {
+                    return Err(EdnsOptionParseError("key tag length must be a multiple of 2".to_string()));
+                }
+
+                Self::KeyTag(buf.chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect())
+            }
+            OptCodes::Ede => {
+                if buf.len() < 2 {
+                    return Err(EdnsOptionParseError("extended error must be at least 2 bytes".to_string()));
+                }
+
+                Self::Ede {
+                    info_code: u16::from_be_bytes([buf[0], buf[1]]),
+                    extra_text: String::from_utf8(buf[2..].to_vec()).map_err(|e| EdnsOptionParseError(e.to_string()))?
+                }
+            }
+            OptCodes::DnsSecTrustedKey => Self::DnsSecTrustedKey(buf.to_vec()),
+            OptCodes::DnsSecValidated => Self::DnsSecValidated(buf.to_vec()),
+            OptCodes::AdaptiveDnsDiscovery => Self::AdaptiveDnsDiscovery(buf.to_vec()),
+            OptCodes::DoH => Self::DoH(buf.to_vec()),
+            OptCodes::MultiUserClientSubnet => Self::MultiUserClientSubnet(buf.to_vec()),
+            OptCodes::Unknown(code) => Self::Unknown(code, buf.to_vec())
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Llq { version, opcode, error, id, lease } => {
+                let mut out = Vec::with_capacity(18);
+                out.extend_from_slice(&version.to_be_bytes());
+                out.extend_from_slice(&opcode.to_be_bytes());
+                out.extend_from_slice(&error.to_be_bytes());
+                out.extend_from_slice(&id.to_be_bytes());
+                out.extend_from_slice(&lease.to_be_bytes());
+                out
+            }
+            Self::Ul { lease, key_lease } => {
+                let mut out = lease.to_be_bytes().to_vec();
+                if let Some(key_lease) = key_lease {
+                    out.extend_from_slice(&key_lease.to_be_bytes());
+                }
+                out
+            }
+            Self::Nsid(data) |
+            Self::Dau(data) |
+            Self::Dhu(data) |
+            Self::N3u(data) => data.clone(),
+            Self::Ecs { address, source_prefix, scope_prefix } => {
+                let (family, octets) = match address {
+                    IpAddr::V4(address) => (1u16, address.octets().to_vec()),
+                    IpAddr::V6(address) => (2u16, address.octets().to_vec())
+                };
+
+                let mut out = family.to_be_bytes().to_vec();
+                out.push(*source_prefix);
+                out.push(*scope_prefix);
+
+                let length = ((*source_prefix as usize).div_ceil(8)).min(octets.len());
+                out.extend_from_slice(&octets[..length]);
+
+                if !source_prefix.is_multiple_of(8) && length > 0 {
+                    out[4+length-1] &= 0xff << (8 - (source_prefix % 8));
+                }
+                out
+            }
+            Self::Expire(expire) => expire.map(|e| e.to_be_bytes().to_vec()).unwrap_or_default(),
+            Self::Cookie { client, server } => {
+                let mut out = client.to_vec();
+                if let Some(server) = server {
+                    out.extend_from_slice(server);
+                }
+                out
+            }
+            Self::TcpKeepalive(timeout) => timeout.map(|t| t.to_be_bytes().to_vec()).unwrap_or_default(),
+            Self::Padding(length) => vec![0u8; *length as usize],
+            Self::Chain(fqdn) => pack_fqdn(fqdn, &mut Default::default(), 0, false),
+            Self::KeyTag(tags) => {
+                let mut out = Vec::with_capacity(tags.len() * 2);
+                for tag in tags {
+                    out.extend_from_slice(&tag.to_be_bytes());
+                }
+                out
+            }
+            Self::Ede { info_code, extra_text } => {
+                let mut out = info_code.to_be_bytes().to_vec();
+                out.extend_from_slice(extra_text.as_bytes());
+                out
+            }
+            Self::DnsSecTrustedKey(data) |
+            Self::DnsSecValidated(data) |
+            Self::AdaptiveDnsDiscovery(data) |
+            Self::DoH(data) |
+            Self::MultiUserClientSubnet(data) |
+            Self::Unknown(_, data) => data.clone()
+        }
+    }
+
+    pub fn get_code(&self) -> OptCodes {
+        match self {
+            Self::Llq { .. }                => OptCodes::Llq,
+            Self::Ul { .. }                 => OptCodes::Ul,
+            Self::Nsid(_)                   => OptCodes::Nsid,
+            Self::Dau(_)                    => OptCodes::Dau,
+            Self::Dhu(_)                    => OptCodes::Dhu,
+            Self::N3u(_)                    => OptCodes::N3u,
+            Self::Ecs { .. }                => OptCodes::Ecs,
+            Self::Expire(_)                 => OptCodes::Expire,
+            Self::Cookie { .. }             => OptCodes::Cookie,
+            Self::TcpKeepalive(_)           => OptCodes::TcpKeepalive,
+            Self::Padding(_)                => OptCodes::Padding,
+            Self::Chain(_)                  => OptCodes::Chain,
+            Self::KeyTag(_)                 => OptCodes::KeyTag,
+            Self::Ede { .. }                => OptCodes::Ede,
+            Self::DnsSecTrustedKey(_)       => OptCodes::DnsSecTrustedKey,
+            Self::DnsSecValidated(_)        => OptCodes::DnsSecValidated,
+            Self::AdaptiveDnsDiscovery(_)   => OptCodes::AdaptiveDnsDiscovery,
+            Self::DoH(_)                    => OptCodes::DoH,
+            Self::MultiUserClientSubnet(_)  => OptCodes::MultiUserClientSubnet,
+            Self::Unknown(code, _)          => OptCodes::Unknown(*code)
+        }
+    }
+}
+
+impl fmt::Display for EdnsOption {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Llq { version, opcode, error, id, lease } => write!(f, "{} {} {} {} {}", version, opcode, error, id, lease),
+            Self::Ul { lease, key_lease } => match key_lease {
+                Some(key_lease) => write!(f, "{} {}", lease, key_lease),
+                None => write!(f, "{}", lease)
+            },
+            Self::Nsid(data) => match std::str::from_utf8(data) {
+                Ok(s) if s.chars().all(|c| c.is_ascii_graphic() || c == ' ') => write!(f, "{} (\"{}\")", hex::encode(data), s),
+                _ => write!(f, "{}", hex::encode(data))
+            },
+            Self::Dau(algorithms) |
+            Self::Dhu(algorithms) |
+            Self::N3u(algorithms) => write!(f, "{}", algorithms.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(" ")),
+            Self::Ecs { address, source_prefix, scope_prefix } => write!(f, "{}/{}/{}", address, source_prefix, scope_prefix),
+            Self::Expire(expire) => match expire {
+                Some(expire) => write!(f, "{}", expire),
+                None => Ok(())
+            },
+            Self::Cookie { client, server } => match server {
+                Some(server) => write!(f, "{}{}", hex::encode(client), hex::encode(server)),
+                None => write!(f, "{}", hex::encode(client))
+            },
+            Self::TcpKeepalive(timeout) => match timeout {
+                Some(timeout) => write!(f, "{}.{} secs", timeout / 10, timeout % 10),
+                None => Ok(())
+            },
+            Self::Padding(length) => write!(f, "({} bytes)", length),
+            Self::Chain(fqdn) => write!(f, "{}.", fqdn),
+            Self::KeyTag(tags) => write!(f, "{}", tags.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")),
+            Self::Ede { info_code, extra_text } => match extra_text.is_empty() {
+                true => write!(f, "{} ({})", info_code, ede_name(*info_code)),
+                false => write!(f, "{} ({}): ({})", info_code, ede_name(*info_code), extra_text)
+            },
+            Self::DnsSecTrustedKey(data) |
+            Self::DnsSecValidated(data) |
+            Self::AdaptiveDnsDiscovery(data) |
+            Self::DoH(data) |
+            Self::MultiUserClientSubnet(data) |
+            Self::Unknown(_, data) => write!(f, "{}", hex::encode(data))
+        }
+    }
+}
+
+//https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#extended-dns-error-codes
+fn ede_name(info_code: u16) -> &'static str {
+    match info_code {
+        0 => "Other Error",
+        1 => "Unsupported DNSKEY Algorithm",
+        2 => "Unsupported DS Digest Type",
+        3 => "Stale Answer",
+        4 => "Forged Answer",
+        5 => "DNSSEC Indeterminate",
+        6 => "DNSSEC Bogus",
+        7 => "Signature Expired",
+        8 => "Signature Not Yet Valid",
+        9 => "DNSKEY Missing",
+        10 => "RRSIGs Missing",
+        11 => "No Zone Key Bit Set",
+        12 => "NSEC Missing",
+        13 => "Cached Error",
+        14 => "Not Ready",
+        15 => "Blocked",
+        16 => "Censored",
+        17 => "Filtered",
+        18 => "Prohibited",
+        19 => "Stale NXDomain Answer",
+        20 => "Not Authoritative",
+        21 => "Not Supported",
+        22 => "No Reachable Authority",
+        23 => "Network Error",
+        24 => "Invalid Data",
+        25 => "Signature Expired before Valid",
+        26 => "Too Early",
+        27 => "Unsupported NSEC3 Iterations Value",
+        28 => "Unable to conform to policy",
+        29 => "Synthesized",
+        30 => "Invalid Query Type",
+        _ => "Unknown"
+    }
+}
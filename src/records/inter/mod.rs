@@ -3,3 +3,5 @@ pub mod opt_codes;
 pub mod svc_param_keys;
 pub mod naptr_flags;
 pub mod svc_param;
+pub mod rr_type_bitmap;
+pub mod edns_option;
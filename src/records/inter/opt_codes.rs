@@ -21,7 +21,8 @@ pub enum OptCodes {
     DnsSecValidated,
     AdaptiveDnsDiscovery,
     DoH,
-    MultiUserClientSubnet
+    MultiUserClientSubnet,
+    Unknown(u16)
 }
 
 impl OptCodes {
@@ -46,7 +47,8 @@ impl OptCodes {
             Self::DnsSecValidated => 18,
             Self::AdaptiveDnsDiscovery => 19,
             Self::DoH => 20,
-            Self::MultiUserClientSubnet => 21
+            Self::MultiUserClientSubnet => 21,
+            Self::Unknown(code) => *code
         }
     }
 }
@@ -86,7 +88,7 @@ impl TryFrom<u16> for OptCodes {
             19 => Self::AdaptiveDnsDiscovery,
             20 => Self::DoH,
             21 => Self::MultiUserClientSubnet,
-            _  => return Err(OptCodeParseError(v))
+            _  => Self::Unknown(v)
         })
     }
 }
@@ -113,7 +115,8 @@ impl fmt::Display for OptCodes {
             Self::DnsSecValidated => "DNSSEC_VALIDATED",
             Self::AdaptiveDnsDiscovery => "ADAPTIVE_DNS_DISCOVERY",
             Self::DoH => "DOH",
-            Self::MultiUserClientSubnet => "MULTI_USER_CLIENT_SUBNET"
+            Self::MultiUserClientSubnet => "MULTI_USER_CLIENT_SUBNET",
+            Self::Unknown(code) => return write!(f, "OPT{}", code)
         })
     }
 }
@@ -3,7 +3,11 @@ use crate::records::{
     ch_a_record::ChARecord,
     aaaa_record::AaaaRecord,
     cname_record::CNameRecord,
+    dname_record::DNameRecord,
     dnskey_record::DnsKeyRecord,
+    ds_record::DsRecord,
+    cds_record::CdsRecord,
+    cdnskey_record::CdnsKeyRecord,
     hinfo_record::HInfoRecord,
     https_record::HttpsRecord,
     loc_record::LocRecord,
@@ -11,9 +15,13 @@ use crate::records::{
     naptr_record::NaptrRecord,
     ns_record::NsRecord,
     nsec_record::NSecRecord,
+    nsec3_record::NSec3Record,
+    nsec3param_record::NSec3ParamRecord,
     ptr_record::PtrRecord,
     rrsig_record::RRSigRecord,
+    tlsa_record::TlsaRecord,
     smimea_record::SmimeaRecord,
+    openpgpkey_record::OpenPgpKeyRecord,
     soa_record::SoaRecord,
     srv_record::SrvRecord,
     tkey_record::TKeyRecord,
@@ -23,7 +31,9 @@ use crate::records::{
     txt_record::TxtRecord,
     opt_record::OptRecord,
     uri_record::UriRecord,
-    any_record::AnyRecord
+    caa_record::CaaRecord,
+    any_record::AnyRecord,
+    unknown_record::UnknownRecord
 };
 
 use std::any::Any;
@@ -58,6 +68,56 @@ pub trait RecordBase: Display + Debug + Send + Sync {
     fn as_any_mut(&mut self) -> &mut dyn Any;
     
     fn clone_box(&self) -> Box<dyn RecordBase>;
+
+    //RFC 4034 S6.2 - UNCOMPRESSED RDATA WITH THE EMBEDDED NAMES OF THE LISTED TYPES LOWERCASED
+    fn to_canonical_bytes(&self) -> Result<Vec<u8>, RecordError> {
+        //NOTHING PAST 0x3FFF IS EVER RECORDED AS A POINTER TARGET, SO NO NAME GETS COMPRESSED
+        let mut buf = self.to_bytes(&mut HashMap::new(), 0x4000)?;
+        if buf.len() <= 2 {
+            return Ok(buf);
+        }
+
+        match self.get_type() {
+            RRTypes::Ns | RRTypes::CName | RRTypes::Ptr | RRTypes::DName => {
+                lowercase_fqdn(&mut buf, 2);
+            }
+            RRTypes::Soa => {
+                let off = lowercase_fqdn(&mut buf, 2);
+                lowercase_fqdn(&mut buf, off);
+            }
+            RRTypes::Mx => {
+                lowercase_fqdn(&mut buf, 4);
+            }
+            RRTypes::Srv => {
+                lowercase_fqdn(&mut buf, 8);
+            }
+            RRTypes::RRSig => {
+                lowercase_fqdn(&mut buf, 20);
+            }
+            RRTypes::Naptr => {
+                let mut off = 6;
+                for _ in 0..3 {
+                    off += 1+*buf.get(off).unwrap_or(&0) as usize;
+                }
+                lowercase_fqdn(&mut buf, off);
+            }
+            _ => {}
+        }
+
+        Ok(buf)
+    }
+
+    //RFC 2136 S1.1.1 - SAME TYPE AND SAME RDATA, WITH NAMES COMPARED CASE-INSENSITIVELY
+    fn rdata_eq(&self, other: &dyn RecordBase) -> bool {
+        if self.get_type() != other.get_type() {
+            return false;
+        }
+
+        match (self.to_canonical_bytes(), other.to_canonical_bytes()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false
+        }
+    }
 }
 
 impl Clone for Box<dyn RecordBase> {
@@ -88,11 +148,19 @@ impl dyn RecordBase {
             RRTypes::Loc    => LocRecord::default().upcast(),
             RRTypes::Srv    => SrvRecord::default().upcast(),
             RRTypes::Naptr  => NaptrRecord::default().upcast(),
+            RRTypes::DName  => DNameRecord::default().upcast(),
+            RRTypes::Ds     => DsRecord::default().upcast(),
             RRTypes::SshFp  => SshFpRecord::default().upcast(),
             RRTypes::RRSig  => RRSigRecord::default().upcast(),
             RRTypes::Nsec   => NSecRecord::default().upcast(),
             RRTypes::DnsKey => DnsKeyRecord::default().upcast(),
+            RRTypes::Nsec3  => NSec3Record::default().upcast(),
+            RRTypes::Nsec3Param => NSec3ParamRecord::default().upcast(),
+            RRTypes::Tlsa   => TlsaRecord::default().upcast(),
             RRTypes::Smimea => SmimeaRecord::default().upcast(),
+            RRTypes::Cds    => CdsRecord::default().upcast(),
+            RRTypes::CdnsKey => CdnsKeyRecord::default().upcast(),
+            RRTypes::OpenPgpKey => OpenPgpKeyRecord::default().upcast(),
             RRTypes::Svcb   => SvcbRecord::default().upcast(),
             RRTypes::Https  => HttpsRecord::default().upcast(),
             /*
@@ -102,13 +170,8 @@ impl dyn RecordBase {
             RRTypes::TKey   => TKeyRecord::default().upcast(),
             RRTypes::TSig   => TSigRecord::default().upcast(),
             RRTypes::Uri    => UriRecord::default().upcast(),
-            /*RRTypes::Caa => {
-                todo!()
-            }
-            _ => {
-                todo!()
-            }
-            */
+            RRTypes::Caa    => CaaRecord::default().upcast(),
+            RRTypes::Unknown(_) => UnknownRecord::new(_type, Vec::new()).upcast(),
             // pseudo/unsupported types:
             _ => return None
         })
@@ -133,11 +196,19 @@ impl dyn RecordBase {
             RRTypes::Loc    => LocRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Srv    => SrvRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Naptr  => NaptrRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::DName  => DNameRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::Ds     => DsRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::SshFp  => SshFpRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::RRSig  => RRSigRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Nsec   => NSecRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::DnsKey => DnsKeyRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::Nsec3  => NSec3Record::from_bytes(buf, off)?.upcast(),
+            RRTypes::Nsec3Param => NSec3ParamRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::Tlsa   => TlsaRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Smimea => SmimeaRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::Cds    => CdsRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::CdnsKey => CdnsKeyRecord::from_bytes(buf, off)?.upcast(),
+            RRTypes::OpenPgpKey => OpenPgpKeyRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Svcb   => SvcbRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Https  => HttpsRecord::from_bytes(buf, off)?.upcast(),
             /*
@@ -147,16 +218,33 @@ impl dyn RecordBase {
             RRTypes::TKey   => TKeyRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::TSig   => TSigRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Uri    => UriRecord::from_bytes(buf, off)?.upcast(),
-            /*RRTypes::Caa => {
-                todo!()
-            }
-            _ => {
-                todo!()
-            }
-            */
+            RRTypes::Caa    => CaaRecord::from_bytes(buf, off)?.upcast(),
             RRTypes::Any    => AnyRecord::from_bytes(buf, off)?.upcast(),
             //RRTypes::Opt    => OptRecord::from_bytes(buf, off)?.upcast(),
-            _ => return Err(RecordError("rrtype could not produce a record".to_string()))
+            _ => {
+                let mut record = UnknownRecord::from_bytes(buf, off)?;
+                record.set_type(_type);
+                record.upcast()
+            }
         })
     }
 }
+
+fn lowercase_fqdn(buf: &mut [u8], off: usize) -> usize {
+    let mut off = off;
+
+    while off < buf.len() {
+        let length = buf[off] as usize;
+        off += 1;
+
+        if length == 0 {
+            break;
+        }
+
+        let end = (off+length).min(buf.len());
+        buf[off..end].make_ascii_lowercase();
+        off = end;
+    }
+
+    off
+}
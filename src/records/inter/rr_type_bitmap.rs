@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::RecordError;
+
+//RFC 4034 S4.1.2 - SHARED BY NSEC AND NSEC3
+pub fn from_bytes(buf: &[u8], off: usize, length: usize) -> Result<Vec<RRTypes>, RecordError> {
+    let mut rr_types = Vec::new();
+    let mut off = off;
+
+    while off < length {
+        if off+2 > length {
+            return Err(RecordError("truncated type bitmap window".to_string()));
+        }
+
+        let window = buf[off];
+        let bitmap_length = buf[off+1] as usize;
+
+        if bitmap_length == 0 || bitmap_length > 32 || off+2+bitmap_length > length {
+            return Err(RecordError("invalid type bitmap length".to_string()));
+        }
+
+        let bitmap = &buf[off+2..off+2+bitmap_length];
+
+        for (i, &byte) in bitmap.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (1 << (7 - bit)) != 0 {
+                    rr_types.push(RRTypes::try_from((window as u16) * 256 + (i as u16 * 8 + bit as u16))
+                        .map_err(|e| RecordError(e.to_string()))?);
+                }
+            }
+        }
+
+        off += 2+bitmap_length;
+    }
+
+    Ok(rr_types)
+}
+
+pub fn to_bytes(rr_types: &[RRTypes]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let mut windows: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+
+    for rr_type in rr_types {
+        let window = (rr_type.get_code() / 256) as u8;
+        let offset = (rr_type.get_code() % 256) as usize;
+        let byte_index = offset / 8;
+        let bit_index = 7 - (offset % 8);
+
+        windows.entry(window).or_insert_with(|| vec![0; 32])[byte_index] |= 1 << bit_index;
+    }
+
+    for (window, bitmap) in windows {
+        if let Some(non_zero_pos) = bitmap.iter().rposition(|&x| x != 0) {
+            let trimmed_bitmap = &bitmap[..=non_zero_pos];
+
+            buf.push(window);
+            buf.push(trimmed_bitmap.len() as u8);
+            buf.extend_from_slice(trimmed_bitmap);
+        }
+    }
+
+    buf
+}
@@ -31,6 +31,10 @@ impl SvcParams {
     pub fn from_bytes(key: SvcParamKeys, buf: &[u8]) -> Result<Self, SvcParamParseError> {
         Ok(match key {
             SvcParamKeys::Mandatory => {
+                if !buf.len().is_multiple_of(2) {
+                    return Err(SvcParamParseError("mandatory keys length must be a multiple of 2".to_string()));
+                }
+
                 let mut out = Vec::new();
                 for c in buf.chunks_exact(2) {
                     out.push(SvcParamKeys::try_from(u16::from_be_bytes([c[0], c[1]])).map_err(|e| SvcParamParseError(e.to_string()))?);
@@ -45,18 +49,39 @@ impl SvcParams {
                     let len = buf[off] as usize;
                     off += 1;
                     let end = off + len;
+                    if end > buf.len() {
+                        return Err(SvcParamParseError("alpn id runs past param length".to_string()));
+                    }
                     ids.push(buf[off..end].to_vec());
                     off = end;
                 }
                 Self::Alpn(ids)
             }
             SvcParamKeys::NoDefaultAlpn => Self::NoDefaultAlpn,
-            SvcParamKeys::Port => Self::Port(u16::from_be_bytes([buf[0], buf[1]])),
-            SvcParamKeys::Ipv4Hint => Self::Ipv4Hint(buf.chunks_exact(4)
+            SvcParamKeys::Port => {
+                if buf.len() != 2 {
+                    return Err(SvcParamParseError("port must be 2 bytes".to_string()));
+                }
+
+                Self::Port(u16::from_be_bytes([buf[0], buf[1]]))
+            }
+            SvcParamKeys::Ipv4Hint => {
+                if !buf.len().is_multiple_of(4) {
+                    return Err(SvcParamParseError("ipv4hint length must be a multiple of 4".to_string()));
+                }
+
+                Self::Ipv4Hint(buf.chunks_exact(4)
                     .map(|c| Ipv4Addr::new(c[0], c[1], c[2], c[3]))
-                    .collect()),
+                    .collect())
+            }
             SvcParamKeys::Ech => Self::Ech(buf.to_vec()),
-            SvcParamKeys::Ipv6Hint => Self::Ipv6Hint((buf.len() / 16) as u16, buf.to_vec())
+            SvcParamKeys::Ipv6Hint => {
+                if !buf.len().is_multiple_of(16) {
+                    return Err(SvcParamParseError("ipv6hint length must be a multiple of 16".to_string()));
+                }
+
+                Self::Ipv6Hint((buf.len() / 16) as u16, buf.to_vec())
+            }
         })
     }
 
@@ -35,11 +35,24 @@ impl Default for LocRecord {
 impl RecordBase for LocRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+18 != length {
+            return Err(RecordError("loc record must be 16 bytes".to_string()));
+        }
+
         let version = buf[off+2];
         let size = buf[off+3];
         let h_precision = buf[off+4];
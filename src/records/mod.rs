@@ -3,12 +3,18 @@ pub mod in_a_record;
 pub mod ch_a_record;
 pub mod aaaa_record;
 pub mod cname_record;
+pub mod dname_record;
 pub mod dnskey_record;
+pub mod ds_record;
+pub mod cds_record;
+pub mod cdnskey_record;
 pub mod svcb_record;
 pub mod https_record;
 pub mod mx_record;
 pub mod ns_record;
 pub mod nsec_record;
+pub mod nsec3_record;
+pub mod nsec3param_record;
 pub mod opt_record;
 pub mod ptr_record;
 pub mod hinfo_record;
@@ -23,5 +29,9 @@ pub mod txt_record;
 pub mod uri_record;
 pub mod loc_record;
 pub mod sshfp_record;
+pub mod tlsa_record;
 pub mod smimea_record;
+pub mod openpgpkey_record;
+pub mod caa_record;
 pub mod any_record;
+pub mod unknown_record;
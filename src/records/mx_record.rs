@@ -25,14 +25,30 @@ impl Default for MxRecord {
 impl RecordBase for MxRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+4 > length {
+            return Err(RecordError("truncated mx record".to_string()));
+        }
+
         let priority = u16::from_be_bytes([buf[off+2], buf[off+3]]);
 
-        let (server, _) = unpack_fqdn(buf, off+4);
+        let (server, server_length) = unpack_fqdn(buf, off+4).map_err(|e| RecordError(e.to_string()))?;
+        if off+4+server_length != length {
+            return Err(RecordError("server does not match record length".to_string()));
+        }
 
         Ok(Self {
             priority,
@@ -33,15 +33,32 @@ impl Default for NaptrRecord {
 impl RecordBase for NaptrRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+7 > length {
+            return Err(RecordError("truncated naptr record".to_string()));
+        }
+
         let order = u16::from_be_bytes([buf[off+2], buf[off+3]]);
         let preference = u16::from_be_bytes([buf[off+4], buf[off+5]]);
 
         let data_length = buf[off+6] as usize;
+        if off+7+data_length > length {
+            return Err(RecordError("flags run past record length".to_string()));
+        }
+
         let mut flags = Vec::new();
 
         for flag in String::from_utf8(buf[off + 7..off + 7 + data_length].to_vec())
@@ -58,18 +75,30 @@ impl RecordBase for NaptrRecord {
 
         let mut off = off+7+data_length;
 
+        if off >= length || off+1+buf[off] as usize > length {
+            return Err(RecordError("service runs past record length".to_string()));
+        }
+
         let data_length = buf[off] as usize;
         let service = String::from_utf8(buf[off + 1..off + 1 + data_length].to_vec())
             .map_err(|e| RecordError(e.to_string()))?;
 
         off += 1+data_length;
 
+        if off >= length || off+1+buf[off] as usize > length {
+            return Err(RecordError("regex runs past record length".to_string()));
+        }
+
         let data_length = buf[off] as usize;
         let regex = String::from_utf8(buf[off + 1..off + 1 + data_length].to_vec())
             .map_err(|e| RecordError(e.to_string()))?;
 
         off += 1+data_length;
 
+        if off >= length || off+1+buf[off] as usize > length {
+            return Err(RecordError("replacement runs past record length".to_string()));
+        }
+
         let data_length = buf[off] as usize;
         let replacement = String::from_utf8(buf[off + 1..off + 1 + data_length].to_vec())
             .map_err(|e| RecordError(e.to_string()))?;
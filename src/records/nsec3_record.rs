@@ -0,0 +1,272 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::records::inter::rr_type_bitmap;
+use crate::utils::{base32, hex, sha1};
+use crate::utils::fqdn_utils::pack_fqdn;
+
+#[derive(Clone, Debug)]
+pub struct NSec3Record {
+    pub(crate) hash_algorithm: u8,
+    pub(crate) flags: u8,
+    pub(crate) iterations: u16,
+    pub(crate) salt: Vec<u8>,
+    pub(crate) next_hashed: Vec<u8>,
+    pub(crate) rr_types: Vec<RRTypes>
+}
+
+impl Default for NSec3Record {
+
+    fn default() -> Self {
+        Self {
+            hash_algorithm: 0,
+            flags: 0,
+            iterations: 0,
+            salt: Vec::new(),
+            next_hashed: Vec::new(),
+            rr_types: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for NSec3Record {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+7 > length {
+            return Err(RecordError("truncated NSEC3 header".to_string()));
+        }
+
+        let hash_algorithm = buf[off+2];
+        let flags = buf[off+3];
+        let iterations = u16::from_be_bytes([buf[off+4], buf[off+5]]);
+
+        let salt_length = buf[off+6] as usize;
+        let off = off+7;
+        if off+salt_length+1 > length {
+            return Err(RecordError("salt runs past record length".to_string()));
+        }
+        let salt = buf[off..off+salt_length].to_vec();
+        let off = off+salt_length;
+
+        let hash_length = buf[off] as usize;
+        let off = off+1;
+        if hash_length == 0 || off+hash_length > length {
+            return Err(RecordError("invalid next hashed owner length".to_string()));
+        }
+        let next_hashed = buf[off..off+hash_length].to_vec();
+        let off = off+hash_length;
+
+        let rr_types = rr_type_bitmap::from_bytes(buf, off, length)?;
+
+        Ok(Self {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt,
+            next_hashed,
+            rr_types
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        if self.salt.len() > 255 {
+            return Err(RecordError("salt is longer than 255 bytes".to_string()));
+        }
+
+        if self.next_hashed.is_empty() || self.next_hashed.len() > 255 {
+            return Err(RecordError("invalid next hashed owner length".to_string()));
+        }
+
+        let mut buf = vec![0u8; 7];
+
+        buf[2] = self.hash_algorithm;
+        buf[3] = self.flags;
+        buf.splice(4..6, self.iterations.to_be_bytes());
+        buf[6] = self.salt.len() as u8;
+        buf.extend_from_slice(&self.salt);
+
+        buf.push(self.next_hashed.len() as u8);
+        buf.extend_from_slice(&self.next_hashed);
+
+        buf.extend_from_slice(&rr_type_bitmap::to_bytes(&self.rr_types));
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::Nsec3
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl NSec3Record {
+
+    pub fn new(hash_algorithm: u8, flags: u8, iterations: u16, salt: &[u8], next_hashed: &[u8], rr_types: Vec<RRTypes>) -> Self {
+        Self {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt: salt.to_vec(),
+            next_hashed: next_hashed.to_vec(),
+            rr_types
+        }
+    }
+
+    pub fn set_hash_algorithm(&mut self, hash_algorithm: u8) {
+        self.hash_algorithm = hash_algorithm;
+    }
+
+    pub fn get_hash_algorithm(&self) -> u8 {
+        self.hash_algorithm
+    }
+
+    pub fn set_flags(&mut self, flags: u8) {
+        self.flags = flags;
+    }
+
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn set_opt_out(&mut self, opt_out: bool) {
+        if opt_out {
+            self.flags |= 0x01;
+            return;
+        }
+
+        self.flags &= !0x01;
+    }
+
+    pub fn is_opt_out(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+
+    pub fn set_iterations(&mut self, iterations: u16) {
+        self.iterations = iterations;
+    }
+
+    pub fn get_iterations(&self) -> u16 {
+        self.iterations
+    }
+
+    pub fn set_salt(&mut self, salt: &[u8]) {
+        self.salt = salt.to_vec();
+    }
+
+    pub fn get_salt(&self) -> &[u8] {
+        self.salt.as_ref()
+    }
+
+    pub fn set_next_hashed(&mut self, next_hashed: &[u8]) {
+        self.next_hashed = next_hashed.to_vec();
+    }
+
+    pub fn get_next_hashed(&self) -> &[u8] {
+        self.next_hashed.as_ref()
+    }
+
+    pub fn add_rr_type(&mut self, rr_type: RRTypes) {
+        self.rr_types.push(rr_type);
+    }
+
+    pub fn get_rr_types(&self) -> &Vec<RRTypes> {
+        self.rr_types.as_ref()
+    }
+
+    pub fn get_rr_types_mut(&mut self) -> &mut Vec<RRTypes> {
+        self.rr_types.as_mut()
+    }
+
+    pub fn hash_owner(&self, owner: &str) -> Result<Vec<u8>, RecordError> {
+        match self.hash_algorithm {
+            1 => Ok(hash_owner(owner, &self.salt, self.iterations).to_vec()),
+            _ => Err(RecordError(format!("unsupported hash algorithm: {}", self.hash_algorithm)))
+        }
+    }
+}
+
+impl fmt::Display for NSec3Record {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<8}{} {} {} {} {} {}", self.get_type().to_string(),
+               self.hash_algorithm,
+               self.flags,
+               self.iterations,
+               salt_to_string(&self.salt),
+               base32::encode(&self.next_hashed),
+               self.rr_types.iter()
+                   .map(|t| t.to_string())
+                   .collect::<Vec<_>>()
+                   .join(" "))
+    }
+}
+
+//RFC 5155 S5 - IH(SALT, X, 0) = H(X || SALT), IH(SALT, X, K) = H(IH(SALT, X, K-1) || SALT)
+pub fn hash_owner(owner: &str, salt: &[u8], iterations: u16) -> [u8; 20] {
+    let mut buf = pack_fqdn(&owner.trim_end_matches('.').to_lowercase(), &mut HashMap::new(), 0, false);
+    buf.extend_from_slice(salt);
+    let mut digest = sha1::hash(&buf);
+
+    for _ in 0..iterations {
+        let mut buf = digest.to_vec();
+        buf.extend_from_slice(salt);
+        digest = sha1::hash(&buf);
+    }
+
+    digest
+}
+
+pub(crate) fn salt_to_string(salt: &[u8]) -> String {
+    match salt.is_empty() {
+        true => "-".to_string(),
+        false => hex::encode(salt)
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x21, 0x1, 0x1, 0x0, 0xc, 0x4, 0xaa, 0xbb, 0xcc, 0xdd, 0x14, 0x2, 0x97, 0x4c, 0x17, 0x5a, 0x3e,
+                    0xc2, 0xd7, 0x6b, 0x8a, 0x9a, 0xb4, 0x16, 0x6a, 0xdd, 0xc8, 0x64, 0x44, 0x6d, 0x9e, 0x0, 0x1, 0x40 ];
+    let record = NSec3Record::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert!(record.is_opt_out());
+    assert_eq!(record.rr_types, vec![RRTypes::A]);
+
+    //RFC 5155 APPENDIX A
+    assert_eq!(base32::encode(&hash_owner("example.", &[0xaa, 0xbb, 0xcc, 0xdd], 12)), "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+    assert_eq!(base32::encode(&hash_owner("a.example", &[0xaa, 0xbb, 0xcc, 0xdd], 12)), "35mthgpgcu1qg68fab165klnsnk3dpvl");
+}
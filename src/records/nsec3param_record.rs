@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::records::nsec3_record::{hash_owner, salt_to_string};
+
+#[derive(Clone, Debug)]
+pub struct NSec3ParamRecord {
+    pub(crate) hash_algorithm: u8,
+    pub(crate) flags: u8,
+    pub(crate) iterations: u16,
+    pub(crate) salt: Vec<u8>
+}
+
+impl Default for NSec3ParamRecord {
+
+    fn default() -> Self {
+        Self {
+            hash_algorithm: 0,
+            flags: 0,
+            iterations: 0,
+            salt: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for NSec3ParamRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+7 > length {
+            return Err(RecordError("truncated NSEC3PARAM header".to_string()));
+        }
+
+        let hash_algorithm = buf[off+2];
+        let flags = buf[off+3];
+        let iterations = u16::from_be_bytes([buf[off+4], buf[off+5]]);
+
+        let salt_length = buf[off+6] as usize;
+        let off = off+7;
+        if off+salt_length != length {
+            return Err(RecordError("salt length does not match record length".to_string()));
+        }
+        let salt = buf[off..off+salt_length].to_vec();
+
+        Ok(Self {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        if self.salt.len() > 255 {
+            return Err(RecordError("salt is longer than 255 bytes".to_string()));
+        }
+
+        let mut buf = vec![0u8; 7];
+
+        buf[2] = self.hash_algorithm;
+        buf[3] = self.flags;
+        buf.splice(4..6, self.iterations.to_be_bytes());
+        buf[6] = self.salt.len() as u8;
+        buf.extend_from_slice(&self.salt);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::Nsec3Param
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl NSec3ParamRecord {
+
+    pub fn new(hash_algorithm: u8, flags: u8, iterations: u16, salt: &[u8]) -> Self {
+        Self {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt: salt.to_vec()
+        }
+    }
+
+    pub fn set_hash_algorithm(&mut self, hash_algorithm: u8) {
+        self.hash_algorithm = hash_algorithm;
+    }
+
+    pub fn get_hash_algorithm(&self) -> u8 {
+        self.hash_algorithm
+    }
+
+    pub fn set_flags(&mut self, flags: u8) {
+        self.flags = flags;
+    }
+
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
+
+    pub fn set_iterations(&mut self, iterations: u16) {
+        self.iterations = iterations;
+    }
+
+    pub fn get_iterations(&self) -> u16 {
+        self.iterations
+    }
+
+    pub fn set_salt(&mut self, salt: &[u8]) {
+        self.salt = salt.to_vec();
+    }
+
+    pub fn get_salt(&self) -> &[u8] {
+        self.salt.as_ref()
+    }
+
+    pub fn hash_owner(&self, owner: &str) -> Result<Vec<u8>, RecordError> {
+        match self.hash_algorithm {
+            1 => Ok(hash_owner(owner, &self.salt, self.iterations).to_vec()),
+            _ => Err(RecordError(format!("unsupported hash algorithm: {}", self.hash_algorithm)))
+        }
+    }
+}
+
+impl fmt::Display for NSec3ParamRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<7} {} {} {} {}", self.get_type().to_string(),
+               self.hash_algorithm,
+               self.flags,
+               self.iterations,
+               salt_to_string(&self.salt))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x9, 0x1, 0x0, 0x0, 0xc, 0x4, 0xaa, 0xbb, 0xcc, 0xdd ];
+    let record = NSec3ParamRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert_eq!(record.to_string(), "NSEC3PARAM 1 0 12 aabbccdd");
+}
@@ -1,29 +1,23 @@
 use std::any::Any;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::records::inter::rr_type_bitmap;
 use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
 
 #[derive(Clone, Debug)]
 pub struct NSecRecord {
-    class: RRClasses,
-    cache_flush: bool,
-    ttl: u32,
-    fqdn: Option<String>,
-    rr_types: Vec<u16>
+    pub(crate) next_domain: Option<String>,
+    pub(crate) rr_types: Vec<RRTypes>
 }
 
 impl Default for NSecRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            cache_flush: false,
-            ttl: 0,
-            fqdn: None,
+            next_domain: None,
             rr_types: Vec::new()
         }
     }
@@ -32,85 +26,43 @@ impl Default for NSecRecord {
 impl RecordBase for NSecRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut off = off;
-
-        let class = u16::from_be_bytes([buf[off], buf[off+1]]);
-        let cache_flush = (class & 0x8000) != 0;
-        let class = RRClasses::try_from(class & 0x7FFF).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
-
-        let (fqdn, length) = unpack_fqdn(buf, off+8);
-
-        let data_length = off+8+u16::from_be_bytes([buf[off+6], buf[off+7]]) as usize;
-        off += length+8;
-
-        let mut rr_types = Vec::new();
-
-        while off < data_length {
-            let window = buf[off];
-            let length = buf[off + 1] as usize;
-
-            if off+2+length > data_length {
-                break;
-            }
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
 
-            let bitmap = &buf[off + 2..off + 2 + length];
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
 
-            for (i, &byte) in bitmap.iter().enumerate() {
-                for bit in 0..8 {
-                    if byte & (1 << (7 - bit)) != 0 {
-                        rr_types.push((window as u16) * 256 + (i as u16 * 8 + bit as u16));
-                    }
-                }
-            }
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
 
-            off += 2+length;
+        let (next_domain, next_domain_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        let off = off+2+next_domain_length;
+        if off > length {
+            return Err(RecordError("next_domain runs past record length".to_string()));
         }
 
+        let rr_types = rr_type_bitmap::from_bytes(buf, off, length)?;
+
         Ok(Self {
-            class,
-            cache_flush,
-            ttl,
-            fqdn: Some(fqdn),
+            next_domain: Some(next_domain),
             rr_types
         })
     }
 
     fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 8];
-
-        let mut class = self.class.get_code();
-        if self.cache_flush {
-            class = class | 0x8000;
-        }
+        let mut buf = vec![0u8; 2];
 
-        buf.splice(0..2, class.to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
+        buf.extend_from_slice(&pack_fqdn(self.next_domain.as_ref()
+            .ok_or_else(|| RecordError("next_domain param was not set".to_string()))?, compression_data, off+2, false));
 
-        buf.extend_from_slice(&pack_fqdn(self.fqdn.as_ref().unwrap().as_str(), compression_data, off+10, true));
+        buf.extend_from_slice(&rr_type_bitmap::to_bytes(&self.rr_types));
 
-        let mut windows: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
-
-        for rr_type in &self.rr_types {
-            let window = (rr_type / 256) as u8;
-            let offset = (rr_type % 256) as usize;
-            let byte_index = offset / 8;
-            let bit_index = 7 - (offset % 8);
-
-            windows.entry(window).or_insert_with(|| vec![0; 32])[byte_index] |= 1 << bit_index;
-        }
-
-        for (window, bitmap) in windows {
-            if let Some(non_zero_pos) = bitmap.iter().rposition(|&x| x != 0) {
-                let trimmed_bitmap = &bitmap[..=non_zero_pos];
-
-                buf.push(window);
-                buf.push(trimmed_bitmap.len() as u8);
-                buf.extend_from_slice(trimmed_bitmap);
-            }
-        }
-
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -138,42 +90,49 @@ impl RecordBase for NSecRecord {
 
 impl NSecRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(next_domain: &str, rr_types: Vec<RRTypes>) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            next_domain: Some(next_domain.to_string()),
+            rr_types
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
-    }
-
-    pub fn get_class(&self) -> RRClasses {
-        self.class
+    pub fn set_next_domain(&mut self, next_domain: &str) {
+        self.next_domain = Some(next_domain.to_string());
     }
 
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
+    pub fn get_next_domain(&self) -> Option<&String> {
+        self.next_domain.as_ref()
     }
 
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
+    pub fn add_rr_type(&mut self, rr_type: RRTypes) {
+        self.rr_types.push(rr_type);
     }
 
-    pub fn set_fqdn(&mut self, fqdn: &str) {
-        self.fqdn = Some(fqdn.to_string());
+    pub fn get_rr_types(&self) -> &Vec<RRTypes> {
+        self.rr_types.as_ref()
     }
 
-    pub fn get_fqdn(&self) -> Option<&String> {
-        self.fqdn.as_ref()
+    pub fn get_rr_types_mut(&mut self) -> &mut Vec<RRTypes> {
+        self.rr_types.as_mut()
     }
 }
 
 impl fmt::Display for NSecRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "type {:?}, class {:?}, domain {}", self.get_type(), self.class, self.fqdn.as_ref().unwrap())
+        write!(f, "{:<8}{} {}", self.get_type().to_string(),
+               format!("{}.", self.next_domain.as_ref().unwrap_or(&String::new())),
+               self.rr_types.iter()
+                   .map(|t| t.to_string())
+                   .collect::<Vec<_>>()
+                   .join(" "))
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x18, 0x4, 0x68, 0x6f, 0x73, 0x74, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0, 0x0, 0x6, 0x40, 0x1, 0x0, 0x0, 0x0, 0x3 ];
+    let record = NSecRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
@@ -0,0 +1,116 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::records::smimea_record::hash_local_part;
+use crate::utils::base64;
+
+#[derive(Clone, Debug)]
+pub struct OpenPgpKeyRecord {
+    pub(crate) public_key: Vec<u8>
+}
+
+impl Default for OpenPgpKeyRecord {
+
+    fn default() -> Self {
+        Self {
+            public_key: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for OpenPgpKeyRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let public_key = buf[off+2..length].to_vec();
+
+        Ok(Self {
+            public_key
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 2];
+
+        buf.extend_from_slice(&self.public_key);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::OpenPgpKey
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl OpenPgpKeyRecord {
+
+    pub fn new(public_key: Vec<u8>) -> Self {
+        Self {
+            public_key
+        }
+    }
+
+    pub fn set_public_key(&mut self, public_key: &[u8]) {
+        self.public_key = public_key.to_vec();
+    }
+
+    pub fn get_public_key(&self) -> &[u8] {
+        self.public_key.as_ref()
+    }
+
+    pub fn owner_name(local_part: &str, domain: &str) -> String {
+        format!("{}._openpgpkey.{}", hash_local_part(local_part), domain.trim_end_matches('.'))
+    }
+}
+
+impl fmt::Display for OpenPgpKeyRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<7} {}", self.get_type().to_string(),
+               base64::encode(&self.public_key))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x8, 0x99, 0x0, 0xd, 0x4, 0x5f, 0x3b, 0x2c, 0x1e ];
+    let record = OpenPgpKeyRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+
+    //RFC 7929 S3
+    assert_eq!(OpenPgpKeyRecord::owner_name("hugh", "example.com"), "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._openpgpkey.example.com");
+}
@@ -2,12 +2,11 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::IpAddr;
 use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::edns_option::EdnsOption;
 use crate::records::inter::opt_codes::OptCodes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
-use crate::utils::hex;
-use crate::utils::index_map::IndexMap;
 
 #[derive(Clone, Debug)]
 pub struct OptRecord {
@@ -15,7 +14,7 @@ pub struct OptRecord {
     ext_rcode: u8,
     version: u8,
     flags: u16,
-    options: IndexMap<OptCodes, Vec<u8>>
+    options: Vec<EdnsOption>
 }
 
 impl Default for OptRecord {
@@ -26,7 +25,7 @@ impl Default for OptRecord {
             ext_rcode: 0,
             version: 0,
             flags: 0x8000,
-            options: IndexMap::new()
+            options: Vec::new()
         }
     }
 }
@@ -34,19 +33,35 @@ impl Default for OptRecord {
 impl RecordBase for OptRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+8 > buf.len() {
+            return Err(RecordError("truncated opt record".to_string()));
+        }
+
         let payload_size = u16::from_be_bytes([buf[off], buf[off+1]]);
         let ext_rcode = buf[off+2];
         let version = buf[off+3];
         let flags = u16::from_be_bytes([buf[off+4], buf[off+5]]);
 
         let data_length = off+8+u16::from_be_bytes([buf[off+6], buf[off+7]]) as usize;
+        if data_length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
         let mut off = off+8;
-        let mut options = IndexMap::new();
+        let mut options = Vec::new();
 
         while off < data_length {
-            let opt_code = OptCodes::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).unwrap();
+            if off+4 > data_length {
+                return Err(RecordError("truncated option header".to_string()));
+            }
+
+            let opt_code = OptCodes::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).map_err(|e| RecordError(e.to_string()))?;
             let length = u16::from_be_bytes([buf[off+2], buf[off+3]]) as usize;
-            options.insert(opt_code, buf[off + 4..off + 4 + length].to_vec());
+            if off+4+length > data_length {
+                return Err(RecordError("option data runs past record length".to_string()));
+            }
+
+            options.push(EdnsOption::from_bytes(opt_code, &buf[off + 4..off + 4 + length]).map_err(|e| RecordError(e.to_string()))?);
 
             off += 4+length;
         }
@@ -70,10 +85,11 @@ impl RecordBase for OptRecord {
 
         buf.splice(4..6, self.flags.to_be_bytes());
 
-        for (code, option) in self.options.iter() {
-            buf.extend_from_slice(&code.get_code().to_be_bytes());
-            buf.extend_from_slice(&(option.len() as u16).to_be_bytes());
-            buf.extend_from_slice(&option);
+        for option in self.options.iter() {
+            let data = option.to_bytes();
+            buf.extend_from_slice(&option.get_code().get_code().to_be_bytes());
+            buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            buf.extend_from_slice(&data);
         }
 
         buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
@@ -104,7 +120,7 @@ impl RecordBase for OptRecord {
 
 impl OptRecord {
 
-    pub fn new(payload_size: u16, ext_rcode: u8, version: u8, flags: u16, options: IndexMap<OptCodes, Vec<u8>>) -> Self {
+    pub fn new(payload_size: u16, ext_rcode: u8, version: u8, flags: u16, options: Vec<EdnsOption>) -> Self {
         Self {
             payload_size,
             ext_rcode,
@@ -146,28 +162,171 @@ impl OptRecord {
         self.flags
     }
 
-    pub fn has_option(&mut self, code: &OptCodes) -> bool {
-        self.options.contains_key(code)
+    pub fn set_dnssec_ok(&mut self, dnssec_ok: bool) {
+        match dnssec_ok {
+            true => self.flags |= 0x8000,
+            false => self.flags &= !0x8000
+        }
+    }
+
+    pub fn is_dnssec_ok(&self) -> bool {
+        (self.flags & 0x8000) != 0
+    }
+
+    pub fn has_option(&self, code: &OptCodes) -> bool {
+        self.options.iter().any(|o| o.get_code().eq(code))
+    }
+
+    pub fn add_option(&mut self, option: EdnsOption) {
+        self.options.push(option);
+    }
+
+    pub fn set_option(&mut self, option: EdnsOption) {
+        let code = option.get_code();
+
+        match self.options.iter().position(|o| o.get_code().eq(&code)) {
+            Some(i) => {
+                self.options[i] = option;
+                let mut j = i+1;
+                while j < self.options.len() {
+                    if self.options[j].get_code().eq(&code) {
+                        self.options.remove(j);
+                        continue;
+                    }
+                    j += 1;
+                }
+            }
+            None => self.options.push(option)
+        }
+    }
+
+    pub fn remove_option(&mut self, code: &OptCodes) {
+        self.options.retain(|o| !o.get_code().eq(code));
+    }
+
+    pub fn get_option(&self, code: &OptCodes) -> Option<&EdnsOption> {
+        self.options.iter().find(|o| o.get_code().eq(code))
+    }
+
+    pub fn get_option_mut(&mut self, code: &OptCodes) -> Option<&mut EdnsOption> {
+        self.options.iter_mut().find(|o| o.get_code().eq(code))
+    }
+
+    pub fn get_options(&self) -> &Vec<EdnsOption> {
+        self.options.as_ref()
+    }
+
+    pub fn get_options_mut(&mut self) -> &mut Vec<EdnsOption> {
+        self.options.as_mut()
+    }
+
+    pub fn set_client_subnet(&mut self, address: IpAddr, source_prefix: u8, scope_prefix: u8) {
+        self.set_option(EdnsOption::Ecs {
+            address,
+            source_prefix,
+            scope_prefix
+        });
+    }
+
+    pub fn get_client_subnet(&self) -> Option<(IpAddr, u8, u8)> {
+        match self.get_option(&OptCodes::Ecs)? {
+            EdnsOption::Ecs { address, source_prefix, scope_prefix } => Some((*address, *source_prefix, *scope_prefix)),
+            _ => None
+        }
+    }
+
+    pub fn set_cookie(&mut self, client: [u8; 8], server: Option<Vec<u8>>) {
+        self.set_option(EdnsOption::Cookie {
+            client,
+            server
+        });
+    }
+
+    pub fn get_cookie(&self) -> Option<(&[u8; 8], Option<&Vec<u8>>)> {
+        match self.get_option(&OptCodes::Cookie)? {
+            EdnsOption::Cookie { client, server } => Some((client, server.as_ref())),
+            _ => None
+        }
+    }
+
+    pub fn add_extended_error(&mut self, info_code: u16, extra_text: &str) {
+        self.add_option(EdnsOption::Ede {
+            info_code,
+            extra_text: extra_text.to_string()
+        });
+    }
+
+    pub fn get_extended_errors(&self) -> Vec<(u16, &str)> {
+        self.options.iter().filter_map(|o| match o {
+            EdnsOption::Ede { info_code, extra_text } => Some((*info_code, extra_text.as_str())),
+            _ => None
+        }).collect()
     }
 
-    pub fn insert_option(&mut self, code: OptCodes, option: Vec<u8>) {
-        self.options.insert(code, option);
+    pub fn set_padding(&mut self, length: u16) {
+        self.set_option(EdnsOption::Padding(length));
     }
 
-    pub fn get_option(&self, code: &OptCodes) -> Option<&Vec<u8>> {
-        self.options.get(code)
+    pub fn get_padding(&self) -> Option<u16> {
+        match self.get_option(&OptCodes::Padding)? {
+            EdnsOption::Padding(length) => Some(*length),
+            _ => None
+        }
     }
 
-    pub fn get_option_mut(&mut self, code: &OptCodes) -> Option<&mut Vec<u8>> {
-        self.options.get_mut(code)
+    pub fn set_tcp_keepalive(&mut self, timeout: Option<u16>) {
+        self.set_option(EdnsOption::TcpKeepalive(timeout));
     }
 
-    pub fn get_options(&self) -> impl Iterator<Item = (&OptCodes, &Vec<u8>)> {
-        self.options.iter()
+    pub fn get_tcp_keepalive(&self) -> Option<Option<u16>> {
+        match self.get_option(&OptCodes::TcpKeepalive)? {
+            EdnsOption::TcpKeepalive(timeout) => Some(*timeout),
+            _ => None
+        }
     }
 
-    pub fn get_options_mut(&mut self) -> impl Iterator<Item = (&OptCodes, &mut Vec<u8>)> {
-        self.options.iter_mut()
+    pub fn set_nsid(&mut self, nsid: &[u8]) {
+        self.set_option(EdnsOption::Nsid(nsid.to_vec()));
+    }
+
+    pub fn get_nsid(&self) -> Option<&[u8]> {
+        match self.get_option(&OptCodes::Nsid)? {
+            EdnsOption::Nsid(nsid) => Some(nsid.as_ref()),
+            _ => None
+        }
+    }
+
+    pub fn set_expire(&mut self, expire: Option<u32>) {
+        self.set_option(EdnsOption::Expire(expire));
+    }
+
+    pub fn get_expire(&self) -> Option<Option<u32>> {
+        match self.get_option(&OptCodes::Expire)? {
+            EdnsOption::Expire(expire) => Some(*expire),
+            _ => None
+        }
+    }
+
+    pub fn set_chain(&mut self, fqdn: &str) {
+        self.set_option(EdnsOption::Chain(fqdn.to_string()));
+    }
+
+    pub fn get_chain(&self) -> Option<&str> {
+        match self.get_option(&OptCodes::Chain)? {
+            EdnsOption::Chain(fqdn) => Some(fqdn.as_str()),
+            _ => None
+        }
+    }
+
+    pub fn set_key_tags(&mut self, tags: Vec<u16>) {
+        self.set_option(EdnsOption::KeyTag(tags));
+    }
+
+    pub fn get_key_tags(&self) -> Option<&Vec<u16>> {
+        match self.get_option(&OptCodes::KeyTag)? {
+            EdnsOption::KeyTag(tags) => Some(tags),
+            _ => None
+        }
     }
 }
 
@@ -176,31 +335,26 @@ impl fmt::Display for OptRecord {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "; EDNS: version: {}, flags: {}; udp: {}", self.version, self.flags, self.payload_size)?;
 
-        for (code, option) in self.options.iter() {
-            match code {
-                OptCodes::Ecs => {
-                    if option.len() >= 4 {
-                        let family = u16::from_be_bytes([option[0], option[1]]);
-                        let src_prefix = option[2];
-                        let scope_prefix = option[3];
-                        let addr = &option[4..];
-
-                        let ip_str = match family {
-                            1 => format!("{}", Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3])),
-                            2 => format!("{}", Ipv6Addr::from(<[u8; 16]>::try_from(addr).unwrap_or_default())),
-                            _ => format!("unknown family {}", family),
-                        };
-
-                        write!(f, "\r\n; {code}: {ip_str}/{src_prefix}/{scope_prefix}")?;
-
-                    } else {
-                        write!(f, "\r\n; {code}: (invalid)")?;
-                    }
-                }
-                _ => write!(f, "\r\n; {code}: {}", hex::encode(option))?
-            }
+        for option in self.options.iter() {
+            write!(f, "\r\n; {}: {}", option.get_code(), option)?;
         }
 
         Ok(())
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x4, 0xd0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2d, 0x0, 0x8, 0x0, 0x7, 0x0, 0x1, 0x18, 0x0, 0xc0, 0xa8, 0x1, 0x0, 0xa, 0x0, 0x8, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x0, 0xf, 0x0, 0x6, 0x0, 0x12, 0x6e, 0x6f, 0x70, 0x65, 0xfd, 0xe9, 0x0, 0x2, 0xbe, 0xef, 0x0, 0xc, 0x0, 0x2, 0x0, 0x0 ];
+    let record = OptRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(record.get_client_subnet(), Some(("192.168.1.0".parse().unwrap(), 24, 0)));
+    assert_eq!(record.get_cookie(), Some((&[1, 2, 3, 4, 5, 6, 7, 8], None)));
+    assert_eq!(record.get_extended_errors(), vec![(18, "nope")]);
+    assert_eq!(record.get_option(&OptCodes::Unknown(65001)), Some(&EdnsOption::Unknown(65001, vec![0xbe, 0xef])));
+    assert_eq!(record.get_padding(), Some(2));
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+
+    let mut invalid = buf.clone();
+    invalid[14] = 0x17;
+    assert!(OptRecord::from_bytes(&invalid, 0).is_err());
+}
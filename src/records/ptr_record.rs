@@ -2,16 +2,12 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
 use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
 
 #[derive(Clone, Debug)]
 pub struct PtrRecord {
-    class: RRClasses,
-    cache_flush: bool,
-    ttl: u32,
     pub(crate) fqdn: Option<String>
 }
 
@@ -19,9 +15,6 @@ impl Default for PtrRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            cache_flush: false,
-            ttl: 0,
             fqdn: None
         }
     }
@@ -30,37 +23,37 @@ impl Default for PtrRecord {
 impl RecordBase for PtrRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let class = u16::from_be_bytes([buf[off], buf[off+1]]);
-        let cache_flush = (class & 0x8000) != 0;
-        let class = RRClasses::try_from(class & 0x7FFF).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
 
-        //let z = u16::from_be_bytes([buf[off+6], buf[off+7]]);
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
 
-        let (fqdn, _) = unpack_fqdn(buf, off+8);
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let (fqdn, fqdn_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        if off+2+fqdn_length != length {
+            return Err(RecordError("fqdn does not match record length".to_string()));
+        }
 
         Ok(Self {
-            class,
-            cache_flush,
-            ttl,
             fqdn: Some(fqdn)
         })
     }
 
     fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 8];
+        let mut buf = vec![0u8; 2];
 
-        let mut class = self.class.get_code();
-        if self.cache_flush {
-            class = class | 0x8000;
-        }
-
-        buf.splice(0..2, class.to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
+        buf.extend_from_slice(&pack_fqdn(self.fqdn.as_ref()
+            .ok_or_else(|| RecordError("fqdn param was not set".to_string()))?, compression_data, off+2, true));
 
-        buf.extend_from_slice(&pack_fqdn(self.fqdn.as_ref().unwrap().as_str(), compression_data, off+8, true));
-
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -88,30 +81,12 @@ impl RecordBase for PtrRecord {
 
 impl PtrRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(fqdn: &str) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            fqdn: Some(fqdn.to_string())
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
-    }
-
-    pub fn get_class(&self) -> RRClasses {
-        self.class
-    }
-
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
-    }
-
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
-    }
-
     pub fn set_fqdn(&mut self, fqdn: &str) {
         self.fqdn = Some(fqdn.to_string());
     }
@@ -124,9 +99,14 @@ impl PtrRecord {
 impl fmt::Display for PtrRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:<8}{:<8}{:<8}{}", self.ttl,
-               self.class.to_string(),
-               self.get_type().to_string(),
-               format!("{}.", self.fqdn.as_ref().unwrap()))
+        write!(f, "{:<8}{}", self.get_type().to_string(),
+               format!("{}.", self.fqdn.as_ref().unwrap_or(&String::new())))
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0xb, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0 ];
+    let record = PtrRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
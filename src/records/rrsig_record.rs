@@ -41,11 +41,24 @@ impl Default for RRSigRecord {
 impl RecordBase for RRSigRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+20 > length {
+            return Err(RecordError("truncated rrsig record".to_string()));
+        }
+
         let type_covered = RRTypes::try_from(u16::from_be_bytes([buf[off+2], buf[off+3]]))
             .map_err(|e| RecordError(e.to_string()))?;
 
@@ -57,9 +70,10 @@ impl RecordBase for RRSigRecord {
         let inception = u32::from_be_bytes([buf[off+14], buf[off+15], buf[off+16], buf[off+17]]);
         let key_tag = u16::from_be_bytes([buf[off+18], buf[off+19]]);
 
-        let (signer_name, signer_name_length) = unpack_fqdn(buf, off+20);
-
-        length += off+2;
+        let (signer_name, signer_name_length) = unpack_fqdn(buf, off+20).map_err(|e| RecordError(e.to_string()))?;
+        if off+20+signer_name_length > length {
+            return Err(RecordError("signer_name runs past record length".to_string()));
+        }
 
         let signature = buf[off+20+signer_name_length..length].to_vec();
 
@@ -77,7 +91,7 @@ impl RecordBase for RRSigRecord {
     }
 
     fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 26];
+        let mut buf = vec![0u8; 20];
 
         buf.splice(2..4, self.type_covered.get_code().to_be_bytes());
 
@@ -4,7 +4,7 @@ use std::fmt;
 use std::fmt::Formatter;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
-use crate::utils::hex;
+use crate::utils::{hex, sha256};
 
 #[derive(Clone, Debug)]
 pub struct SmimeaRecord {
@@ -29,17 +29,28 @@ impl Default for SmimeaRecord {
 impl RecordBase for SmimeaRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+5 > length {
+            return Err(RecordError("truncated smimea record".to_string()));
+        }
+
         let usage = buf[off+2];
         let selector = buf[off+3];
         let matching_type = buf[off+4];
 
-        length += off+2;
-
         let certificate = buf[off+5..length].to_vec();
 
         Ok(Self {
@@ -127,6 +138,10 @@ impl SmimeaRecord {
     pub fn get_certificate(&self) -> &[u8] {
         self.certificate.as_ref()
     }
+
+    pub fn owner_name(local_part: &str, domain: &str) -> String {
+        format!("{}._smimecert.{}", hash_local_part(local_part), domain.trim_end_matches('.'))
+    }
 }
 
 impl fmt::Display for SmimeaRecord {
@@ -140,9 +155,15 @@ impl fmt::Display for SmimeaRecord {
     }
 }
 
+//RFC 7929 S3 / RFC 8162 S3 - SHA2-256 OF THE LOCAL PART TRUNCATED TO 28 OCTETS
+pub fn hash_local_part(local_part: &str) -> String {
+    hex::encode(&sha256::hash(local_part.as_bytes())[..28])
+}
+
 #[test]
 fn test() {
     let buf = vec![ 0x0, 0x1a, 0x1, 0x2, 0x3, 0x30, 0x25, 0x1f, 0xd9, 0x47, 0x7c, 0xfd, 0x17, 0x6a, 0x98, 0x3a, 0x34, 0xe1, 0x90, 0xbb, 0x7d, 0xa3, 0xc2, 0xf3, 0x7c, 0xa, 0xba, 0x95 ];
     let record = SmimeaRecord::from_bytes(&buf, 0).unwrap();
     assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert_eq!(SmimeaRecord::owner_name("hugh", "example.com."), "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._smimecert.example.com");
 }
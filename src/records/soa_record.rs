@@ -35,17 +35,30 @@ impl Default for SoaRecord {
 impl RecordBase for SoaRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
-        let (fqdn, data_length) = unpack_fqdn(buf, off+2);
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        let (fqdn, data_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
         let mut off = off+data_length+2;
 
-        let (mailbox, data_length) = unpack_fqdn(buf, off);
+        let (mailbox, data_length) = unpack_fqdn(buf, off).map_err(|e| RecordError(e.to_string()))?;
         off += data_length;
 
+        if off+20 != length {
+            return Err(RecordError("soa timers do not match record length".to_string()));
+        }
+
         let serial = u32::from_be_bytes([buf[off], buf[off+1], buf[off+2], buf[off+3]]);
         let refresh = u32::from_be_bytes([buf[off+4], buf[off+5], buf[off+6], buf[off+7]]);
         let retry = u32::from_be_bytes([buf[off+8], buf[off+9], buf[off+10], buf[off+11]]);
@@ -68,12 +81,14 @@ impl RecordBase for SoaRecord {
 
         let mut buf = vec![0u8; 2];
 
-        let fqdn = pack_fqdn(self.fqdn.as_ref().unwrap().as_str(), compression_data, off+2, true);
+        let fqdn = pack_fqdn(self.fqdn.as_ref()
+            .ok_or_else(|| RecordError("fqdn param was not set".to_string()))?, compression_data, off+2, true);
         buf.extend_from_slice(&fqdn);
 
-        off += fqdn.len()+8;
+        off += fqdn.len()+2;
 
-        let mailbox = pack_fqdn(self.mailbox.as_ref().unwrap().as_str(), compression_data, off, true);
+        let mailbox = pack_fqdn(self.mailbox.as_ref()
+            .ok_or_else(|| RecordError("mailbox param was not set".to_string()))?, compression_data, off, true);
         buf.extend_from_slice(&mailbox);
 
         buf.extend_from_slice(&self.serial.to_be_bytes());
@@ -195,7 +210,7 @@ impl fmt::Display for SoaRecord {
 
 #[test]
 fn test() {
-    let buf = vec![ 0x0, 0x34, 0x3, 0x6e, 0x73, 0x31, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0, 0x5, 0x61, 0x64, 0x6d, 0x69, 0x6e, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0, 0x0, 0x0, 0x0, 0x4, 0x0, 0x9, 0x3a, 0x80, 0x0, 0x1, 0x51, 0x80, 0x0, 0x24, 0xea, 0x0, 0x0, 0x9, 0x3a, 0x80 ];
+    let buf = vec![ 0x0, 0x2b, 0x3, 0x6e, 0x73, 0x31, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0, 0x5, 0x61, 0x64, 0x6d, 0x69, 0x6e, 0xc0, 0x6, 0x0, 0x0, 0x0, 0x4, 0x0, 0x9, 0x3a, 0x80, 0x0, 0x1, 0x51, 0x80, 0x0, 0x24, 0xea, 0x0, 0x0, 0x9, 0x3a, 0x80 ];
     let record = SoaRecord::from_bytes(&buf, 0).unwrap();
     assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
 }
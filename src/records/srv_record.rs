@@ -29,16 +29,32 @@ impl Default for SrvRecord {
 impl RecordBase for SrvRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+8 > length {
+            return Err(RecordError("truncated srv record".to_string()));
+        }
+
         let priority = u16::from_be_bytes([buf[off+2], buf[off+3]]);
         let weight = u16::from_be_bytes([buf[off+4], buf[off+5]]);
         let port = u16::from_be_bytes([buf[off+6], buf[off+7]]);
 
-        let (target, _) = unpack_fqdn(buf, off+8);
+        let (target, target_length) = unpack_fqdn(buf, off+8).map_err(|e| RecordError(e.to_string()))?;
+        if off+8+target_length != length {
+            return Err(RecordError("target does not match record length".to_string()));
+        }
 
         Ok(Self {
             priority,
@@ -55,7 +71,8 @@ impl RecordBase for SrvRecord {
         buf.splice(4..6, self.weight.to_be_bytes());
         buf.splice(6..8, self.port.to_be_bytes());
 
-        buf.extend_from_slice(&pack_fqdn(self.target.as_ref().unwrap().as_str(), compression_data, off+8, true));
+        buf.extend_from_slice(&pack_fqdn(self.target.as_ref()
+            .ok_or_else(|| RecordError("target param was not set".to_string()))?, compression_data, off+8, true));
 
         buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
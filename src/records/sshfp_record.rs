@@ -2,15 +2,12 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
 use crate::utils::hex;
 
 #[derive(Clone, Debug)]
 pub struct SshFpRecord {
-    class: RRClasses,
-    ttl: u32,
     pub(crate) algorithm: u8,
     pub(crate) fingerprint_type: u8,
     pub(crate) fingerprint: Vec<u8>
@@ -20,8 +17,6 @@ impl Default for SshFpRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            ttl: 0,
             algorithm: 0,
             fingerprint_type: 0,
             fingerprint: Vec::new()
@@ -32,19 +27,30 @@ impl Default for SshFpRecord {
 impl RecordBase for SshFpRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let class = RRClasses::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
 
-        let algorithm = buf[off+8];
-        let fingerprint_type = buf[off+9];
+        if off+4 > length {
+            return Err(RecordError("truncated sshfp record".to_string()));
+        }
 
-        let data_length = off+8+u16::from_be_bytes([buf[off+6], buf[off+7]]) as usize;
+        let algorithm = buf[off+2];
+        let fingerprint_type = buf[off+3];
 
-        let fingerprint = buf[off+10..data_length].to_vec();
+        let fingerprint = buf[off+4..length].to_vec();
 
         Ok(Self {
-            class,
-            ttl,
             algorithm,
             fingerprint_type,
             fingerprint
@@ -52,17 +58,14 @@ impl RecordBase for SshFpRecord {
     }
 
     fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 10];
-
-        buf.splice(0..2, self.class.get_code().to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
+        let mut buf = vec![0u8; 4];
 
-        buf[8] = self.algorithm;
-        buf[9] = self.fingerprint_type;
+        buf[2] = self.algorithm;
+        buf[3] = self.fingerprint_type;
 
         buf.extend_from_slice(&self.fingerprint);
 
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -90,30 +93,14 @@ impl RecordBase for SshFpRecord {
 
 impl SshFpRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(algorithm: u8, fingerprint_type: u8, fingerprint: Vec<u8>) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            algorithm,
+            fingerprint_type,
+            fingerprint
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
-    }
-
-    pub fn get_class(&self) -> RRClasses {
-        self.class
-    }
-
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
-    }
-
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
-    }
-
     pub fn set_algorithm(&mut self, algorithm: u8) {
         self.algorithm = algorithm;
     }
@@ -142,11 +129,16 @@ impl SshFpRecord {
 impl fmt::Display for SshFpRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:<8}{:<8}{:<8}{} {} {}", self.ttl,
-               self.class.to_string(),
-               self.get_type().to_string(),
+        write!(f, "{:<8}{} {} {}", self.get_type().to_string(),
                self.algorithm,
                self.fingerprint_type,
                hex::encode(&self.fingerprint))
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x16, 0x1, 0x2, 0x8b, 0x9f, 0x2f, 0x2b, 0x6b, 0x3b, 0x6, 0xe3, 0xd8, 0x9f, 0x54, 0xd0, 0x73, 0xd, 0xce, 0x5b, 0x4, 0x26, 0xf8, 0x8c ];
+    let record = SshFpRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
@@ -29,27 +29,51 @@ impl Default for SvcbRecord {
 impl RecordBase for SvcbRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+4 > length {
+            return Err(RecordError("truncated svc record".to_string()));
+        }
+
         let priority = u16::from_be_bytes([buf[off+2], buf[off+3]]);
 
-        let (target, target_length) = unpack_fqdn(&buf, off+4);
+        let (target, target_length) = unpack_fqdn(buf, off+4).map_err(|e| RecordError(e.to_string()))?;
 
-        length += off+2;
         let mut off = off+4+target_length;
         let mut params = Vec::new();
 
         while off < length {
+            if off+4 > length {
+                return Err(RecordError("truncated svc param header".to_string()));
+            }
+
             let key = SvcParamKeys::try_from(u16::from_be_bytes([buf[off], buf[off+1]]))
                 .map_err(|e| RecordError(e.to_string()))?;
-            let length = u16::from_be_bytes([buf[off+2], buf[off+3]]) as usize;
-            params.push(SvcParams::from_bytes(key, &buf[off+4..off+4+length])
+            let param_length = u16::from_be_bytes([buf[off+2], buf[off+3]]) as usize;
+            if off+4+param_length > length {
+                return Err(RecordError("svc param runs past record length".to_string()));
+            }
+
+            params.push(SvcParams::from_bytes(key, &buf[off+4..off+4+param_length])
                 .map_err(|e| RecordError(e.to_string()))?);
 
-            off += length+4;
+            off += param_length+4;
+        }
+
+        if off != length {
+            return Err(RecordError("target runs past record length".to_string()));
         }
 
         Ok(Self {
@@ -2,7 +2,6 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
 use crate::utils::base64;
@@ -10,8 +9,6 @@ use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
 
 #[derive(Clone, Debug)]
 pub struct TKeyRecord {
-    class: RRClasses,
-    ttl: u32,
     pub(crate) algorithm_name: Option<String>,
     pub(crate) inception: u32,
     pub(crate) expiration: u32,
@@ -25,8 +22,6 @@ impl Default for TKeyRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            ttl: 0,
             algorithm_name: None,
             inception: 0,
             expiration: 0,
@@ -41,13 +36,26 @@ impl Default for TKeyRecord {
 impl RecordBase for TKeyRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut off = off;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
 
-        let class = RRClasses::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
+        let (algorithm_name, algorithm_name_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        let mut off = off+2+algorithm_name_length;
 
-        let (algorithm_name, algorithm_name_length) = unpack_fqdn(buf, off+8);
-        off += 8+algorithm_name_length;
+        if off+14 > length {
+            return Err(RecordError("truncated tkey record".to_string()));
+        }
 
         let inception = u32::from_be_bytes([buf[off], buf[off+1], buf[off+2], buf[off+3]]);
         let expiration = u32::from_be_bytes([buf[off+4], buf[off+5], buf[off+6], buf[off+7]]);
@@ -56,15 +64,21 @@ impl RecordBase for TKeyRecord {
         let error = u16::from_be_bytes([buf[off+10], buf[off+11]]);
 
         let key_length = 14+u16::from_be_bytes([buf[off+12], buf[off+13]]) as usize;
+        if off+key_length+2 > length {
+            return Err(RecordError("key runs past record length".to_string()));
+        }
+
         let key = buf[off + 14.. off + key_length].to_vec();
         off += key_length;
 
         let data_length = off+2+u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if data_length != length {
+            return Err(RecordError("other data does not match record length".to_string()));
+        }
+
         let data = buf[off + 2..data_length].to_vec();
 
         Ok(Self {
-            class,
-            ttl,
             algorithm_name: Some(algorithm_name),
             inception,
             expiration,
@@ -76,12 +90,10 @@ impl RecordBase for TKeyRecord {
     }
 
     fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 8];
+        let mut buf = vec![0u8; 2];
 
-        buf.splice(0..2, self.class.get_code().to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
-
-        buf.extend_from_slice(&pack_fqdn(self.algorithm_name.as_ref().unwrap().as_str(), compression_data, off+8, true)); //PROBABLY NO COMPRESS
+        buf.extend_from_slice(&pack_fqdn(self.algorithm_name.as_ref()
+            .ok_or_else(|| RecordError("algorithm_name param was not set".to_string()))?, compression_data, off+2, false));
 
         buf.extend_from_slice(&self.inception.to_be_bytes());
         buf.extend_from_slice(&self.expiration.to_be_bytes());
@@ -95,7 +107,7 @@ impl RecordBase for TKeyRecord {
         buf.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
         buf.extend_from_slice(&self.data);
 
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -123,38 +135,80 @@ impl RecordBase for TKeyRecord {
 
 impl TKeyRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(algorithm_name: &str, inception: u32, expiration: u32, mode: u16, error: u16, key: Vec<u8>, data: Vec<u8>) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            algorithm_name: Some(algorithm_name.to_string()),
+            inception,
+            expiration,
+            mode,
+            error,
+            key,
+            data
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
+    pub fn set_algorithm_name(&mut self, algorithm_name: &str) {
+        self.algorithm_name = Some(algorithm_name.to_string());
     }
 
-    pub fn get_class(&self) -> RRClasses {
-        self.class
+    pub fn get_algorithm_name(&self) -> Option<&String> {
+        self.algorithm_name.as_ref()
     }
 
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
+    pub fn set_inception(&mut self, inception: u32) {
+        self.inception = inception;
     }
 
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
+    pub fn get_inception(&self) -> u32 {
+        self.inception
+    }
+
+    pub fn set_expiration(&mut self, expiration: u32) {
+        self.expiration = expiration;
+    }
+
+    pub fn get_expiration(&self) -> u32 {
+        self.expiration
+    }
+
+    pub fn set_mode(&mut self, mode: u16) {
+        self.mode = mode;
+    }
+
+    pub fn get_mode(&self) -> u16 {
+        self.mode
+    }
+
+    pub fn set_error(&mut self, error: u16) {
+        self.error = error;
+    }
+
+    pub fn get_error(&self) -> u16 {
+        self.error
+    }
+
+    pub fn set_key(&mut self, key: &[u8]) {
+        self.key = key.to_vec();
+    }
+
+    pub fn get_key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) {
+        self.data = data.to_vec();
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        self.data.as_ref()
     }
 }
 
 impl fmt::Display for TKeyRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:<8}{:<8}{:<8}{} {} {} {} {} {} {}", self.ttl,
-               self.class.to_string(),
-               self.get_type().to_string(),
-               format!("{}.", self.algorithm_name.as_ref().unwrap()),
+        write!(f, "{:<8}{} {} {} {} {} {} {}", self.get_type().to_string(),
+               format!("{}.", self.algorithm_name.as_ref().unwrap_or(&String::new())),
                self.inception,
                self.expiration,
                self.mode,
@@ -163,3 +217,10 @@ impl fmt::Display for TKeyRecord {
                base64::encode(&self.data)) //IF EMPTY USE -
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x1c, 0x8, 0x67, 0x73, 0x73, 0x2d, 0x74, 0x73, 0x69, 0x67, 0x0, 0x50, 0xf8, 0xcf, 0xbb, 0x50, 0xfa, 0x21, 0x3b, 0x0, 0x3, 0x0, 0x0, 0x0, 0x2, 0xab, 0xcd, 0x0, 0x0 ];
+    let record = TKeyRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
@@ -0,0 +1,161 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::hex;
+
+#[derive(Clone, Debug)]
+pub struct TlsaRecord {
+    pub(crate) usage: u8,
+    pub(crate) selector: u8,
+    pub(crate) matching_type: u8,
+    pub(crate) certificate: Vec<u8>
+}
+
+impl Default for TlsaRecord {
+
+    fn default() -> Self {
+        Self {
+            usage: 0,
+            selector: 0,
+            matching_type: 0,
+            certificate: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for TlsaRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
+
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+5 > length {
+            return Err(RecordError("truncated tlsa record".to_string()));
+        }
+
+        let usage = buf[off+2];
+        let selector = buf[off+3];
+        let matching_type = buf[off+4];
+
+        let certificate = buf[off+5..length].to_vec();
+
+        Ok(Self {
+            usage,
+            selector,
+            matching_type,
+            certificate
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = vec![0u8; 5];
+
+        buf[2] = self.usage;
+        buf[3] = self.selector;
+        buf[4] = self.matching_type;
+
+        buf.extend_from_slice(&self.certificate);
+
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        RRTypes::Tlsa
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl TlsaRecord {
+
+    pub fn new(usage: u8, selector: u8, matching_type: u8, certificate: Vec<u8>) -> Self {
+        Self {
+            usage,
+            selector,
+            matching_type,
+            certificate
+        }
+    }
+
+    pub fn set_usage(&mut self, usage: u8) {
+        self.usage = usage;
+    }
+
+    pub fn get_usage(&self) -> u8 {
+        self.usage
+    }
+
+    pub fn set_selector(&mut self, selector: u8) {
+        self.selector = selector;
+    }
+
+    pub fn get_selector(&self) -> u8 {
+        self.selector
+    }
+
+    pub fn set_matching_type(&mut self, matching_type: u8) {
+        self.matching_type = matching_type;
+    }
+
+    pub fn get_matching_type(&self) -> u8 {
+        self.matching_type
+    }
+
+    pub fn set_certificate(&mut self, certificate: &[u8]) {
+        self.certificate = certificate.to_vec();
+    }
+
+    pub fn get_certificate(&self) -> &[u8] {
+        self.certificate.as_ref()
+    }
+}
+
+impl fmt::Display for TlsaRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<8}{} {} {} {}", self.get_type().to_string(),
+               self.usage,
+               self.selector,
+               self.matching_type,
+               hex::encode(&self.certificate))
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x23, 0x0, 0x0, 0x1, 0xd2, 0xab, 0xde, 0x24, 0xd, 0x7c, 0xd3, 0xee, 0x6b, 0x4b, 0x28, 0xc5, 0x4d, 0xf0, 0x34, 0xb9, 0x79, 0x83, 0xa1, 0xd1, 0x6e, 0x8a, 0x41, 0xe, 0x45, 0x61, 0xcb, 0x10, 0x66, 0x18, 0xe9, 0x71 ];
+    //RFC 6698 S2.3
+    let record = TlsaRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert_eq!(record.to_string(), "TLSA    0 0 1 d2abde240d7cd3ee6b4b28c54df034b97983a1d16e8a410e4561cb106618e971");
+}
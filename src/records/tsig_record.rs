@@ -2,7 +2,6 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
 use crate::records::inter::record_base::{RecordBase, RecordError};
 use crate::utils::fqdn_utils::{pack_fqdn, unpack_fqdn};
@@ -10,8 +9,6 @@ use crate::utils::hex;
 
 #[derive(Clone, Debug)]
 pub struct TSigRecord {
-    class: RRClasses,
-    ttl: u32,
     pub(crate) algorithm_name: Option<String>,
     pub(crate) time_signed: u64,
     pub(crate) fudge: u16,
@@ -25,8 +22,6 @@ impl Default for TSigRecord {
 
     fn default() -> Self {
         Self {
-            class: RRClasses::default(),
-            ttl: 0,
             algorithm_name: None,
             time_signed: 0,
             fudge: 0,
@@ -41,15 +36,26 @@ impl Default for TSigRecord {
 impl RecordBase for TSigRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut off = off;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
 
-        let class = RRClasses::try_from(u16::from_be_bytes([buf[off], buf[off+1]])).unwrap();
-        let ttl = u32::from_be_bytes([buf[off+2], buf[off+3], buf[off+4], buf[off+5]]);
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length == 0 {
+            return Ok(Default::default());
+        }
 
-        //let length = u16::from_be_bytes([buf[off+6], buf[off+7]]) as usize;
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
 
-        let (algorithm_name, algorithm_name_length) = unpack_fqdn(buf, off+8);
-        off += 8+algorithm_name_length;
+        let (algorithm_name, algorithm_name_length) = unpack_fqdn(buf, off+2).map_err(|e| RecordError(e.to_string()))?;
+        let mut off = off+2+algorithm_name_length;
+
+        if off+10 > length {
+            return Err(RecordError("truncated tsig record".to_string()));
+        }
 
         let time_signed = ((buf[off] as u64) << 40)
                 | ((buf[off+1] as u64) << 32)
@@ -60,6 +66,10 @@ impl RecordBase for TSigRecord {
         let fudge = u16::from_be_bytes([buf[off+6], buf[off+7]]);
 
         let mac_length = 10+u16::from_be_bytes([buf[off+8], buf[off+9]]) as usize;
+        if off+mac_length+6 > length {
+            return Err(RecordError("mac runs past record length".to_string()));
+        }
+
         let mac = buf[off + 10..off + mac_length].to_vec();
         off += mac_length;
 
@@ -67,11 +77,13 @@ impl RecordBase for TSigRecord {
         let error = u16::from_be_bytes([buf[off+2], buf[off+3]]);
 
         let data_length = off+6+u16::from_be_bytes([buf[off+4], buf[off+5]]) as usize;
+        if data_length != length {
+            return Err(RecordError("other data does not match record length".to_string()));
+        }
+
         let data = buf[off + 6..data_length].to_vec();
 
         Ok(Self {
-            class,
-            ttl,
             algorithm_name: Some(algorithm_name),
             time_signed,
             fudge,
@@ -83,12 +95,10 @@ impl RecordBase for TSigRecord {
     }
 
     fn to_bytes(&self, compression_data: &mut HashMap<String, usize>, off: usize) -> Result<Vec<u8>, RecordError> {
-        let mut buf = vec![0u8; 8];
-
-        buf.splice(0..2, self.class.get_code().to_be_bytes());
-        buf.splice(2..6, self.ttl.to_be_bytes());
+        let mut buf = vec![0u8; 2];
 
-        buf.extend_from_slice(&pack_fqdn(self.algorithm_name.as_ref().unwrap().as_str(), compression_data, off+8, true)); //PROBABLY NO COMPRESS
+        buf.extend_from_slice(&pack_fqdn(self.algorithm_name.as_ref()
+            .ok_or_else(|| RecordError("algorithm_name param was not set".to_string()))?, compression_data, off+2, false));
 
         buf.extend_from_slice(&[
             ((self.time_signed >> 40) & 0xFF) as u8,
@@ -109,7 +119,7 @@ impl RecordBase for TSigRecord {
         buf.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
         buf.extend_from_slice(&self.data);
 
-        buf.splice(6..8, ((buf.len()-8) as u16).to_be_bytes());
+        buf.splice(0..2, ((buf.len()-2) as u16).to_be_bytes());
 
         Ok(buf)
     }
@@ -137,38 +147,80 @@ impl RecordBase for TSigRecord {
 
 impl TSigRecord {
 
-    pub fn new(ttl: u32, class: RRClasses) -> Self {
+    pub fn new(algorithm_name: &str, time_signed: u64, fudge: u16, mac: Vec<u8>, original_id: u16, error: u16, data: Vec<u8>) -> Self {
         Self {
-            class,
-            ttl,
-            ..Self::default()
+            algorithm_name: Some(algorithm_name.to_string()),
+            time_signed,
+            fudge,
+            mac,
+            original_id,
+            error,
+            data
         }
     }
 
-    pub fn set_class(&mut self, class: RRClasses) {
-        self.class = class;
+    pub fn set_algorithm_name(&mut self, algorithm_name: &str) {
+        self.algorithm_name = Some(algorithm_name.to_string());
+    }
+
+    pub fn get_algorithm_name(&self) -> Option<&String> {
+        self.algorithm_name.as_ref()
+    }
+
+    pub fn set_time_signed(&mut self, time_signed: u64) {
+        self.time_signed = time_signed;
+    }
+
+    pub fn get_time_signed(&self) -> u64 {
+        self.time_signed
+    }
+
+    pub fn set_fudge(&mut self, fudge: u16) {
+        self.fudge = fudge;
     }
 
-    pub fn get_class(&self) -> RRClasses {
-        self.class
+    pub fn get_fudge(&self) -> u16 {
+        self.fudge
     }
 
-    pub fn set_ttl(&mut self, ttl: u32) {
-        self.ttl = ttl;
+    pub fn set_mac(&mut self, mac: &[u8]) {
+        self.mac = mac.to_vec();
     }
 
-    pub fn get_ttl(&self) -> u32 {
-        self.ttl
+    pub fn get_mac(&self) -> &[u8] {
+        self.mac.as_ref()
+    }
+
+    pub fn set_original_id(&mut self, original_id: u16) {
+        self.original_id = original_id;
+    }
+
+    pub fn get_original_id(&self) -> u16 {
+        self.original_id
+    }
+
+    pub fn set_error(&mut self, error: u16) {
+        self.error = error;
+    }
+
+    pub fn get_error(&self) -> u16 {
+        self.error
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) {
+        self.data = data.to_vec();
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        self.data.as_ref()
     }
 }
 
 impl fmt::Display for TSigRecord {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:<8}{:<8}{:<8}{} {} {} {} {} {} {}", self.ttl,
-               self.class.to_string(),
-               self.get_type().to_string(),
-               format!("{}.", self.algorithm_name.as_ref().unwrap()),
+        write!(f, "{:<8}{} {} {} {} {} {} {}", self.get_type().to_string(),
+               format!("{}.", self.algorithm_name.as_ref().unwrap_or(&String::new())),
                self.time_signed,
                self.fudge,
                hex::encode(&self.mac),
@@ -177,3 +229,10 @@ impl fmt::Display for TSigRecord {
                hex::encode(&self.data))
     }
 }
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x21, 0xb, 0x68, 0x6d, 0x61, 0x63, 0x2d, 0x73, 0x68, 0x61, 0x32, 0x35, 0x36, 0x0, 0x0, 0x0, 0x65, 0x5c, 0x8a, 0x0, 0x1, 0x2c, 0x0, 0x4, 0xde, 0xad, 0xbe, 0xef, 0x12, 0x34, 0x0, 0x0, 0x0, 0x0 ];
+    let record = TSigRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+}
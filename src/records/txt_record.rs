@@ -22,21 +22,33 @@ impl Default for TxtRecord {
 impl RecordBase for TxtRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let mut length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
-        length += off+2;
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
         let mut off = off+2;
         let mut data = Vec::new();
 
         while off < length {
-            let length = buf[off] as usize;
-            let record = String::from_utf8(buf[off + 1..off + 1 + length].to_vec())
+            let data_length = buf[off] as usize;
+            if off+1+data_length > length {
+                return Err(RecordError("character string runs past record length".to_string()));
+            }
+
+            let record = String::from_utf8(buf[off + 1..off + 1 + data_length].to_vec())
                 .map_err(|e| RecordError(e.to_string()))?;
             data.push(record);
-            off += length+1;
+            off += data_length+1;
         }
 
         Ok(Self {
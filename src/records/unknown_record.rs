@@ -0,0 +1,109 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::records::inter::record_base::{RecordBase, RecordError};
+use crate::utils::hex;
+
+#[derive(Clone, Debug)]
+pub struct UnknownRecord {
+    pub(crate) _type: RRTypes,
+    pub(crate) data: Vec<u8>
+}
+
+impl Default for UnknownRecord {
+
+    fn default() -> Self {
+        Self {
+            _type: RRTypes::Unknown(0),
+            data: Vec::new()
+        }
+    }
+}
+
+impl RecordBase for UnknownRecord {
+
+    fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = off+2+u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        Ok(Self {
+            data: buf[off+2..length].to_vec(),
+            ..Default::default()
+        })
+    }
+
+    fn to_bytes(&self, _compression_data: &mut HashMap<String, usize>, _off: usize) -> Result<Vec<u8>, RecordError> {
+        let mut buf = (self.data.len() as u16).to_be_bytes().to_vec();
+        buf.extend_from_slice(&self.data);
+
+        Ok(buf)
+    }
+
+    fn get_type(&self) -> RRTypes {
+        self._type
+    }
+
+    fn upcast(self) -> Box<dyn RecordBase> {
+        Box::new(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordBase> {
+        Box::new(self.clone())
+    }
+}
+
+impl UnknownRecord {
+
+    pub fn new(_type: RRTypes, data: Vec<u8>) -> Self {
+        Self {
+            _type,
+            data
+        }
+    }
+
+    pub fn set_type(&mut self, _type: RRTypes) {
+        self._type = _type;
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) {
+        self.data = data.to_vec();
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+}
+
+impl fmt::Display for UnknownRecord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.data.is_empty() {
+            true => write!(f, "{:<7} \\# 0", self.get_type().to_string()),
+            false => write!(f, "{:<7} \\# {} {}", self.get_type().to_string(), self.data.len(), hex::encode(&self.data))
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let buf = vec![ 0x0, 0x5, 0x8, 0x1, 0x2, 0x3, 0x4 ];
+    let record = UnknownRecord::from_bytes(&buf, 0).unwrap();
+    assert_eq!(buf, record.to_bytes(&mut HashMap::new(), 0).unwrap());
+    assert_eq!(UnknownRecord::new(RRTypes::Unknown(65534), record.data).to_string(), "TYPE65534 \\# 5 0801020304");
+}
@@ -26,15 +26,27 @@ impl Default for UriRecord {
 impl RecordBase for UriRecord {
 
     fn from_bytes(buf: &[u8], off: usize) -> Result<Self, RecordError> {
-        let length = u16::from_be_bytes([buf[off], buf[off+1]]);
+        if off+2 > buf.len() {
+            return Err(RecordError("truncated record length".to_string()));
+        }
+
+        let length = u16::from_be_bytes([buf[off], buf[off+1]]) as usize;
         if length == 0 {
             return Ok(Default::default());
         }
 
+        let length = off+2+length;
+        if length > buf.len() {
+            return Err(RecordError("record data runs past end of buffer".to_string()));
+        }
+
+        if off+6 > length {
+            return Err(RecordError("truncated uri record".to_string()));
+        }
+
         let priority = u16::from_be_bytes([buf[off+2], buf[off+3]]);
         let weight = u16::from_be_bytes([buf[off+4], buf[off+5]]);
-
-        let target = String::from_utf8(buf[off+6..off+2+length as usize].to_vec())
+        let target = String::from_utf8(buf[off+6..length].to_vec())
             .map_err(|e| RecordError(e.to_string()))?;
 
         Ok(Self {
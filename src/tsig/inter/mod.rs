@@ -0,0 +1 @@
+pub mod tsig_algorithms;
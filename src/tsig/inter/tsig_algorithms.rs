@@ -0,0 +1,80 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use crate::utils::hmac;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TsigAlgorithms {
+    HmacMd5,
+    HmacSha1,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512
+}
+
+impl TsigAlgorithms {
+
+    //RFC 8945 S6 - NAMES AS THEY APPEAR IN THE TSIG ALGORITHM NAME FIELD
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::HmacMd5 => "hmac-md5.sig-alg.reg.int",
+            Self::HmacSha1 => "hmac-sha1",
+            Self::HmacSha256 => "hmac-sha256",
+            Self::HmacSha384 => "hmac-sha384",
+            Self::HmacSha512 => "hmac-sha512"
+        }
+    }
+
+    pub fn get_digest_len(&self) -> usize {
+        match self {
+            Self::HmacMd5 => 16,
+            Self::HmacSha1 => 20,
+            Self::HmacSha256 => 32,
+            Self::HmacSha384 => 48,
+            Self::HmacSha512 => 64
+        }
+    }
+
+    pub fn sign(&self, secret: &[u8], buf: &[u8]) -> Vec<u8> {
+        match self {
+            Self::HmacMd5 => hmac::hmac_md5(secret, buf),
+            Self::HmacSha1 => hmac::hmac_sha1(secret, buf),
+            Self::HmacSha256 => hmac::hmac_sha256(secret, buf),
+            Self::HmacSha384 => hmac::hmac_sha384(secret, buf),
+            Self::HmacSha512 => hmac::hmac_sha512(secret, buf)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TsigAlgorithmParseError(pub String);
+
+impl fmt::Display for TsigAlgorithmParseError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown tsig algorithm: {}", self.0)
+    }
+}
+
+impl FromStr for TsigAlgorithms {
+
+    type Err = TsigAlgorithmParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim_end_matches('.').to_lowercase().as_str() {
+            "hmac-md5" | "hmac-md5.sig-alg.reg.int" => Self::HmacMd5,
+            "hmac-sha1" => Self::HmacSha1,
+            "hmac-sha256" => Self::HmacSha256,
+            "hmac-sha384" => Self::HmacSha384,
+            "hmac-sha512" => Self::HmacSha512,
+            _ => return Err(TsigAlgorithmParseError(s.to_string()))
+        })
+    }
+}
+
+impl fmt::Display for TsigAlgorithms {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
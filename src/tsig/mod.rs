@@ -0,0 +1,4 @@
+pub mod inter;
+pub mod tsig_key;
+pub mod tsig_keyring;
+pub mod tsig_stream;
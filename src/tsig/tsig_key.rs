@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use crate::tsig::inter::tsig_algorithms::TsigAlgorithms;
+use crate::utils::fqdn_utils::pack_fqdn;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TsigKey {
+    name: String,
+    algorithm: TsigAlgorithms,
+    secret: Vec<u8>
+}
+
+impl TsigKey {
+
+    pub fn new(name: &str, algorithm: TsigAlgorithms, secret: &[u8]) -> Self {
+        Self {
+            name: name.trim_end_matches('.').to_lowercase(),
+            algorithm,
+            secret: secret.to_vec()
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_algorithm(&self) -> TsigAlgorithms {
+        self.algorithm
+    }
+
+    pub fn get_secret(&self) -> &[u8] {
+        self.secret.as_ref()
+    }
+
+    pub fn sign(&self, buf: &[u8]) -> Vec<u8> {
+        self.algorithm.sign(&self.secret, buf)
+    }
+
+    //RFC 8945 S4.2 - WIRE LENGTH OF A TSIG RECORD THIS KEY SIGNS WITH, NO ERROR AND NO OTHER DATA
+    pub fn get_tsig_len(&self) -> usize {
+        pack_fqdn(&self.name, &mut HashMap::new(), 0, false).len()+10
+            +pack_fqdn(self.algorithm.get_name(), &mut HashMap::new(), 0, false).len()+16
+            +self.algorithm.get_digest_len()
+    }
+}
+
+#[test]
+fn test() {
+    use crate::messages::inter::response_codes::ResponseCodes;
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::messages::inter::rr_types::RRTypes;
+    use crate::messages::message::{Message, TSIG_FUDGE};
+    use crate::messages::rr_query::RRQuery;
+    use crate::tsig::tsig_keyring::TsigKeyring;
+    use crate::utils::hex;
+
+    let query = || {
+        let mut message = Message::new(0x1234);
+        message.add_query(RRQuery::new("find9.net", RRTypes::A, RRClasses::In));
+        message
+    };
+
+    //INDEPENDENTLY COMPUTED OVER THE RFC 8945 S4.3.3 DIGEST LAYOUT
+    let key = TsigKey::new("XFR.find9.net.", TsigAlgorithms::HmacSha256, b"0123456789abcdef0123456789abcdef");
+    let mut message = query();
+    let mac = message.sign_tsig(&key, 1000, None);
+    assert_eq!(hex::encode(&mac), "650fcf2899cbeb2063571ba77069a586b20f6423efc681aab9ae6c79e9422325");
+
+    let mut keyring = TsigKeyring::new();
+    for algorithm in [TsigAlgorithms::HmacMd5, TsigAlgorithms::HmacSha1, TsigAlgorithms::HmacSha256, TsigAlgorithms::HmacSha384, TsigAlgorithms::HmacSha512] {
+        let key = TsigKey::new("xfr.find9.net", algorithm, b"secret");
+        keyring.add_key(key.clone());
+
+        let mut message = query();
+        let mac = message.sign_tsig(&key, 1000, None);
+        assert_eq!(mac.len(), algorithm.get_digest_len());
+        assert_eq!(message.to_bytes(512).len(), query().to_bytes(512).len()+key.get_tsig_len());
+
+        let received = Message::from_bytes(&message.to_bytes(512)).unwrap();
+        assert_eq!(received.verify_tsig(&keyring, 1000+TSIG_FUDGE as u64, None), Ok(mac.clone()));
+
+        //RESPONSE MAC COVERS THE REQUEST MAC
+        let mut response = query();
+        response.set_qr(true);
+        let response_mac = response.sign_tsig(&key, 1001, Some(&mac));
+        let received = Message::from_bytes(&response.to_bytes(512)).unwrap();
+        assert_eq!(received.verify_tsig(&keyring, 1001, Some(&mac)), Ok(response_mac));
+        assert_eq!(received.verify_tsig(&keyring, 1001, None), Err(ResponseCodes::BadSig));
+    }
+
+    let key = TsigKey::new("xfr.find9.net", TsigAlgorithms::HmacSha256, b"secret");
+    let mut message = query();
+    let mac = message.sign_tsig(&key, 1000, None);
+    let buf = message.to_bytes(512);
+
+    //A CHANGED ID IS FINE, ORIGINAL ID IS WHAT WAS SIGNED
+    let mut forwarded = buf.clone();
+    forwarded[0] = 0xff;
+    assert_eq!(Message::from_bytes(&forwarded).unwrap().verify_tsig(&keyring, 1000, None), Ok(mac));
+
+    let mut tampered = buf.clone();
+    tampered[3] = 0x01;
+    let tampered = Message::from_bytes(&tampered).unwrap();
+    assert_eq!(tampered.verify_tsig(&keyring, 1000, None), Err(ResponseCodes::BadSig));
+
+    let response = tampered.tsig_error_response(ResponseCodes::BadSig, &keyring, 1000);
+    assert_eq!(response.get_response_code(), ResponseCodes::NotAuth);
+    let (_, tsig) = response.get_tsig().unwrap();
+    assert_eq!(tsig.get_error(), ResponseCodes::BadSig.get_code());
+    assert!(tsig.get_mac().is_empty());
+
+    let received = Message::from_bytes(&buf).unwrap();
+    assert_eq!(received.verify_tsig(&TsigKeyring::new(), 1000, None), Err(ResponseCodes::BadKey));
+    assert_eq!(query().verify_tsig(&keyring, 1000, None), Err(ResponseCodes::FormErr));
+
+    //BADTIME ANSWER IS SIGNED AND TELLS THE CLIENT OUR CLOCK
+    let now = 1000+TSIG_FUDGE as u64+1;
+    assert_eq!(received.verify_tsig(&keyring, now, None), Err(ResponseCodes::BadTime));
+    let response = received.tsig_error_response(ResponseCodes::BadTime, &keyring, now);
+    let response = Message::from_bytes(&response.to_bytes(512)).unwrap();
+    let (_, tsig) = response.get_tsig().unwrap();
+    assert_eq!(tsig.get_error(), ResponseCodes::BadTime.get_code());
+    assert_eq!(tsig.get_data(), &now.to_be_bytes()[2..]);
+    assert_eq!(response.verify_tsig(&keyring, 1000, Some(received.get_tsig().unwrap().1.get_mac())).map(|_| ()), Ok(()));
+}
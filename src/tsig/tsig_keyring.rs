@@ -0,0 +1,321 @@
+use std::{fmt, fs};
+use std::fmt::Formatter;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use crate::tsig::inter::tsig_algorithms::TsigAlgorithms;
+use crate::tsig::tsig_key::TsigKey;
+use crate::utils::base64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TsigKeyringError {
+    Io(String),
+    ParseError { line: usize, reason: String }
+}
+
+impl fmt::Display for TsigKeyringError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(reason) => write!(f, "io error: {}", reason),
+            Self::ParseError { line, reason } => write!(f, "line {}: {}", line, reason)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Open,
+    Close,
+    End
+}
+
+impl fmt::Display for Token {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "{}", word),
+            Self::Quoted(word) => write!(f, "\"{}\"", word),
+            Self::Open => write!(f, "{{"),
+            Self::Close => write!(f, "}}"),
+            Self::End => write!(f, ";")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TsigKeyring {
+    keys: Vec<TsigKey>
+}
+
+impl Default for TsigKeyring {
+
+    fn default() -> Self {
+        Self {
+            keys: Vec::new()
+        }
+    }
+}
+
+impl TsigKeyring {
+
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    //BIND KEY FILE, EITHER A NAMED.CONF INCLUDE OR TSIG-KEYGEN OUTPUT
+    pub fn open<P: Into<PathBuf>>(file_path: P) -> Result<Self, TsigKeyringError> {
+        fs::read_to_string(file_path.into()).map_err(|e| TsigKeyringError::Io(e.to_string()))?.parse()
+    }
+
+    //SECRETS ARE WRITTEN OWNER ONLY TO A TEMP FILE THAT IS RENAMED OVER THE OLD ONE, SO NO READER SEES HALF A KEYRING
+    pub fn save<P: Into<PathBuf>>(&self, file_path: P) -> io::Result<()> {
+        let file_path = file_path.into();
+        let mut temp_path = file_path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        //MODE ONLY APPLIES TO A NEW FILE, SO A LEFTOVER TEMP FILE ISN'T REUSED
+        match fs::remove_file(&temp_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(&temp_path)?;
+        file.write_all(self.to_string().as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&temp_path, &file_path)?;
+
+        //THE RENAME ISN'T DURABLE UNTIL THE DIRECTORY ENTRY IS
+        if let Some(parent) = file_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            File::open(parent)?.sync_all()?;
+        }
+
+        Ok(())
+    }
+
+    //A KEY WITH THE SAME NAME AND ALGORITHM IS REPLACED
+    pub fn add_key(&mut self, key: TsigKey) {
+        self.keys.retain(|k| k.get_name() != key.get_name() || k.get_algorithm() != key.get_algorithm());
+        self.keys.push(key);
+    }
+
+    pub fn get_key(&self, name: &str, algorithm: TsigAlgorithms) -> Option<&TsigKey> {
+        let name = name.trim_end_matches('.');
+        self.keys.iter().find(|k| k.get_name().eq_ignore_ascii_case(name) && k.get_algorithm() == algorithm)
+    }
+
+    pub fn get_keys(&self) -> &Vec<TsigKey> {
+        self.keys.as_ref()
+    }
+
+    pub fn as_ref(&self) -> &Self {
+        self
+    }
+
+    pub fn as_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl FromStr for TsigKeyring {
+
+    type Err = TsigKeyringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokenize(s)?.into_iter();
+        let mut keyring = Self::new();
+
+        while let Some((line, token)) = tokens.next() {
+            match token {
+                Token::Word(word) if word.eq_ignore_ascii_case("key") => keyring.add_key(parse_key(&mut tokens, line)?),
+                token => return Err(TsigKeyringError::ParseError { line, reason: format!("expected key statement, found {}", token) })
+            }
+        }
+
+        Ok(keyring)
+    }
+}
+
+impl fmt::Display for TsigKeyring {
+
+    //SAME LAYOUT TSIG-KEYGEN WRITES, BIND ONLY KNOWS MD5 BY ITS SHORT NAME IN KEY FILES
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for key in &self.keys {
+            writeln!(f, "key \"{}\" {{", key.get_name())?;
+            writeln!(f, "\talgorithm {};", key.get_algorithm().get_name().trim_end_matches(".sig-alg.reg.int"))?;
+            writeln!(f, "\tsecret \"{}\";", base64::encode(key.get_secret()))?;
+            writeln!(f, "}};")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_key(tokens: &mut impl Iterator<Item = (usize, Token)>, line: usize) -> Result<TsigKey, TsigKeyringError> {
+    let mut next = |expected: &str| tokens.next().ok_or_else(|| TsigKeyringError::ParseError { line, reason: format!("unexpected end of file, expected {}", expected) });
+    let error = |line: usize, reason: String| TsigKeyringError::ParseError { line, reason };
+
+    let name = match next("key name")? {
+        (_, Token::Word(name)) | (_, Token::Quoted(name)) => name,
+        (line, token) => return Err(error(line, format!("expected key name, found {}", token)))
+    };
+
+    match next("{")? {
+        (_, Token::Open) => {}
+        (line, token) => return Err(error(line, format!("expected {{, found {}", token)))
+    }
+
+    let mut algorithm = None;
+    let mut secret = None;
+
+    loop {
+        let (line, clause) = match next("}")? {
+            (_, Token::Close) => break,
+            (line, Token::Word(clause)) => (line, clause.to_lowercase()),
+            (line, token) => return Err(error(line, format!("expected key clause, found {}", token)))
+        };
+
+        let value = match next("value")? {
+            (_, Token::Word(value)) | (_, Token::Quoted(value)) => value,
+            (line, token) => return Err(error(line, format!("expected value for {}, found {}", clause, token)))
+        };
+
+        match clause.as_str() {
+            "algorithm" => algorithm = Some(TsigAlgorithms::from_str(&value).map_err(|e| error(line, e.to_string()))?),
+            "secret" => secret = Some(base64::decode(&value).map_err(|e| error(line, format!("invalid secret: {}", e)))?),
+            _ => return Err(error(line, format!("unknown key clause {}", clause)))
+        }
+
+        match next(";")? {
+            (_, Token::End) => {}
+            (line, token) => return Err(error(line, format!("expected ;, found {}", token)))
+        }
+    }
+
+    match next(";")? {
+        (_, Token::End) => {}
+        (line, token) => return Err(error(line, format!("expected ;, found {}", token)))
+    }
+
+    let algorithm = algorithm.ok_or_else(|| error(line, format!("key {} has no algorithm", name)))?;
+    let secret = secret.ok_or_else(|| error(line, format!("key {} has no secret", name)))?;
+
+    Ok(TsigKey::new(&name, algorithm, &secret))
+}
+
+//NAMED.CONF SYNTAX, COMMENTS ARE #, // OR /* */
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, TsigKeyringError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '{' => tokens.push((line, Token::Open)),
+            '}' => tokens.push((line, Token::Close)),
+            ';' => tokens.push((line, Token::End)),
+            '#' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('*') if chars.next_if_eq(&'/').is_some() => break,
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(TsigKeyringError::ParseError { line: start, reason: "unterminated comment".to_string() })
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\n') => {
+                            line += 1;
+                            word.push('\n');
+                        }
+                        Some(c) => word.push(c),
+                        None => return Err(TsigKeyringError::ParseError { line: start, reason: "unterminated string".to_string() })
+                    }
+                }
+                tokens.push((start, Token::Quoted(word)));
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '{' | '}' | ';' | '"')) {
+                    word.push(c);
+                }
+                tokens.push((line, Token::Word(word)));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[test]
+fn test() {
+    let keyring: TsigKeyring = "\
+# tsig-keygen -a hmac-sha256 xfr.find9.net
+key \"xfr.find9.net\" {
+\talgorithm hmac-sha256;
+\tsecret \"MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=\";
+};
+
+/* LEGACY PEER */
+key legacy.find9.net. { algorithm hmac-md5; secret \"c2VjcmV0\"; }; // SHORT FORM
+".parse().unwrap();
+
+    assert_eq!(keyring.get_keys().len(), 2);
+    let key = keyring.get_key("XFR.find9.net.", TsigAlgorithms::HmacSha256).unwrap();
+    assert_eq!(key.get_secret(), b"0123456789abcdef0123456789abcdef");
+    assert!(keyring.get_key("xfr.find9.net", TsigAlgorithms::HmacSha512).is_none());
+    assert_eq!(keyring.get_key("legacy.find9.net", TsigAlgorithms::HmacMd5).unwrap().get_secret(), b"secret");
+
+    let written = keyring.to_string();
+    assert!(written.starts_with("key \"xfr.find9.net\" {\n\talgorithm hmac-sha256;\n\tsecret \"MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=\";\n};\n"));
+    assert!(written.contains("algorithm hmac-md5;"));
+    assert_eq!(written.parse::<TsigKeyring>().unwrap().get_keys(), keyring.get_keys());
+
+    let path = std::env::temp_dir().join(format!("rlibdns-keyring-{}.key", std::process::id()));
+    std::fs::write(&path, "stale").unwrap();
+    keyring.save(&path).unwrap();
+    assert_eq!(TsigKeyring::open(&path).unwrap().get_keys(), keyring.get_keys());
+    assert!(!path.with_extension("key.tmp").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!("key \"a\" {\n\talgorithm hmac-sha999;\n};".parse::<TsigKeyring>().err(), Some(TsigKeyringError::ParseError { line: 2, reason: "unknown tsig algorithm: hmac-sha999".to_string() }));
+    assert!(matches!("key \"a\" { algorithm hmac-sha256; };".parse::<TsigKeyring>(), Err(TsigKeyringError::ParseError { line: 1, .. })));
+    assert!(matches!("key \"a\" {\n\tsecret \"c2VjcmV0\";\n".parse::<TsigKeyring>(), Err(TsigKeyringError::ParseError { .. })));
+    assert!(matches!("options { };".parse::<TsigKeyring>(), Err(TsigKeyringError::ParseError { line: 1, .. })));
+}
@@ -0,0 +1,179 @@
+use crate::messages::inter::response_codes::ResponseCodes;
+use crate::messages::message::{mac_prefix, Message};
+use crate::tsig::tsig_key::TsigKey;
+
+//RFC 8945 S5.3.1 - A RECEIVER MUST SEE A SIGNED MESSAGE AT LEAST EVERY 100
+pub const MAX_UNSIGNED_MESSAGES: usize = 99;
+
+//RFC 8945 S5.3.1 - SIGNS OR VERIFIES A MULTI-MESSAGE RESPONSE, EACH MAC CHAINS FROM THE ONE BEFORE IT
+#[derive(Debug, Clone)]
+pub struct TsigStream {
+    key: TsigKey,
+    prefix: Vec<u8>,
+    messages: usize,
+    unsigned: usize
+}
+
+impl TsigStream {
+
+    //REQUEST_MAC IS THE MAC ON THE QUERY THAT STARTED THE STREAM
+    pub fn new(key: TsigKey, request_mac: Option<&[u8]>) -> Self {
+        Self {
+            key,
+            prefix: mac_prefix(request_mac),
+            messages: 0,
+            unsigned: 0
+        }
+    }
+
+    //FIRST MESSAGE CARRIES THE FULL TSIG VARIABLES, EVERY ONE AFTER ONLY THE TIMERS,
+    //THE MESSAGE HAS TO GO OUT WITH THE SAME MAX_PAYLOAD_LEN IT WAS SIGNED WITH
+    pub fn sign(&mut self, message: &mut Message, time_signed: u64, max_payload_len: usize) -> Vec<u8> {
+        let mac = message.sign_tsig_with(&self.key, time_signed, &self.prefix, self.messages > 0, max_payload_len);
+        self.prefix = mac_prefix(Some(&mac));
+        self.messages += 1;
+        mac
+    }
+
+    //SENDS A MESSAGE WITHOUT A TSIG, IT STILL GOES INTO THE NEXT MAC AS SENT WITH MAX_PAYLOAD_LEN
+    pub fn skip(&mut self, message: &Message, max_payload_len: usize) {
+        self.prefix.extend_from_slice(&message.to_bytes(max_payload_len));
+        self.messages += 1;
+    }
+
+    //UNSIGNED MESSAGES GO INTO THE NEXT DIGEST EXACTLY AS THEY WERE RECEIVED
+    pub fn verify(&mut self, message: &Message, now: u64) -> Result<(), ResponseCodes> {
+        if message.get_tsig().is_none() {
+            if self.messages == 0 || self.unsigned >= MAX_UNSIGNED_MESSAGES {
+                return Err(ResponseCodes::FormErr);
+            }
+
+            self.prefix.extend_from_slice(&message.unsigned_wire());
+            self.messages += 1;
+            self.unsigned += 1;
+            return Ok(());
+        }
+
+        let mac = message.verify_tsig_with(&self.key, now, &self.prefix, self.messages > 0)?;
+        self.prefix = mac_prefix(Some(&mac));
+        self.messages += 1;
+        self.unsigned = 0;
+        Ok(())
+    }
+
+    //THE LAST MESSAGE OF A STREAM MUST BE SIGNED
+    pub fn is_complete(&self) -> bool {
+        self.messages > 0 && self.unsigned == 0
+    }
+
+    pub fn get_key(&self) -> &TsigKey {
+        &self.key
+    }
+}
+
+#[test]
+fn test() {
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::messages::inter::rr_types::RRTypes;
+    use crate::messages::rr_query::RRQuery;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::inter::record_base::RecordBase;
+    use crate::records::soa_record::SoaRecord;
+    use crate::tsig::inter::tsig_algorithms::TsigAlgorithms;
+    use crate::tsig::tsig_keyring::TsigKeyring;
+    use crate::zone::inter::zone_types::ZoneTypes;
+    use crate::zone::zone::Zone;
+
+    let key = TsigKey::new("xfr.find9.net.", TsigAlgorithms::HmacSha256, b"0123456789abcdef0123456789abcdef");
+    let mut keyring = TsigKeyring::new();
+    keyring.add_key(key.clone());
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, SoaRecord::new("ns1.find9.net", "admin.find9.net", 1, 7200, 3600, 1209600, 60).upcast());
+    for i in 0..500 {
+        zone.add_record(&format!("host{}", i), 300, InARecord::new([10, 0, (i >> 8) as u8, i as u8].into()).upcast());
+    }
+
+    let mut query = Message::new(7);
+    query.add_query(RRQuery::new("find9.net", RRTypes::Axfr, RRClasses::In));
+    let request_mac = query.sign_tsig(&key, 1000, None);
+
+    let query = Message::from_bytes(&query.to_bytes(512)).unwrap();
+    assert_eq!(query.verify_tsig(&keyring, 1000, None), Ok(request_mac.clone()));
+
+    let mut signer = TsigStream::new(key.clone(), Some(&request_mac));
+    let messages: Vec<Vec<u8>> = zone.axfr(&query, 4096, Some((&mut signer, 1001))).iter().map(|m| m.to_bytes(4096)).collect();
+    assert!(messages.len() > 1);
+
+    let mut verifier = TsigStream::new(key.clone(), Some(&request_mac));
+    for buf in &messages {
+        verifier.verify(&Message::from_bytes(buf).unwrap(), 1002).unwrap();
+    }
+    assert!(verifier.is_complete());
+
+    //A MESSAGE TRUNCATED TO FIT IS SIGNED OVER WHAT IS ACTUALLY SENT
+    let mut signer = TsigStream::new(key.clone(), Some(&request_mac));
+    let mut message = Message::new(7);
+    message.set_qr(true);
+    for i in 0..100 {
+        message.add_section(0, &format!("host{}.find9.net", i), RRClasses::In, 300, InARecord::new([10, 0, 0, i].into()).upcast());
+    }
+    signer.sign(&mut message, 1001, 512);
+    let received = Message::from_bytes(&message.to_bytes(512)).unwrap();
+    assert!(received.is_truncated());
+    TsigStream::new(key.clone(), Some(&request_mac)).verify(&received, 1001).unwrap();
+
+    //OUT OF ORDER MESSAGES BREAK THE CHAIN
+    let mut verifier = TsigStream::new(key.clone(), Some(&request_mac));
+    assert_eq!(verifier.verify(&Message::from_bytes(&messages[1]).unwrap(), 1002), Err(ResponseCodes::BadSig));
+
+    //UNSIGNED MESSAGES IN THE MIDDLE ARE FOLDED INTO THE NEXT MAC
+    let mut signer = TsigStream::new(key.clone(), Some(&request_mac));
+    let mut verifier = TsigStream::new(key.clone(), Some(&request_mac));
+    for i in 0..5 {
+        let mut message = Message::new(7);
+        message.set_qr(true);
+        message.add_section(0, &format!("host{}.find9.net", i), RRClasses::In, 300, InARecord::new([10, 0, 0, i].into()).upcast());
+
+        match i % 2 {
+            0 => {
+                signer.sign(&mut message, 1001, 512);
+            }
+            _ => signer.skip(&message, 512)
+        }
+
+        verifier.verify(&Message::from_bytes(&message.to_bytes(512)).unwrap(), 1001).unwrap();
+        assert_eq!(verifier.is_complete(), i % 2 == 0);
+    }
+
+    //AN UNSIGNED MESSAGE FROM A PEER THAT DOESN'T COMPRESS IS FOLDED IN AS RECEIVED, NOT AS WE WOULD ENCODE IT
+    let mut signer = TsigStream::new(key.clone(), Some(&request_mac));
+    let mut first = Message::new(7);
+    first.set_qr(true);
+    let mac = signer.sign(&mut first, 1001, 512);
+
+    let mut unsigned = vec![0x0, 0x7, 0x80, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0];
+    for last in 1..3 {
+        unsigned.extend_from_slice(&[0x1, 0x61, 0x5, 0x66, 0x69, 0x6e, 0x64, 0x39, 0x3, 0x6e, 0x65, 0x74, 0x0]);
+        unsigned.extend_from_slice(&[0x0, 0x1, 0x0, 0x1, 0x0, 0x0, 0x1, 0x2c, 0x0, 0x4, 0xa, 0x0, 0x0, last]);
+    }
+    assert_ne!(Message::from_bytes(&unsigned).unwrap().to_bytes(512), unsigned);
+
+    //ONCE CHANGED THE RECEIVED BYTES NO LONGER DESCRIBE THE MESSAGE
+    let mut changed = Message::from_bytes(&unsigned).unwrap();
+    assert_eq!(changed.unsigned_wire(), unsigned);
+    changed.get_section_mut(0).pop();
+    assert_eq!(changed.unsigned_wire(), changed.to_bytes(512));
+
+    let mut prefix = mac_prefix(Some(&mac));
+    prefix.extend_from_slice(&unsigned);
+    let mut last = Message::new(7);
+    last.set_qr(true);
+    last.sign_tsig_with(&key, 1001, &prefix, true, 512);
+
+    let mut verifier = TsigStream::new(key, Some(&request_mac));
+    for buf in [first.to_bytes(512), unsigned, last.to_bytes(512)] {
+        verifier.verify(&Message::from_bytes(&buf).unwrap(), 1001).unwrap();
+    }
+    assert!(verifier.is_complete());
+}
@@ -0,0 +1,61 @@
+use std::io;
+
+//RFC 4648 S7 - BASE32 WITH EXTENDED HEX ALPHABET, NSEC3 USES IT WITHOUT PADDING
+pub fn encode(input: &[u8]) -> String {
+    const TABLE: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+    let mut output = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &byte in input {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            output.push(TABLE[((buffer >> bits) & 0x1F) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(TABLE[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+
+    output
+}
+
+pub fn decode(input: &str) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &c in input.trim_end_matches('=').as_bytes() {
+        let v = val(c).ok_or(io::Error::new(io::ErrorKind::InvalidInput, "Invalid base32hex"))?;
+        buffer = (buffer << 5) | v as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(output)
+}
+
+fn val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'v' => Some(c - b'a' + 10),
+        b'A'..=b'V' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(encode(b"foobar"), "cpnmuoj1e8");
+    assert_eq!(decode("CPNMUOJ1E8======").unwrap(), b"foobar");
+    assert_eq!(decode(&encode(&[0xff; 20])).unwrap(), vec![0xff; 20]);
+}
@@ -0,0 +1,368 @@
+use std::cmp::Ordering;
+
+//UNSIGNED, LITTLE ENDIAN 64 BIT LIMBS WITH NO TRAILING ZERO LIMBS, ONLY AS MUCH AS DNSSEC NEEDS
+//NOTHING HERE IS CONSTANT TIME
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BigUint {
+    limbs: Vec<u64>
+}
+
+impl Default for BigUint {
+
+    fn default() -> Self {
+        Self {
+            limbs: Vec::new()
+        }
+    }
+}
+
+impl BigUint {
+
+    pub fn from_u64(n: u64) -> Self {
+        Self::from_limbs(vec![n])
+    }
+
+    pub fn from_bytes_be(buf: &[u8]) -> Self {
+        let mut limbs = Vec::with_capacity(buf.len().div_ceil(8));
+
+        for chunk in buf.rchunks(8) {
+            let mut limb = [0u8; 8];
+            limb[8-chunk.len()..].copy_from_slice(chunk);
+            limbs.push(u64::from_be_bytes(limb));
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn from_bytes_le(buf: &[u8]) -> Self {
+        let mut buf = buf.to_vec();
+        buf.reverse();
+        Self::from_bytes_be(&buf)
+    }
+
+    //NONE IF THE VALUE DOESN'T FIT IN LEN BYTES
+    pub fn to_bytes_be(&self, len: usize) -> Option<Vec<u8>> {
+        if self.bits() > len*8 {
+            return None;
+        }
+
+        let mut buf = vec![0u8; len];
+        for (i, limb) in self.limbs.iter().enumerate() {
+            for (j, byte) in limb.to_le_bytes().iter().enumerate() {
+                if i*8+j < len {
+                    buf[len-1-(i*8+j)] = *byte;
+                }
+            }
+        }
+
+        Some(buf)
+    }
+
+    pub fn to_bytes_le(&self, len: usize) -> Option<Vec<u8>> {
+        let mut buf = self.to_bytes_be(len)?;
+        buf.reverse();
+        Some(buf)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len()*64-last.leading_zeros() as usize,
+            None => 0
+        }
+    }
+
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs.get(i/64).is_some_and(|limb| (limb >> (i%64)) & 1 == 1)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len+1);
+        let mut carry = 0u64;
+
+        for i in 0..len {
+            let (sum, c0) = self.limb(i).overflowing_add(other.limb(i));
+            let (sum, c1) = sum.overflowing_add(carry);
+            limbs.push(sum);
+            carry = (c0 | c1) as u64;
+        }
+        limbs.push(carry);
+
+        Self::from_limbs(limbs)
+    }
+
+    //SATURATES AT ZERO
+    pub fn sub(&self, other: &Self) -> Self {
+        if self.cmp(other) != Ordering::Greater {
+            return Self::default();
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0u64;
+
+        for i in 0..self.limbs.len() {
+            let (diff, b0) = self.limb(i).overflowing_sub(other.limb(i));
+            let (diff, b1) = diff.overflowing_sub(borrow);
+            limbs.push(diff);
+            borrow = (b0 | b1) as u64;
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut limbs = vec![0u64; self.limbs.len()+other.limbs.len()];
+
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let t = (*a as u128)*(*b as u128)+limbs[i+j] as u128+carry;
+                limbs[i+j] = t as u64;
+                carry = t >> 64;
+            }
+            limbs[i+other.limbs.len()] = carry as u64;
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn shl(&self, n: usize) -> Self {
+        let (words, bits) = (n/64, n%64);
+        let mut limbs = vec![0u64; words];
+
+        let mut carry = 0u64;
+        for limb in &self.limbs {
+            limbs.push((limb << bits) | carry);
+            carry = match bits {
+                0 => 0,
+                _ => limb >> (64-bits)
+            };
+        }
+        limbs.push(carry);
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn shr(&self, n: usize) -> Self {
+        let (words, bits) = (n/64, n%64);
+        if words >= self.limbs.len() {
+            return Self::default();
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len()-words);
+        for i in words..self.limbs.len() {
+            let high = match bits {
+                0 => 0,
+                _ => self.limb(i+1) << (64-bits)
+            };
+            limbs.push((self.limbs[i] >> bits) | high);
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    //SHIFT AND SUBTRACT, ONLY USED FOR ONE OFF REDUCTIONS
+    pub fn rem(&self, m: &Self) -> Self {
+        if m.is_zero() || self.cmp(m) == Ordering::Less {
+            return self.clone();
+        }
+
+        let mut r = Self::default();
+        for i in (0..self.bits()).rev() {
+            r = r.shl(1);
+            if self.bit(i) {
+                r = r.add(&Self::from_u64(1));
+            }
+
+            if r.cmp(m) != Ordering::Less {
+                r = r.sub(m);
+            }
+        }
+
+        r
+    }
+
+    fn limb(&self, i: usize) -> u64 {
+        self.limbs.get(i).copied().unwrap_or(0)
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self {
+            limbs
+        }
+    }
+}
+
+impl Ord for BigUint {
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//MONTGOMERY ARITHMETIC MOD AN ODD MODULUS, VALUES PASSED IN AND OUT OF MUL/ADD/SUB/POW ARE IN MONTGOMERY FORM
+#[derive(Clone, Debug)]
+pub struct Modulus {
+    m: BigUint,
+    len: usize,
+    m_inv: u64,
+    r2: BigUint
+}
+
+impl Modulus {
+
+    pub fn new(m: BigUint) -> Option<Self> {
+        if !m.bit(0) || m.bits() < 2 {
+            return None;
+        }
+
+        let len = m.limbs.len();
+
+        //NEWTON ITERATION FOR M^-1 MOD 2^64, EACH STEP DOUBLES THE CORRECT BITS
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m.limbs[0].wrapping_mul(inv)));
+        }
+
+        let r2 = BigUint::from_u64(1).shl(128*len).rem(&m);
+
+        Some(Self {
+            m,
+            len,
+            m_inv: inv.wrapping_neg(),
+            r2
+        })
+    }
+
+    pub fn get_modulus(&self) -> &BigUint {
+        &self.m
+    }
+
+    pub fn to_mont(&self, a: &BigUint) -> BigUint {
+        self.mul(&a.rem(&self.m), &self.r2)
+    }
+
+    pub fn from_mont(&self, a: &BigUint) -> BigUint {
+        self.mul(a, &BigUint::from_u64(1))
+    }
+
+    pub fn one(&self) -> BigUint {
+        self.to_mont(&BigUint::from_u64(1))
+    }
+
+    //CIOS MONTGOMERY PRODUCT, A * B * R^-1 MOD M
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let n = self.len;
+        let mut t = vec![0u64; n+2];
+
+        for i in 0..n {
+            let ai = a.limb(i) as u128;
+            let mut carry = 0u128;
+            for (j, tj) in t.iter_mut().enumerate().take(n) {
+                let sum = *tj as u128+ai*b.limb(j) as u128+carry;
+                *tj = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128+carry;
+            t[n] = sum as u64;
+            t[n+1] = (sum >> 64) as u64;
+
+            let q = t[0].wrapping_mul(self.m_inv) as u128;
+            let mut carry = (t[0] as u128+q*self.m.limbs[0] as u128) >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128+q*self.m.limbs[j] as u128+carry;
+                t[j-1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128+carry;
+            t[n-1] = sum as u64;
+            t[n] = t[n+1]+(sum >> 64) as u64;
+            t[n+1] = 0;
+        }
+
+        let t = BigUint::from_limbs(t);
+        match t.cmp(&self.m) {
+            Ordering::Less => t,
+            _ => t.sub(&self.m)
+        }
+    }
+
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let sum = a.add(b);
+        match sum.cmp(&self.m) {
+            Ordering::Less => sum,
+            _ => sum.sub(&self.m)
+        }
+    }
+
+    pub fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        match a.cmp(b) {
+            Ordering::Less => a.add(&self.m).sub(b),
+            _ => a.sub(b)
+        }
+    }
+
+    pub fn neg(&self, a: &BigUint) -> BigUint {
+        self.sub(&BigUint::default(), a)
+    }
+
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        let mut result = self.one();
+
+        for i in (0..e.bits()).rev() {
+            result = self.mul(&result, &result);
+            if e.bit(i) {
+                result = self.mul(&result, a);
+            }
+        }
+
+        result
+    }
+
+    //FERMAT, ONLY VALID FOR A PRIME MODULUS
+    pub fn inv(&self, a: &BigUint) -> BigUint {
+        self.pow(a, &self.m.sub(&BigUint::from_u64(2)))
+    }
+}
+
+#[test]
+fn test() {
+    use crate::utils::hex;
+
+    let a = BigUint::from_bytes_be(&hex::decode("0123456789abcdeffedcba98765432100f1e2d3c4b5a6978").unwrap());
+    let b = BigUint::from_bytes_be(&hex::decode("fedcba9876543210").unwrap());
+    assert_eq!(a.to_bytes_be(24).unwrap(), hex::decode("0123456789abcdeffedcba98765432100f1e2d3c4b5a6978").unwrap());
+    assert_eq!(a.to_bytes_be(23), None);
+    assert_eq!(BigUint::from_bytes_le(&a.to_bytes_le(24).unwrap()), a);
+
+    assert_eq!(a.add(&b).sub(&b), a);
+    assert_eq!(a.mul(&b).rem(&b), BigUint::default());
+    assert_eq!(a.shl(67).shr(67), a);
+    assert_eq!(hex::encode(&a.rem(&b).to_bytes_be(8).unwrap()), "184ea301d395ab58");
+
+    //2^127 - 1 IS PRIME
+    let m = Modulus::new(BigUint::from_u64(1).shl(127).sub(&BigUint::from_u64(1))).unwrap();
+    let x = m.to_mont(&a);
+    let y = m.to_mont(&b);
+    assert_eq!(m.from_mont(&m.mul(&x, &y)), a.mul(&b).rem(m.get_modulus()));
+    assert_eq!(m.from_mont(&m.mul(&x, &m.inv(&x))), BigUint::from_u64(1));
+    assert_eq!(m.from_mont(&m.add(&m.sub(&x, &y), &y)), a.rem(m.get_modulus()));
+    assert_eq!(m.from_mont(&m.pow(&y, &BigUint::from_u64(3))), b.mul(&b).mul(&b).rem(m.get_modulus()));
+    assert!(Modulus::new(BigUint::from_u64(10)).is_none());
+}
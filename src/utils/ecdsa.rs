@@ -0,0 +1,382 @@
+use crate::utils::bigint::{BigUint, Modulus};
+use crate::utils::{hex, hmac};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EcCurves {
+    P256,
+    P384
+}
+
+impl EcCurves {
+
+    //BYTES IN ONE COORDINATE OR ONE HALF OF A SIGNATURE
+    pub fn get_len(&self) -> usize {
+        match self {
+            Self::P256 => 32,
+            Self::P384 => 48
+        }
+    }
+
+    //RFC 6979 S3.2 - THE HMAC MATCHES THE DIGEST THE CURVE IS PAIRED WITH IN DNSSEC
+    fn hmac(&self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::P256 => hmac::hmac_sha256(key, message),
+            Self::P384 => hmac::hmac_sha384(key, message)
+        }
+    }
+
+    //FIPS 186-4 D.1.2 - BOTH CURVES HAVE A = -3
+    fn params(&self) -> Curve {
+        let (p, n, b, gx, gy) = match self {
+            Self::P256 => (
+                "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+                "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+                "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+                "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"
+            ),
+            Self::P384 => (
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+                "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+                "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+                "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f"
+            )
+        };
+
+        let int = |s: &str| BigUint::from_bytes_be(&hex::decode(s).unwrap_or_default());
+        let p = Modulus::new(int(p)).expect("curve prime is odd");
+        let n = Modulus::new(int(n)).expect("curve order is odd");
+
+        Curve {
+            b: p.to_mont(&int(b)),
+            g: Point {
+                x: p.to_mont(&int(gx)),
+                y: p.to_mont(&int(gy)),
+                z: p.one()
+            },
+            p,
+            n,
+            len: self.get_len()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Point {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint
+}
+
+//FIELD VALUES ARE KEPT IN MONTGOMERY FORM, POINTS IN JACOBIAN COORDINATES WITH Z = 0 AS INFINITY
+struct Curve {
+    p: Modulus,
+    n: Modulus,
+    b: BigUint,
+    g: Point,
+    len: usize
+}
+
+impl Curve {
+
+    fn infinity(&self) -> Point {
+        Point {
+            x: self.p.one(),
+            y: self.p.one(),
+            z: BigUint::default()
+        }
+    }
+
+    //X || Y UNCOMPRESSED, NONE UNLESS THE POINT IS ON THE CURVE
+    fn decode_point(&self, buf: &[u8]) -> Option<Point> {
+        if buf.len() != self.len*2 {
+            return None;
+        }
+
+        let x = BigUint::from_bytes_be(&buf[..self.len]);
+        let y = BigUint::from_bytes_be(&buf[self.len..]);
+        if &x >= self.p.get_modulus() || &y >= self.p.get_modulus() {
+            return None;
+        }
+
+        let (x, y) = (self.p.to_mont(&x), self.p.to_mont(&y));
+
+        //Y^2 = X^3 - 3X + B
+        let f = &self.p;
+        let x3 = f.mul(&f.mul(&x, &x), &x);
+        let three_x = f.add(&f.add(&x, &x), &x);
+        if f.mul(&y, &y) != f.add(&f.sub(&x3, &three_x), &self.b) {
+            return None;
+        }
+
+        Some(Point {
+            x,
+            y,
+            z: f.one()
+        })
+    }
+
+    fn encode_point(&self, point: &Point) -> Option<Vec<u8>> {
+        let (x, y) = self.to_affine(point)?;
+        let mut buf = x.to_bytes_be(self.len)?;
+        buf.extend_from_slice(&y.to_bytes_be(self.len)?);
+        Some(buf)
+    }
+
+    fn to_affine(&self, point: &Point) -> Option<(BigUint, BigUint)> {
+        if point.z.is_zero() {
+            return None;
+        }
+
+        let f = &self.p;
+        let z_inv = f.inv(&point.z);
+        let z_inv2 = f.mul(&z_inv, &z_inv);
+        Some((f.from_mont(&f.mul(&point.x, &z_inv2)), f.from_mont(&f.mul(&point.y, &f.mul(&z_inv2, &z_inv)))))
+    }
+
+    //DBL-2001-B
+    fn double(&self, point: &Point) -> Point {
+        if point.z.is_zero() || point.y.is_zero() {
+            return self.infinity();
+        }
+
+        let f = &self.p;
+        let delta = f.mul(&point.z, &point.z);
+        let gamma = f.mul(&point.y, &point.y);
+        let beta = f.mul(&point.x, &gamma);
+        let t = f.mul(&f.sub(&point.x, &delta), &f.add(&point.x, &delta));
+        let alpha = f.add(&f.add(&t, &t), &t);
+
+        let beta4 = f.add(&f.add(&beta, &beta), &f.add(&beta, &beta));
+        let x = f.sub(&f.mul(&alpha, &alpha), &f.add(&beta4, &beta4));
+
+        let yz = f.add(&point.y, &point.z);
+        let z = f.sub(&f.sub(&f.mul(&yz, &yz), &gamma), &delta);
+
+        let gamma2 = f.mul(&gamma, &gamma);
+        let gamma2_8 = f.add(&f.add(&f.add(&gamma2, &gamma2), &f.add(&gamma2, &gamma2)), &f.add(&f.add(&gamma2, &gamma2), &f.add(&gamma2, &gamma2)));
+        let y = f.sub(&f.mul(&alpha, &f.sub(&beta4, &x)), &gamma2_8);
+
+        Point {
+            x,
+            y,
+            z
+        }
+    }
+
+    //ADD-2007-BL
+    fn add(&self, a: &Point, b: &Point) -> Point {
+        if a.z.is_zero() {
+            return b.clone();
+        }
+
+        if b.z.is_zero() {
+            return a.clone();
+        }
+
+        let f = &self.p;
+        let z1z1 = f.mul(&a.z, &a.z);
+        let z2z2 = f.mul(&b.z, &b.z);
+        let u1 = f.mul(&a.x, &z2z2);
+        let u2 = f.mul(&b.x, &z1z1);
+        let s1 = f.mul(&f.mul(&a.y, &b.z), &z2z2);
+        let s2 = f.mul(&f.mul(&b.y, &a.z), &z1z1);
+
+        let h = f.sub(&u2, &u1);
+        let r = f.sub(&s2, &s1);
+
+        if h.is_zero() {
+            return match r.is_zero() {
+                true => self.double(a),
+                false => self.infinity()
+            };
+        }
+
+        let h2 = f.add(&h, &h);
+        let i = f.mul(&h2, &h2);
+        let j = f.mul(&h, &i);
+        let r = f.add(&r, &r);
+        let v = f.mul(&u1, &i);
+
+        let x = f.sub(&f.sub(&f.mul(&r, &r), &j), &f.add(&v, &v));
+        let s1j = f.mul(&s1, &j);
+        let y = f.sub(&f.mul(&r, &f.sub(&v, &x)), &f.add(&s1j, &s1j));
+        let zz = f.add(&a.z, &b.z);
+        let z = f.mul(&f.sub(&f.sub(&f.mul(&zz, &zz), &z1z1), &z2z2), &h);
+
+        Point {
+            x,
+            y,
+            z
+        }
+    }
+
+    //SHAMIR'S TRICK, U1 * G + U2 * Q
+    fn mul_add(&self, u1: &BigUint, u2: &BigUint, q: &Point) -> Point {
+        let gq = self.add(&self.g, q);
+        let mut result = self.infinity();
+
+        for i in (0..u1.bits().max(u2.bits())).rev() {
+            result = self.double(&result);
+
+            match (u1.bit(i), u2.bit(i)) {
+                (true, true) => result = self.add(&result, &gq),
+                (true, false) => result = self.add(&result, &self.g),
+                (false, true) => result = self.add(&result, q),
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    //FIPS 186-4 S6.4 - LEFTMOST BITS OF THE DIGEST, AS MANY AS THE ORDER HAS
+    fn digest_to_int(&self, digest: &[u8]) -> BigUint {
+        let e = BigUint::from_bytes_be(digest);
+        let n_bits = self.n.get_modulus().bits();
+
+        match digest.len()*8 > n_bits {
+            true => e.shr(digest.len()*8-n_bits),
+            false => e
+        }
+    }
+}
+
+//RFC 6605 S4 - PUBLIC KEY IS X || Y, SIGNATURE IS R || S
+pub fn verify(curve: EcCurves, public_key: &[u8], digest: &[u8], signature: &[u8]) -> bool {
+    let curve = curve.params();
+
+    if signature.len() != curve.len*2 {
+        return false;
+    }
+
+    let Some(q) = curve.decode_point(public_key) else {
+        return false;
+    };
+
+    let r = BigUint::from_bytes_be(&signature[..curve.len]);
+    let s = BigUint::from_bytes_be(&signature[curve.len..]);
+    let n = &curve.n;
+    if r.is_zero() || s.is_zero() || &r >= n.get_modulus() || &s >= n.get_modulus() {
+        return false;
+    }
+
+    let w = n.inv(&n.to_mont(&s));
+    let u1 = n.from_mont(&n.mul(&n.to_mont(&curve.digest_to_int(digest)), &w));
+    let u2 = n.from_mont(&n.mul(&n.to_mont(&r), &w));
+
+    match curve.to_affine(&curve.mul_add(&u1, &u2, &q)) {
+        Some((x, _)) => x.rem(n.get_modulus()) == r,
+        None => false
+    }
+}
+
+//RFC 6605 S4 - X || Y OF D * G
+pub fn public_key(curve: EcCurves, private_key: &[u8]) -> Option<Vec<u8>> {
+    let params = curve.params();
+    let d = BigUint::from_bytes_be(private_key);
+    if d.is_zero() || &d >= params.n.get_modulus() {
+        return None;
+    }
+
+    params.encode_point(&params.mul_add(&d, &BigUint::default(), &params.g))
+}
+
+//RFC 6979 - K COMES FROM HMAC-DRBG OVER THE KEY AND DIGEST, SO NO RANDOMNESS IS NEEDED AND A BAD RNG CAN'T LEAK THE KEY
+pub fn sign(curve: EcCurves, private_key: &[u8], digest: &[u8]) -> Option<Vec<u8>> {
+    let params = curve.params();
+    let n = &params.n;
+    let d = BigUint::from_bytes_be(private_key);
+    if d.is_zero() || &d >= n.get_modulus() {
+        return None;
+    }
+
+    let x = d.to_bytes_be(params.len)?;
+    let e = params.digest_to_int(digest).rem(n.get_modulus());
+    let h = e.to_bytes_be(params.len)?;
+
+    let hlen = curve.hmac(&[], &[]).len();
+    let mut v = vec![0x01u8; hlen];
+    let mut k = vec![0x00u8; hlen];
+
+    for round in [0x00u8, 0x01] {
+        k = curve.hmac(&k, &[v.as_slice(), &[round], &x, &h].concat());
+        v = curve.hmac(&k, &v);
+    }
+
+    loop {
+        let mut t = Vec::new();
+        while t.len() < params.len {
+            v = curve.hmac(&k, &v);
+            t.extend_from_slice(&v);
+        }
+
+        let nonce = params.digest_to_int(&t[..params.len]);
+        if !nonce.is_zero() && &nonce < n.get_modulus() {
+            if let Some((rx, _)) = params.to_affine(&params.mul_add(&nonce, &BigUint::default(), &params.g)) {
+                let r = rx.rem(n.get_modulus());
+
+                //S = K^-1 (E + R D)
+                let rd = n.mul(&n.to_mont(&r), &n.to_mont(&d));
+                let s = n.from_mont(&n.mul(&n.inv(&n.to_mont(&nonce)), &n.add(&n.to_mont(&e), &rd)));
+
+                if !r.is_zero() && !s.is_zero() {
+                    let mut signature = r.to_bytes_be(params.len)?;
+                    signature.extend_from_slice(&s.to_bytes_be(params.len)?);
+                    return Some(signature);
+                }
+            }
+        }
+
+        k = curve.hmac(&k, &[v.as_slice(), &[0x00]].concat());
+        v = curve.hmac(&k, &v);
+    }
+}
+
+#[test]
+fn test() {
+    use crate::utils::{base64, sha256, sha512};
+
+    for curve in [EcCurves::P256, EcCurves::P384] {
+        let params = curve.params();
+        let (x, y) = params.to_affine(&params.g).unwrap();
+        let g = [x.to_bytes_be(params.len).unwrap(), y.to_bytes_be(params.len).unwrap()].concat();
+        assert!(params.decode_point(&g).is_some());
+
+        //N * G IS INFINITY
+        let n = params.n.get_modulus();
+        assert!(params.mul_add(&n.sub(&BigUint::from_u64(1)), &BigUint::from_u64(1), &params.g).z.is_zero());
+    }
+
+    let public_key = base64::decode("d27WGJnXTl/1JdZqxLxtqmUdq3vfxQsUuhHKrOjqulfMngGWPtXndGWWg8fXo4t8zy9KrBPgaY6L/Db8YBGJrw==").unwrap();
+    let signature = base64::decode("Grs0B6rLtBdbqAsuSya4DNMUVSBGK3I+JwlUFpE6MqRaCGLBSCe6G1m7/YW/RZU3Fm7hs6zopA5vKYKGouS0zg==").unwrap();
+    assert!(verify(EcCurves::P256, &public_key, &sha256::hash(b"rlibdns"), &signature));
+    assert!(!verify(EcCurves::P256, &public_key, &sha256::hash(b"rlibdnS"), &signature));
+
+    let mut off_curve = public_key.clone();
+    off_curve[63] ^= 1;
+    assert!(!verify(EcCurves::P256, &off_curve, &sha256::hash(b"rlibdns"), &signature));
+
+    let public_key = base64::decode("S2Ptegeb3u+HAnfB2gQrB84DhTgd8gstbo8ISYxwcgYle7SfTi2DD9TgcCFQsaCpcZlCPTkhj7pudKl8virqjV06vUR755KfWLNMbPDCGeeyi6DkfStbtTv5n7fNh2uX").unwrap();
+    let signature = base64::decode("dsqhLZVOOSwDr7jQot4gksAoAgJsSc+2u/ldvVxFYssG4xGt3OOIl2hF3lLcxeGmjk/wTkmeVtS95qAkSmz9OsOVFRdNjjWdSfGY/PubzhY+sWZK2/m3Tdvuztx+4WXH").unwrap();
+    assert!(verify(EcCurves::P384, &public_key, &sha512::hash_384(b"rlibdns"), &signature));
+    assert!(!verify(EcCurves::P384, &public_key, &sha512::hash_384(b"rlibdns"), &signature[..95]));
+
+    //RFC 6979 A.2.5 AND A.2.6, MESSAGE "sample"
+    let private_key = hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+    let public_key = self::public_key(EcCurves::P256, &private_key).unwrap();
+    assert_eq!(hex::encode(&public_key), "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299");
+    let signature = sign(EcCurves::P256, &private_key, &sha256::hash(b"sample")).unwrap();
+    assert_eq!(hex::encode(&signature), "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8");
+    assert!(verify(EcCurves::P256, &public_key, &sha256::hash(b"sample"), &signature));
+
+    let private_key = hex::decode("6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5").unwrap();
+    let public_key = self::public_key(EcCurves::P384, &private_key).unwrap();
+    assert_eq!(hex::encode(&public_key), "ec3a4e415b4e19a4568618029f427fa5da9a8bc4ae92e02e06aae5286b300c64def8f0ea9055866064a254515480bc138015d9b72d7d57244ea8ef9ac0c621896708a59367f9dfb9f54ca84b3f1c9db1288b231c3ae0d4fe7344fd2533264720");
+    let signature = sign(EcCurves::P384, &private_key, &sha512::hash_384(b"sample")).unwrap();
+    assert_eq!(hex::encode(&signature), "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe4699ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8");
+    assert!(verify(EcCurves::P384, &public_key, &sha512::hash_384(b"sample"), &signature));
+
+    assert!(sign(EcCurves::P256, &[0; 32], &sha256::hash(b"sample")).is_none());
+}
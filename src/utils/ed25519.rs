@@ -0,0 +1,267 @@
+use crate::utils::bigint::{BigUint, Modulus};
+use crate::utils::{hex, sha512};
+
+pub const PRIVATE_KEY_LEN: usize = 32;
+pub const PUBLIC_KEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+
+//RFC 8032 S5.1
+const D: &str = "52036cee2b6ffe738cc740797779e89800700a4d4141d8ab75eb4dca135978a3";
+const L: &str = "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed";
+const BASE: &str = "5866666666666666666666666666666666666666666666666666666666666666";
+
+#[derive(Clone, Debug)]
+struct Point {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+    t: BigUint
+}
+
+//TWISTED EDWARDS IN EXTENDED COORDINATES, FIELD VALUES IN MONTGOMERY FORM
+struct Curve {
+    p: Modulus,
+    l: Modulus,
+    d2: BigUint,
+    d: BigUint
+}
+
+impl Curve {
+
+    fn new() -> Self {
+        let int = |s: &str| BigUint::from_bytes_be(&hex::decode(s).unwrap_or_default());
+        let p = Modulus::new(BigUint::from_u64(1).shl(255).sub(&BigUint::from_u64(19))).expect("field prime is odd");
+        let l = Modulus::new(int(L)).expect("group order is odd");
+        let d = p.to_mont(&int(D));
+
+        Self {
+            d2: p.add(&d, &d),
+            d,
+            p,
+            l
+        }
+    }
+
+    fn identity(&self) -> Point {
+        Point {
+            x: BigUint::default(),
+            y: self.p.one(),
+            z: self.p.one(),
+            t: BigUint::default()
+        }
+    }
+
+    //RFC 8032 S5.1.3
+    fn decode_point(&self, buf: &[u8]) -> Option<Point> {
+        if buf.len() != PUBLIC_KEY_LEN {
+            return None;
+        }
+
+        let mut y = buf.to_vec();
+        let sign = y[31] >> 7 == 1;
+        y[31] &= 0x7f;
+
+        let y = BigUint::from_bytes_le(&y);
+        if &y >= self.p.get_modulus() {
+            return None;
+        }
+
+        let f = &self.p;
+        let y = f.to_mont(&y);
+        let y2 = f.mul(&y, &y);
+        let u = f.sub(&y2, &f.one());
+        let v = f.add(&f.mul(&self.d, &y2), &f.one());
+
+        //X = U V^3 (U V^7)^((P-5)/8)
+        let v3 = f.mul(&f.mul(&v, &v), &v);
+        let v7 = f.mul(&f.mul(&v3, &v3), &v);
+        let exponent = f.get_modulus().sub(&BigUint::from_u64(5)).shr(3);
+        let mut x = f.mul(&f.mul(&u, &v3), &f.pow(&f.mul(&u, &v7), &exponent));
+
+        let vx2 = f.mul(&v, &f.mul(&x, &x));
+        if vx2 != u {
+            if vx2 != f.neg(&u) {
+                return None;
+            }
+
+            let sqrt_m1 = f.pow(&f.to_mont(&BigUint::from_u64(2)), &f.get_modulus().sub(&BigUint::from_u64(1)).shr(2));
+            x = f.mul(&x, &sqrt_m1);
+        }
+
+        let x_int = f.from_mont(&x);
+        if x_int.is_zero() && sign {
+            return None;
+        }
+
+        if x_int.bit(0) != sign {
+            x = f.neg(&x);
+        }
+
+        Some(Point {
+            t: f.mul(&x, &y),
+            x,
+            y,
+            z: f.one()
+        })
+    }
+
+    fn encode_point(&self, point: &Point) -> Vec<u8> {
+        let f = &self.p;
+        let z_inv = f.inv(&point.z);
+        let x = f.from_mont(&f.mul(&point.x, &z_inv));
+        let y = f.from_mont(&f.mul(&point.y, &z_inv));
+
+        let mut buf = y.to_bytes_le(32).unwrap_or_default();
+        if x.bit(0) {
+            buf[31] |= 0x80;
+        }
+
+        buf
+    }
+
+    //ADD-2008-HWCD-3, COMPLETE SO IT ALSO DOUBLES
+    fn add(&self, a: &Point, b: &Point) -> Point {
+        let f = &self.p;
+        let aa = f.mul(&f.sub(&a.y, &a.x), &f.sub(&b.y, &b.x));
+        let bb = f.mul(&f.add(&a.y, &a.x), &f.add(&b.y, &b.x));
+        let c = f.mul(&f.mul(&a.t, &self.d2), &b.t);
+        let zz = f.mul(&a.z, &b.z);
+        let d = f.add(&zz, &zz);
+
+        let e = f.sub(&bb, &aa);
+        let ff = f.sub(&d, &c);
+        let g = f.add(&d, &c);
+        let h = f.add(&bb, &aa);
+
+        Point {
+            x: f.mul(&e, &ff),
+            y: f.mul(&g, &h),
+            z: f.mul(&ff, &g),
+            t: f.mul(&e, &h)
+        }
+    }
+
+    fn neg(&self, point: &Point) -> Point {
+        Point {
+            x: self.p.neg(&point.x),
+            y: point.y.clone(),
+            z: point.z.clone(),
+            t: self.p.neg(&point.t)
+        }
+    }
+
+    fn mul(&self, k: &BigUint, point: &Point) -> Point {
+        let mut result = self.identity();
+
+        for i in (0..k.bits()).rev() {
+            result = self.add(&result, &result);
+            if k.bit(i) {
+                result = self.add(&result, point);
+            }
+        }
+
+        result
+    }
+
+    fn base(&self) -> Point {
+        self.decode_point(&hex::decode(BASE).unwrap_or_default()).expect("base point decodes")
+    }
+
+    fn hash_to_scalar(&self, parts: &[&[u8]]) -> BigUint {
+        let mut hasher = sha512::Sha512::new();
+        for part in parts {
+            hasher.update(part);
+        }
+
+        BigUint::from_bytes_le(&hasher.finalize()).rem(self.l.get_modulus())
+    }
+}
+
+//RFC 8032 S5.1.7 - [S]B = R + [K]A, CHECKED BY ENCODING [S]B - [K]A AND COMPARING WITH R
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if signature.len() != SIGNATURE_LEN {
+        return false;
+    }
+
+    let curve = Curve::new();
+
+    let Some(a) = curve.decode_point(public_key) else {
+        return false;
+    };
+
+    let s = BigUint::from_bytes_le(&signature[32..]);
+    if &s >= curve.l.get_modulus() || curve.decode_point(&signature[..32]).is_none() {
+        return false;
+    }
+
+    let k = curve.hash_to_scalar(&[&signature[..32], public_key, message]);
+    let check = curve.add(&curve.mul(&s, &curve.base()), &curve.neg(&curve.mul(&k, &a)));
+
+    curve.encode_point(&check) == signature[..32]
+}
+
+//RFC 8032 S5.1.5 - THE PRIVATE KEY IS THE 32 BYTE SEED, THE SCALAR AND NONCE PREFIX COME FROM ITS HASH
+fn expand(curve: &Curve, seed: &[u8]) -> Option<(BigUint, Vec<u8>, Vec<u8>)> {
+    if seed.len() != PRIVATE_KEY_LEN {
+        return None;
+    }
+
+    let h = sha512::hash(seed);
+    let mut scalar = h[..32].to_vec();
+    scalar[0] &= 0xf8;
+    scalar[31] &= 0x7f;
+    scalar[31] |= 0x40;
+
+    let s = BigUint::from_bytes_le(&scalar);
+    let public_key = curve.encode_point(&curve.mul(&s, &curve.base()));
+    Some((s, h[32..].to_vec(), public_key))
+}
+
+pub fn public_key(seed: &[u8]) -> Option<Vec<u8>> {
+    let (_, _, public_key) = expand(&Curve::new(), seed)?;
+    Some(public_key)
+}
+
+//RFC 8032 S5.1.6
+pub fn sign(seed: &[u8], message: &[u8]) -> Option<Vec<u8>> {
+    let curve = Curve::new();
+    let (s, prefix, public_key) = expand(&curve, seed)?;
+
+    let r = curve.hash_to_scalar(&[&prefix, message]);
+    let mut signature = curve.encode_point(&curve.mul(&r, &curve.base()));
+
+    //S = (R + K * S) MOD L
+    let k = curve.hash_to_scalar(&[&signature, &public_key, message]);
+    let l = curve.l.get_modulus();
+    signature.extend_from_slice(&r.add(&k.mul(&s).rem(l)).rem(l).to_bytes_le(32)?);
+
+    Some(signature)
+}
+
+#[test]
+fn test() {
+    //RFC 8032 S7.1 TEST 1 AND TEST 2
+    let public_key = hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+    let signature = hex::decode("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b").unwrap();
+    assert!(verify(&public_key, b"", &signature));
+    assert!(!verify(&public_key, b"\x00", &signature));
+
+    let seed = hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+    assert_eq!(self::public_key(&seed), Some(public_key.clone()));
+    assert_eq!(sign(&seed, b""), Some(signature.clone()));
+    assert!(sign(&seed[1..], b"").is_none());
+
+    let public_key = hex::decode("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c").unwrap();
+    let signature = hex::decode("92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00").unwrap();
+    assert!(verify(&public_key, b"\x72", &signature));
+
+    let mut forged = signature.clone();
+    forged[63] |= 0x10;
+    assert!(!verify(&public_key, b"\x72", &forged));
+    assert!(!verify(&public_key[1..], b"\x72", &signature));
+
+    let curve = Curve::new();
+    let base = curve.base();
+    assert_eq!(hex::encode(&curve.encode_point(&base)), BASE);
+    assert_eq!(curve.encode_point(&curve.mul(curve.l.get_modulus(), &base)), curve.encode_point(&curve.identity()));
+}
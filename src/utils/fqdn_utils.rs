@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
 
 pub fn pack_fqdn(fqdn: &str, compression_data: &mut HashMap<String, usize>, off: usize, compress: bool) -> Vec<u8> {
     if fqdn.is_empty() {
@@ -36,40 +38,74 @@ pub fn pack_fqdn(fqdn: &str, compression_data: &mut HashMap<String, usize>, off:
     buf
 }
 
-pub fn unpack_fqdn(buf: &[u8], off: usize) -> (String, usize) {
+pub const MAX_FQDN_LEN: usize = 255;
+pub const MAX_LABEL_LEN: usize = 63;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FqdnParseError(pub String);
+
+impl fmt::Display for FqdnParseError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub fn unpack_fqdn(buf: &[u8], off: usize) -> Result<(String, usize), FqdnParseError> {
     let mut builder: Vec<&str> = Vec::new();
     let mut pos = off;
     let mut jumped = false;
     let mut original_pos = pos;
+    let mut wire_len = 0;
+
+    loop {
+        if pos >= buf.len() {
+            return Err(FqdnParseError(format!("name at offset {} runs past end of buffer", off)));
+        }
 
-    while pos < buf.len() {
         let length = buf[pos] as usize;
+        let label_pos = pos;
         pos += 1;
 
         if length == 0 {
             break;
         }
 
-        if (length & 0xC0) == 0xC0 {
-            if pos >= buf.len() {
-                break;
-            }
-            let pointer_offset = ((length & 0x3F) << 8) | buf[pos] as usize;
-            pos += 1;
-
-            if !jumped {
-                original_pos = pos;
+        match length & 0xC0 {
+            0xC0 => {
+                if pos >= buf.len() {
+                    return Err(FqdnParseError(format!("truncated compression pointer at offset {}", label_pos)));
+                }
+
+                let pointer_offset = ((length & 0x3F) << 8) | buf[pos] as usize;
+                pos += 1;
+
+                //POINTERS MAY ONLY REFER TO PRIOR DATA, THIS ALSO RULES OUT LOOPS
+                if pointer_offset >= label_pos {
+                    return Err(FqdnParseError(format!("forward compression pointer at offset {}", label_pos)));
+                }
+
+                if !jumped {
+                    original_pos = pos;
+                }
+                pos = pointer_offset;
+                jumped = true;
             }
-            pos = pointer_offset;
-            jumped = true;
-
-        } else {
-            if pos + length > buf.len() {
-                break;
+            0x00 => {
+                if pos + length > buf.len() {
+                    return Err(FqdnParseError(format!("label at offset {} runs past end of buffer", label_pos)));
+                }
+
+                wire_len += length + 1;
+                if wire_len + 1 > MAX_FQDN_LEN {
+                    return Err(FqdnParseError(format!("name at offset {} exceeds {} bytes", off, MAX_FQDN_LEN)));
+                }
+
+                builder.push(std::str::from_utf8(&buf[pos..pos + length])
+                    .map_err(|_| FqdnParseError(format!("label at offset {} is not valid utf-8", label_pos)))?);
+                pos += length;
             }
-
-            builder.push(std::str::from_utf8(&buf[pos..pos + length]).unwrap());
-            pos += length;
+            _ => return Err(FqdnParseError(format!("unsupported label type 0x{:02x} at offset {}", length & 0xC0, label_pos)))
         }
     }
 
@@ -80,7 +116,7 @@ pub fn unpack_fqdn(buf: &[u8], off: usize) -> (String, usize) {
         pos
     };
 
-    (builder.join("."), final_pos - off)
+    Ok((builder.join("."), final_pos - off))
 }
 
 pub fn encode_fqdn(fqdn: &str) -> Vec<u8> {
@@ -132,9 +168,9 @@ pub fn fqdn_to_relative(apex: &str, child: &str) -> Option<String> {
         return Some(String::new());
     }
 
-    if let Some(stripped) = child.strip_suffix(apex) {
-        return Some(stripped.trim_end_matches('.').to_string());
+    if apex.is_empty() {
+        return Some(child.to_string());
     }
 
-    None
+    child.strip_suffix(apex)?.strip_suffix('.').map(|stripped| stripped.to_string())
 }
@@ -0,0 +1,62 @@
+use crate::utils::{md5, sha1, sha256, sha512};
+
+//RFC 2104 - H(K ^ OPAD, H(K ^ IPAD, TEXT))
+pub fn hmac(hash: fn(&[u8]) -> Vec<u8>, block_len: usize, key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut key = match key.len() > block_len {
+        true => hash(key),
+        false => key.to_vec()
+    };
+    key.resize(block_len, 0);
+
+    let mut inner: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(message);
+
+    let mut outer: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&hash(&inner));
+
+    hash(&outer)
+}
+
+pub fn hmac_md5(key: &[u8], message: &[u8]) -> Vec<u8> {
+    hmac(|buf| md5::hash(buf).to_vec(), md5::BLOCK_LEN, key, message)
+}
+
+pub fn hmac_sha1(key: &[u8], message: &[u8]) -> Vec<u8> {
+    hmac(|buf| sha1::hash(buf).to_vec(), sha1::BLOCK_LEN, key, message)
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    hmac(|buf| sha256::hash(buf).to_vec(), sha256::BLOCK_LEN, key, message)
+}
+
+pub fn hmac_sha384(key: &[u8], message: &[u8]) -> Vec<u8> {
+    hmac(|buf| sha512::hash_384(buf).to_vec(), sha512::BLOCK_LEN, key, message)
+}
+
+pub fn hmac_sha512(key: &[u8], message: &[u8]) -> Vec<u8> {
+    hmac(|buf| sha512::hash(buf).to_vec(), sha512::BLOCK_LEN, key, message)
+}
+
+//CONSTANT TIME SO A FORGED MAC CAN'T BE FOUND BYTE BY BYTE
+pub fn verify(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[test]
+fn test() {
+    use crate::utils::hex;
+
+    //RFC 2202 / RFC 4231 TEST CASE 2
+    assert_eq!(hex::encode(&hmac_md5(b"Jefe", b"what do ya want for nothing?")), "750c783e6ab0b503eaa86e310a5db738");
+    assert_eq!(hex::encode(&hmac_sha1(b"Jefe", b"what do ya want for nothing?")), "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79");
+    assert_eq!(hex::encode(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    assert_eq!(hex::encode(&hmac_sha384(b"Jefe", b"what do ya want for nothing?")), "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649");
+    assert_eq!(hex::encode(&hmac_sha512(b"Jefe", b"what do ya want for nothing?")), "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737");
+
+    //RFC 4231 TEST CASE 6 - KEY LONGER THAN THE BLOCK IS HASHED FIRST
+    assert_eq!(hex::encode(&hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")), "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+
+    assert!(verify(b"abc", b"abc"));
+    assert!(!verify(b"abc", b"abd"));
+    assert!(!verify(b"abc", b"ab"));
+}
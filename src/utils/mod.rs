@@ -3,6 +3,16 @@ pub mod index_map;
 pub mod trie;
 pub mod random;
 pub mod base64;
+pub mod base32;
 pub mod hex;
+pub mod md5;
+pub mod sha1;
+pub mod sha256;
+pub mod sha512;
+pub mod hmac;
+pub mod bigint;
+pub mod rsa;
+pub mod ecdsa;
+pub mod ed25519;
 pub mod coord_utils;
 pub mod time_utils;
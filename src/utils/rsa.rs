@@ -0,0 +1,105 @@
+use crate::utils::bigint::{BigUint, Modulus};
+
+//RFC 8017 S9.2 - DER DIGESTINFO PREFIXES FOR EMSA-PKCS1-V1_5
+pub const SHA1_DIGEST_INFO: [u8; 15] = [0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14];
+pub const SHA256_DIGEST_INFO: [u8; 19] = [0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20];
+pub const SHA512_DIGEST_INFO: [u8; 19] = [0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40];
+
+//RFC 3110 S2 - EXPONENT LENGTH IN ONE BYTE, OR ZERO THEN TWO BYTES, THEN THE EXPONENT AND THE MODULUS
+pub fn decode_public_key(buf: &[u8]) -> Option<(BigUint, BigUint)> {
+    let (exponent_len, off) = match *buf.first()? {
+        0 => (u16::from_be_bytes([*buf.get(1)?, *buf.get(2)?]) as usize, 3),
+        len => (len as usize, 1)
+    };
+
+    if exponent_len == 0 || buf.len() <= off+exponent_len {
+        return None;
+    }
+
+    Some((BigUint::from_bytes_be(&buf[off..off+exponent_len]), BigUint::from_bytes_be(&buf[off+exponent_len..])))
+}
+
+pub fn encode_public_key(exponent: &BigUint, modulus: &BigUint) -> Vec<u8> {
+    let exponent = exponent.to_bytes_be(exponent.bits().div_ceil(8)).unwrap_or_default();
+
+    let mut buf = match exponent.len() {
+        1..=255 => vec![exponent.len() as u8],
+        len => vec![0, (len >> 8) as u8, len as u8]
+    };
+    buf.extend_from_slice(&exponent);
+    buf.extend_from_slice(&modulus.to_bytes_be(modulus.bits().div_ceil(8)).unwrap_or_default());
+
+    buf
+}
+
+//RFC 8017 S8.2.2 - RSASSA-PKCS1-V1_5 OVER AN ALREADY COMPUTED DIGEST
+pub fn verify(public_key: &[u8], digest_info: &[u8], digest: &[u8], signature: &[u8]) -> bool {
+    let Some((exponent, modulus)) = decode_public_key(public_key) else {
+        return false;
+    };
+
+    let len = modulus.bits().div_ceil(8);
+    let s = BigUint::from_bytes_be(signature);
+    if signature.len() != len || s >= modulus {
+        return false;
+    }
+
+    let Some(m) = Modulus::new(modulus) else {
+        return false;
+    };
+
+    let em = m.from_mont(&m.pow(&m.to_mont(&s), &exponent));
+
+    match (em.to_bytes_be(len), encode(digest_info, digest, len)) {
+        (Some(em), Some(expected)) => em == expected,
+        _ => false
+    }
+}
+
+//RFC 8017 S8.2.1 - NO CRT, SIGNING IS RARE ENOUGH THAT THE SIMPLE WAY IS FINE
+pub fn sign(modulus: &BigUint, private_exponent: &BigUint, digest_info: &[u8], digest: &[u8]) -> Option<Vec<u8>> {
+    let len = modulus.bits().div_ceil(8);
+    let em = BigUint::from_bytes_be(&encode(digest_info, digest, len)?);
+
+    let m = Modulus::new(modulus.clone())?;
+    m.from_mont(&m.pow(&m.to_mont(&em), private_exponent)).to_bytes_be(len)
+}
+
+//RFC 8017 S9.2 - 00 01 FF .. FF 00 DIGESTINFO DIGEST
+fn encode(digest_info: &[u8], digest: &[u8], len: usize) -> Option<Vec<u8>> {
+    let t_len = digest_info.len()+digest.len();
+    if len < t_len+11 {
+        return None;
+    }
+
+    let mut buf = vec![0xff; len];
+    buf[0] = 0x00;
+    buf[1] = 0x01;
+    buf[len-t_len-1] = 0x00;
+    buf[len-t_len..len-digest.len()].copy_from_slice(digest_info);
+    buf[len-digest.len()..].copy_from_slice(digest);
+
+    Some(buf)
+}
+
+#[test]
+fn test() {
+    use crate::utils::{base64, sha256};
+
+    let public_key = base64::decode("AwEAAa3ux4j50Emwloq/OBb5otE/ocexiyqWeQp4n2XeGSosl4he9tgGsR3GheElvmYF+0PM8xV1flU6whH8K/0pLeZLS+FMf2bBZUjuzUMUQ/wHI8N8UKnpQiq/EoUgtGeGoZJa9TN8NUKh5eZaYyu86hUbQli3wyuoFWYvrqKBxaY5").unwrap();
+    let signature = base64::decode("Nm54Tq2vVnPz3qflJeyum3U+Sj1/IXquiUbkSFqnX69CMnCVGTNjHcTOBB2juN1wq00AxxC9gbBOSjd599FHoCUb/2qhjWZpv+4Kw9iW0XsIjMrdQ/fYwSzT4PbTLIcDEW57WXWprhfhfKr1iNqOwcmE7i/N0YBKDyB1AnsRhtE=").unwrap();
+
+    let (exponent, modulus) = decode_public_key(&public_key).unwrap();
+    assert_eq!(exponent, BigUint::from_u64(65537));
+    assert_eq!(encode_public_key(&exponent, &modulus), public_key);
+
+    assert!(verify(&public_key, &SHA256_DIGEST_INFO, &sha256::hash(b"rlibdns"), &signature));
+    assert!(!verify(&public_key, &SHA256_DIGEST_INFO, &sha256::hash(b"rlibdnS"), &signature));
+    assert!(!verify(&public_key, &SHA512_DIGEST_INFO, &sha256::hash(b"rlibdns"), &signature));
+    assert!(!verify(&public_key, &SHA256_DIGEST_INFO, &sha256::hash(b"rlibdns"), &signature[1..]));
+    assert!(decode_public_key(&[3, 1, 0, 1]).is_none());
+
+    //PKCS1 V1.5 IS DETERMINISTIC SO OUR SIGNATURE HAS TO MATCH BYTE FOR BYTE
+    let private_exponent = BigUint::from_bytes_be(&base64::decode("Ok4N41Pix6EDhfU9xy3dr4lGIRvaC+evIZx3yn0eMG4MSfGTi60KmHGhFW1Cmw6sQPkmD254b/encMH4uP+h28CtwTYzrovLuOkQNWce//I2P1iKdD27lgm5lUryrB9PPhpA6C+v8oE/TghfNYZtVkRWV+SYH7ch1guO09EVxWU=").unwrap());
+    assert_eq!(sign(&modulus, &private_exponent, &SHA256_DIGEST_INFO, &sha256::hash(b"rlibdns")), Some(signature));
+}
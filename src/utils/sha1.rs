@@ -0,0 +1,126 @@
+pub const DIGEST_LEN: usize = 20;
+pub const BLOCK_LEN: usize = 64;
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buf: Vec<u8>,
+    length: u64
+}
+
+impl Default for Sha1 {
+
+    fn default() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0],
+            buf: Vec::with_capacity(BLOCK_LEN),
+            length: 0
+        }
+    }
+}
+
+impl Sha1 {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, input: &[u8]) {
+        self.length = self.length.wrapping_add(input.len() as u64);
+        let mut input = input;
+
+        if !self.buf.is_empty() {
+            let take = (BLOCK_LEN - self.buf.len()).min(input.len());
+            self.buf.extend_from_slice(&input[..take]);
+            input = &input[take..];
+
+            if self.buf.len() < BLOCK_LEN {
+                return;
+            }
+
+            let block = std::mem::take(&mut self.buf);
+            self.compress(&block);
+        }
+
+        let mut chunks = input.chunks_exact(BLOCK_LEN);
+        for block in &mut chunks {
+            self.compress(block);
+        }
+
+        self.buf.extend_from_slice(chunks.remainder());
+    }
+
+    pub fn finalize(mut self) -> [u8; DIGEST_LEN] {
+        let bit_length = self.length.wrapping_mul(8);
+
+        let mut padding = vec![0x80u8];
+        padding.resize(1 + (BLOCK_LEN + 55 - self.buf.len()) % BLOCK_LEN, 0);
+        padding.extend_from_slice(&bit_length.to_be_bytes());
+
+        let length = self.length;
+        self.update(&padding);
+        self.length = length;
+
+        let mut output = [0u8; DIGEST_LEN];
+        for (i, word) in self.state.iter().enumerate() {
+            output[i*4..i*4+4].copy_from_slice(&word.to_be_bytes());
+        }
+
+        output
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 80];
+
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([block[i*4], block[i*4+1], block[i*4+2], block[i*4+3]]);
+        }
+
+        for i in 16..80 {
+            w[i] = (w[i-3] ^ w[i-8] ^ w[i-14] ^ w[i-16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6)
+            };
+
+            let temp = a.rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+        self.state[4] = self.state[4].wrapping_add(e);
+    }
+}
+
+pub fn hash(input: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut hasher = Sha1::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+#[test]
+fn test() {
+    use crate::utils::hex;
+    assert_eq!(hex::encode(&hash(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(hex::encode(&hash(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(hex::encode(&hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+    assert_eq!(hex::encode(&hash(&vec![b'a'; 1_000_000])), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+}
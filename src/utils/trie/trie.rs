@@ -204,6 +204,29 @@ impl<V> Trie<V> {
         self.get(key).is_some()
     }
 
+    pub fn contains_prefix(&self, prefix: &[u8]) -> bool {
+        let Some(mut node) = self.root.as_ref() else { return false };
+
+        loop {
+            match node {
+                Node::Branch(br) => {
+                    //EVERY KEY BELOW THIS BRANCH SHARES THE NIBBLES BEFORE ITS OFFSET
+                    if br.offset >= 2 * prefix.len() {
+                        node = &br.twigs[0];
+                        continue;
+                    }
+
+                    let n = Self::nibble(prefix, br.offset);
+                    match br.get_child(n) {
+                        Some(child) => node = child,
+                        None => return false
+                    }
+                }
+                Node::Leaf(leaf) => return is_prefix(prefix, leaf.key.as_slice())
+            }
+        }
+    }
+
     pub fn iter(&self) -> Entries<'_, V> {
         let mut stack = Vec::new();
         if let Some(root) = self.root.as_ref() {
@@ -1 +1,2 @@
 pub mod zone_types;
+pub mod refresh_actions;
@@ -0,0 +1,20 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RefreshActions {
+    QuerySoa,
+    Ixfr(u32),
+    Axfr
+}
+
+impl fmt::Display for RefreshActions {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QuerySoa => write!(f, "QUERY SOA"),
+            Self::Ixfr(serial) => write!(f, "IXFR {}", serial),
+            Self::Axfr => write!(f, "AXFR")
+        }
+    }
+}
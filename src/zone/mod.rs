@@ -2,4 +2,7 @@ pub mod inter;
 pub mod zone_store;
 pub mod zone;
 pub mod zone_reader;
+pub mod zone_transfer;
+pub mod zone_refresh;
+pub mod zone_notify;
 pub mod rr_set;
@@ -43,10 +43,23 @@ impl RRSet {
         self.records.push(record);
     }
 
+    pub fn remove_record(&mut self, record: &dyn RecordBase) -> Option<Box<dyn RecordBase>> {
+        let index = self.records.iter().position(|r| r.rdata_eq(record))?;
+        Some(self.records.remove(index))
+    }
+
+    pub fn contains_record(&self, record: &dyn RecordBase) -> bool {
+        self.records.iter().any(|r| r.rdata_eq(record))
+    }
+
     pub fn get_records(&self) -> &Vec<Box<dyn RecordBase>> {
         &self.records
     }
 
+    pub fn into_records(self) -> Vec<Box<dyn RecordBase>> {
+        self.records
+    }
+
     pub fn total_records(&self) -> usize {
         self.records.len()
     }
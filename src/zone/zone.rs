@@ -1,21 +1,63 @@
-use std::io;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
 use std::path::PathBuf;
-use crate::journal::journal_reader::JournalReader;
+use crate::journal::inter::txn_op_codes::TxnOpCodes;
+use crate::journal::journal::Journal;
+use crate::journal::journal_reader::{JournalError, JournalReader};
+use crate::journal::journal_writer::JournalWriter;
+use crate::journal::txn::Txn;
+use crate::messages::inter::op_codes::OpCodes;
+use crate::messages::inter::response_codes::ResponseCodes;
 use crate::messages::inter::rr_classes::RRClasses;
 use crate::messages::inter::rr_types::RRTypes;
+use crate::messages::message::{Message, MessageRecord, DNS_HEADER_LEN};
 use crate::messages::rr_query::RRQuery;
 use crate::zone::rr_set::RRSet;
 use crate::records::inter::record_base::RecordBase;
-use crate::utils::fqdn_utils::{decode_fqdn, encode_fqdn};
+use crate::records::soa_record::SoaRecord;
+use crate::tsig::tsig_stream::TsigStream;
+use crate::utils::fqdn_utils::{decode_fqdn, encode_fqdn, fqdn_to_relative, to_fqdn};
 use crate::utils::trie::trie::Trie;
 use crate::zone::inter::zone_types::ZoneTypes;
 
+pub const MAX_TCP_MESSAGE_LEN: usize = 65535;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ZoneError {
+    MissingSoa,
+    MalformedTxn(String),
+    SerialMismatch { expected: u32, found: u32 },
+    NotInZone(String),
+    Journal(String),
+    Rejected(ResponseCodes),
+    MalformedTransfer(String),
+    IncompleteTransfer
+}
+
+impl fmt::Display for ZoneError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSoa => write!(f, "zone has no SOA record"),
+            Self::MalformedTxn(reason) => write!(f, "malformed transaction: {}", reason),
+            Self::SerialMismatch { expected, found } => write!(f, "transaction starts at serial {} but the zone is at serial {}", found, expected),
+            Self::NotInZone(name) => write!(f, "{} is not in the zone", name),
+            Self::Journal(reason) => write!(f, "journal error: {}", reason),
+            Self::Rejected(code) => write!(f, "transfer rejected with {}", code),
+            Self::MalformedTransfer(reason) => write!(f, "malformed transfer: {}", reason),
+            Self::IncompleteTransfer => write!(f, "transfer ended before the closing SOA")
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Zone {
     _type: ZoneTypes,
     class: RRClasses,
     rrmap: Trie<Vec<RRSet>>,
-    journal_path: Option<PathBuf>
+    journal_path: Option<PathBuf>,
+    expired: bool
 }
 
 impl Default for Zone {
@@ -25,7 +67,8 @@ impl Default for Zone {
             _type: Default::default(),
             class: Default::default(),
             rrmap: Trie::new(),
-            journal_path: None
+            journal_path: None,
+            expired: false
         }
     }
 }
@@ -65,6 +108,15 @@ impl Zone {
         self._type.eq(&ZoneTypes::Master) || self._type.eq(&ZoneTypes::Slave)
     }
 
+    //RFC 1034 S4.3.5 - A SECONDARY PAST ITS EXPIRE TIMER MUST NOT BE SERVED
+    pub fn set_expired(&mut self, expired: bool) {
+        self.expired = expired;
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expired
+    }
+
     pub fn add_record(&mut self, query: &str, ttl: u32, record: Box<dyn RecordBase>) {
         let key = encode_fqdn(query);
         let _type = record.get_type();
@@ -92,17 +144,50 @@ impl Zone {
         }
     }
 
-    pub fn remove_set(&mut self) {
-        println!("REMOVE SET");
+    pub fn remove_set(&mut self, query: &str, _type: &RRTypes) -> Vec<MessageRecord> {
+        let key = encode_fqdn(query);
+        let Some(sets) = self.rrmap.get_mut(&key) else { return Vec::new() };
+        let Some(index) = sets.iter().position(|s| s.get_type().eq(_type)) else { return Vec::new() };
+
+        let set = sets.remove(index);
+        if sets.is_empty() {
+            self.rrmap.remove(&key);
+        }
+
+        self.to_message_records(query, set)
     }
 
-    pub fn remove_record(&mut self) {
-        println!("REMOVE RECORD");
+    pub fn remove_record(&mut self, query: &str, record: &dyn RecordBase) -> Option<MessageRecord> {
+        let key = encode_fqdn(query);
+        let sets = self.rrmap.get_mut(&key)?;
+        let index = sets.iter().position(|s| s.get_type().eq(&record.get_type()))?;
+
+        let ttl = sets[index].get_ttl();
+        let removed = sets[index].remove_record(record)?;
+
+        if sets[index].total_records() == 0 {
+            sets.remove(index);
+
+            if sets.is_empty() {
+                self.rrmap.remove(&key);
+            }
+        }
+
+        Some((query.to_string(), self.class, ttl, removed))
     }
 
-    pub fn remove_all_records(&mut self, query: &str) {
-        println!("REMOVE ALL RECORD");
+    pub fn remove_all_records(&mut self, query: &str) -> Vec<MessageRecord> {
+        match self.rrmap.remove(&encode_fqdn(query)) {
+            Some(sets) => sets.into_iter().flat_map(|set| self.to_message_records(query, set)).collect(),
+            None => Vec::new()
+        }
     }
+
+    fn to_message_records(&self, query: &str, set: RRSet) -> Vec<MessageRecord> {
+        let ttl = set.get_ttl();
+        set.into_records().into_iter().map(|record| (query.to_string(), self.class, ttl, record)).collect()
+    }
+
     /*
     pub fn add_record(&mut self, name: &str, record: Box<dyn RecordBase>) {
         let key = encode_fqdn(name);
@@ -134,6 +219,27 @@ impl Zone {
         self.rrmap.get(&encode_fqdn(query))
     }
 
+    //RFC 6672 S2.2 - THE DNAME AT THE CLOSEST ENCLOSER OCCLUDES EVERYTHING BELOW IT, SO THE SHALLOWEST ONE WINS
+    pub fn get_dname(&self, query: &str) -> Option<(String, &RRSet)> {
+        if query.is_empty() {
+            return None;
+        }
+
+        if let Some(set) = self.get_sets("", &RRTypes::DName) {
+            return Some((String::new(), set));
+        }
+
+        let labels = query.split('.').collect::<Vec<_>>();
+        for i in (1..labels.len()).rev() {
+            let owner = labels[i..].join(".");
+            if let Some(set) = self.get_sets(&owner, &RRTypes::DName) {
+                return Some((owner, set));
+            }
+        }
+
+        None
+    }
+
     pub fn get_all_sets_recursive(&self) -> impl Iterator<Item = (String, &Vec<RRSet>)> {
         self.rrmap.iter().map(|(key, records)| (decode_fqdn(key), records))
     }
@@ -157,19 +263,533 @@ impl Zone {
     */
 
     pub fn get_delegation_point(&self, query: &str) -> Option<(String, &RRSet)> {
-        match self.rrmap.get_shallowest(&encode_fqdn(query)) {
-            Some((name, sets)) => {
-                sets
-                    .iter()
-                    .find(|s| s.get_type().eq(&RRTypes::Ns))
-                    .map(|set| (decode_fqdn(name), set))
+        if query.is_empty() {
+            return None;
+        }
+
+        let labels = query.split('.').collect::<Vec<_>>();
+        for i in (0..labels.len()).rev() {
+            let cut = labels[i..].join(".");
+            if let Some(set) = self.get_sets(&cut, &RRTypes::Ns) {
+                return Some((cut, set));
             }
-            None => None
         }
+
+        None
+    }
+
+    //TRUE IF THE NAME OWNS RECORDS OR IS AN EMPTY NON-TERMINAL
+    pub fn contains_name(&self, query: &str) -> bool {
+        query.is_empty() || self.rrmap.contains_prefix(&encode_fqdn(query))
+    }
+
+    pub fn get_soa(&self) -> Option<(u32, &SoaRecord)> {
+        let set = self.get_sets("", &RRTypes::Soa)?;
+        Some((set.get_ttl(), set.get_records().first()?.as_any().downcast_ref::<SoaRecord>()?))
+    }
+
+    //RFC 2136 S3 - CHECK THE PREREQUISITES, APPLY THE UPDATES AND RETURN WHAT CHANGED AS A JOURNAL TRANSACTION,
+    //APEX IS THE NAME THIS ZONE IS SERVED UNDER
+    pub fn apply_update(&mut self, apex: &str, message: &Message) -> Result<Txn, ResponseCodes> {
+        if message.get_op_code() != OpCodes::Update {
+            return Err(ResponseCodes::FormErr);
+        }
+
+        let [zone_query] = message.get_queries().as_slice() else {
+            return Err(ResponseCodes::FormErr);
+        };
+
+        if zone_query.get_type() != RRTypes::Soa {
+            return Err(ResponseCodes::FormErr);
+        }
+
+        if zone_query.get_class() != self.class || self._type != ZoneTypes::Master {
+            return Err(ResponseCodes::NotAuth);
+        }
+
+        //RFC 2136 S3.1.2 - THE ZONE SECTION HAS TO NAME THIS ZONE
+        let apex = apex.trim_end_matches('.').to_lowercase();
+        if zone_query.get_fqdn().trim_end_matches('.').to_lowercase() != apex {
+            return Err(ResponseCodes::NotAuth);
+        }
+
+        let (soa_ttl, soa) = self.get_soa().map(|(ttl, soa)| (ttl, soa.clone())).ok_or(ResponseCodes::NotAuth)?;
+
+        self.check_prerequisites(&apex, message.get_section(0))?;
+        let updates = self.prescan_updates(&apex, message.get_section(1))?;
+
+        let mut deleted = Vec::new();
+        let mut added = Vec::new();
+        let mut serial = None;
+
+        for (query, class, ttl, record) in updates {
+            let _type = record.get_type();
+
+            if class == self.class {
+                if _type == RRTypes::Soa {
+                    //RFC 2136 S3.4.2.2 - ONLY A NEWER SERIAL REPLACES THE SOA
+                    if let Some(update) = record.as_any().downcast_ref::<SoaRecord>() {
+                        if query.is_empty() && serial_gt(update.get_serial(), soa.get_serial()) {
+                            serial = Some((ttl, update.clone()));
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(sets) = self.get_all_sets(&query) {
+                    let has_cname = sets.iter().any(|s| s.get_type().eq(&RRTypes::CName));
+                    let has_other = sets.iter().any(|s| !matches!(s.get_type(), RRTypes::CName | RRTypes::RRSig | RRTypes::Nsec));
+
+                    if (_type == RRTypes::CName && has_other) || (has_cname && !matches!(_type, RRTypes::CName | RRTypes::RRSig | RRTypes::Nsec)) {
+                        continue;
+                    }
+
+                    if let Some(set) = sets.iter().find(|s| s.get_type().eq(&_type)) {
+                        if set.get_ttl() == ttl && set.contains_record(record.as_ref()) {
+                            continue;
+                        }
+                    }
+                }
+
+                //A CNAME OR A CHANGED TTL REPLACES THE EXISTING RECORDS
+                if let Some(set) = self.get_sets(&query, &_type) {
+                    if _type == RRTypes::CName || set.get_ttl() != ttl {
+                        let records = set.get_records().iter()
+                            .filter(|r| _type == RRTypes::CName || !r.rdata_eq(record.as_ref()))
+                            .cloned()
+                            .collect::<Vec<_>>();
+
+                        deleted.extend(self.remove_set(&query, &_type));
+
+                        for r in records.into_iter().filter(|_| _type != RRTypes::CName) {
+                            self.add_record(&query, ttl, r.clone());
+                            added.push((query.clone(), self.class, ttl, r));
+                        }
+                    }
+                }
+
+                self.add_record(&query, ttl, record.clone());
+                added.push((query, self.class, ttl, record.clone()));
+                continue;
+            }
+
+            match class {
+                RRClasses::Any => {
+                    match _type {
+                        RRTypes::Any => {
+                            if query.is_empty() {
+                                for _type in self.get_all_sets("").map(|sets| sets.iter().map(|s| s.get_type()).collect::<Vec<_>>()).unwrap_or_default() {
+                                    if _type != RRTypes::Soa && _type != RRTypes::Ns {
+                                        deleted.extend(self.remove_set("", &_type));
+                                    }
+                                }
+                                continue;
+                            }
+
+                            deleted.extend(self.remove_all_records(&query));
+                        }
+                        _ => {
+                            if query.is_empty() && (_type == RRTypes::Soa || _type == RRTypes::Ns) {
+                                continue;
+                            }
+
+                            deleted.extend(self.remove_set(&query, &_type));
+                        }
+                    }
+                }
+                _ => {
+                    if _type == RRTypes::Soa {
+                        continue;
+                    }
+
+                    //RFC 2136 S3.4.2.4 - NEVER REMOVE THE LAST NS AT THE APEX
+                    if query.is_empty() && _type == RRTypes::Ns {
+                        if let Some(set) = self.get_sets("", &RRTypes::Ns) {
+                            if set.total_records() == 1 && set.contains_record(record.as_ref()) {
+                                continue;
+                            }
+                        }
+                    }
+
+                    deleted.extend(self.remove_record(&query, record.as_ref()));
+                }
+            }
+        }
+
+        if deleted.is_empty() && added.is_empty() && serial.is_none() {
+            return Ok(Txn::new(soa.get_serial(), soa.get_serial()));
+        }
+
+        let (ttl, new_soa) = serial.unwrap_or_else(|| {
+            let mut new_soa = soa.clone();
+            new_soa.set_serial(soa.get_serial().wrapping_add(1));
+            (soa_ttl, new_soa)
+        });
+
+        self.remove_set("", &RRTypes::Soa);
+        self.add_record("", ttl, new_soa.clone().upcast());
+
+        let mut txn = Txn::new(soa.get_serial(), new_soa.get_serial());
+        txn.add_record(TxnOpCodes::Delete, &apex, self.class, soa_ttl, soa.upcast());
+        for (query, class, ttl, record) in deleted {
+            txn.add_record(TxnOpCodes::Delete, &to_fqdn(&apex, &query), class, ttl, record);
+        }
+
+        txn.add_record(TxnOpCodes::Add, &apex, self.class, ttl, new_soa.upcast());
+        for (query, class, ttl, record) in added {
+            txn.add_record(TxnOpCodes::Add, &to_fqdn(&apex, &query), class, ttl, record);
+        }
+
+        Ok(txn)
+    }
+
+    //RFC 2136 S3.2
+    fn check_prerequisites(&self, apex: &str, prerequisites: &[MessageRecord]) -> Result<(), ResponseCodes> {
+        let mut required: Vec<(String, RRTypes, Vec<&dyn RecordBase>)> = Vec::new();
+
+        for (name, class, ttl, record) in prerequisites {
+            if *ttl != 0 {
+                return Err(ResponseCodes::FormErr);
+            }
+
+            let query = fqdn_to_relative(apex, &name.to_lowercase()).ok_or(ResponseCodes::NotZone)?;
+            let _type = record.get_type();
+
+            match class {
+                RRClasses::Any => {
+                    if !is_empty_rdata(record.as_ref()) {
+                        return Err(ResponseCodes::FormErr);
+                    }
+
+                    match _type {
+                        RRTypes::Any => {
+                            if self.get_all_sets(&query).is_none() {
+                                return Err(ResponseCodes::NxDomain);
+                            }
+                        }
+                        _ => {
+                            if self.get_sets(&query, &_type).is_none() {
+                                return Err(ResponseCodes::NxRRSet);
+                            }
+                        }
+                    }
+                }
+                RRClasses::None => {
+                    if !is_empty_rdata(record.as_ref()) {
+                        return Err(ResponseCodes::FormErr);
+                    }
+
+                    match _type {
+                        RRTypes::Any => {
+                            if self.get_all_sets(&query).is_some() {
+                                return Err(ResponseCodes::YxDomain);
+                            }
+                        }
+                        _ => {
+                            if self.get_sets(&query, &_type).is_some() {
+                                return Err(ResponseCodes::YxRRSet);
+                            }
+                        }
+                    }
+                }
+                _ if *class == self.class => {
+                    match required.iter_mut().find(|(n, t, _)| n.eq(&query) && t.eq(&_type)) {
+                        Some((_, _, records)) => records.push(record.as_ref()),
+                        None => required.push((query, _type, vec![record.as_ref()]))
+                    }
+                }
+                _ => return Err(ResponseCodes::FormErr)
+            }
+        }
+
+        //VALUE DEPENDENT - THE RRSET MUST MATCH EXACTLY
+        for (query, _type, records) in required {
+            let set = self.get_sets(&query, &_type).ok_or(ResponseCodes::NxRRSet)?;
+
+            if !records.iter().all(|r| set.contains_record(*r)) ||
+                    !set.get_records().iter().all(|r| records.iter().any(|o| o.rdata_eq(r.as_ref()))) {
+                return Err(ResponseCodes::NxRRSet);
+            }
+        }
+
+        Ok(())
+    }
+
+    //RFC 2136 S3.4.1
+    fn prescan_updates(&self, apex: &str, updates: &[MessageRecord]) -> Result<Vec<MessageRecord>, ResponseCodes> {
+        let mut prescanned = Vec::new();
+
+        for (name, class, ttl, record) in updates {
+            let query = fqdn_to_relative(apex, &name.to_lowercase()).ok_or(ResponseCodes::NotZone)?;
+            let _type = record.get_type();
+
+            if matches!(_type, RRTypes::Axfr | RRTypes::Ixfr | RRTypes::Opt | RRTypes::TSig | RRTypes::TKey) {
+                return Err(ResponseCodes::FormErr);
+            }
+
+            match class {
+                _ if *class == self.class => {
+                    if _type == RRTypes::Any {
+                        return Err(ResponseCodes::FormErr);
+                    }
+                }
+                RRClasses::Any => {
+                    if *ttl != 0 || !is_empty_rdata(record.as_ref()) {
+                        return Err(ResponseCodes::FormErr);
+                    }
+                }
+                RRClasses::None => {
+                    if *ttl != 0 || _type == RRTypes::Any {
+                        return Err(ResponseCodes::FormErr);
+                    }
+                }
+                _ => return Err(ResponseCodes::FormErr)
+            }
+
+            prescanned.push((query, *class, *ttl, record.clone()));
+        }
+
+        Ok(prescanned)
+    }
+
+    //REPLAYS EVERY TRANSACTION NEWER THAN THE CURRENT SOA SERIAL, RETURNING HOW MANY WERE APPLIED
+    pub fn apply_journal(&mut self) -> Result<usize, ZoneError> {
+        match self.journal_path.as_ref() {
+            Some(journal_path) if journal_path.exists() => {}
+            _ => return Ok(0)
+        }
+
+        let mut reader = self.get_journal_reader().map_err(|e| ZoneError::Journal(e.to_string()))?;
+        let mut applied = 0;
+
+        //USE THE INDEX TO SKIP WHAT IS ALREADY APPLIED, OTHERWISE SCAN FROM THE START
+        let (_, soa) = self.get_soa().ok_or(ZoneError::MissingSoa)?;
+        match reader.seek_serial(soa.get_serial()) {
+            Ok(_) | Err(JournalError::SerialNotFound(_)) => {}
+            Err(e) => return Err(ZoneError::Journal(e.to_string()))
+        }
+
+        for txn in reader.iter() {
+            let txn = txn.map_err(|e| ZoneError::Journal(e.to_string()))?;
+            let (_, soa) = self.get_soa().ok_or(ZoneError::MissingSoa)?;
+            let serial = soa.get_serial();
+
+            //ALREADY IN THE MASTER FILE
+            if txn.get_serial_0() != serial && serial_gt(serial, txn.get_serial_0()) && !serial_gt(txn.get_serial_1(), serial) {
+                continue;
+            }
+
+            self.apply_txn(&txn)?;
+            applied += 1;
+        }
+
+        Ok(applied)
+    }
+
+    pub fn apply_txn(&mut self, txn: &Txn) -> Result<(), ZoneError> {
+        let (_, soa) = self.get_soa().ok_or(ZoneError::MissingSoa)?;
+
+        if txn.get_serial_0() != soa.get_serial() {
+            return Err(ZoneError::SerialMismatch {
+                expected: soa.get_serial(),
+                found: txn.get_serial_0()
+            });
+        }
+
+        let deletes = txn.get_records(TxnOpCodes::Delete);
+        let adds = txn.get_records(TxnOpCodes::Add);
+
+        let apex = match (deletes.first(), adds.first()) {
+            (Some((apex, _, _, deleted)), Some((_, _, _, added))) if deleted.get_type() == RRTypes::Soa && added.get_type() == RRTypes::Soa => {
+                apex.trim_end_matches('.').to_lowercase()
+            }
+            _ => return Err(ZoneError::MalformedTxn("transaction does not start with the old and new SOA".to_string()))
+        };
+
+        //RESOLVE EVERY NAME BEFORE TOUCHING THE ZONE SO A BAD TRANSACTION CHANGES NOTHING
+        let resolve = |records: &Vec<MessageRecord>| {
+            records.iter().map(|(name, class, ttl, record)| {
+                Ok((fqdn_to_relative(&apex, &name.trim_end_matches('.').to_lowercase()).ok_or_else(|| ZoneError::NotInZone(name.clone()))?, *class, *ttl, record.clone()))
+            }).collect::<Result<Vec<_>, ZoneError>>()
+        };
+
+        let deletes = resolve(deletes)?;
+        let adds = resolve(adds)?;
+
+        //THE RESULTING SERIAL IS ONLY KNOWN ONCE EVERYTHING IS APPLIED, SO WORK ON A COPY AND SWAP IT IN
+        let mut zone = self.clone();
+
+        for (query, _, _, record) in deletes {
+            zone.remove_record(&query, record.as_ref());
+        }
+
+        for (query, _, ttl, record) in adds {
+            if zone.get_sets(&query, &record.get_type()).is_some_and(|set| set.contains_record(record.as_ref())) {
+                continue;
+            }
+
+            zone.add_record(&query, ttl, record);
+        }
+
+        match zone.get_soa() {
+            Some((_, soa)) if soa.get_serial() == txn.get_serial_1() => {}
+            Some((_, soa)) => return Err(ZoneError::SerialMismatch {
+                expected: txn.get_serial_1(),
+                found: soa.get_serial()
+            }),
+            None => return Err(ZoneError::MissingSoa)
+        }
+
+        *self = zone;
+        Ok(())
+    }
+
+    //RFC 1995 S4 - ANSWER AN IXFR FROM THE JOURNAL, FALLING BACK TO THE WHOLE ZONE WHEN THE JOURNAL CAN'T BRIDGE THE GAP
+    pub fn ixfr(&self, query: &Message, journal: &Journal) -> Vec<Message> {
+        let mut response = Message::new(query.get_id());
+        response.set_qr(true);
+        response.set_op_code(query.get_op_code());
+
+        for q in query.get_queries() {
+            response.add_query(q.clone());
+        }
+
+        let [q] = query.get_queries().as_slice() else {
+            response.set_response_code(ResponseCodes::FormErr);
+            return vec![response];
+        };
+
+        if query.get_op_code() != OpCodes::Query || q.get_type() != RRTypes::Ixfr {
+            response.set_response_code(ResponseCodes::FormErr);
+            return vec![response];
+        }
+
+        if self.expired {
+            response.set_response_code(ResponseCodes::ServFail);
+            return vec![response];
+        }
+
+        let Some((soa_ttl, soa)) = self.get_soa().filter(|_| q.get_class() == self.class) else {
+            response.set_response_code(ResponseCodes::NotAuth);
+            return vec![response];
+        };
+
+        let Some(client_serial) = query.get_section(1).iter()
+                .find_map(|(_, _, _, record)| record.as_any().downcast_ref::<SoaRecord>())
+                .map(|soa| soa.get_serial()) else {
+            response.set_response_code(ResponseCodes::FormErr);
+            return vec![response];
+        };
+
+        response.set_authoritative(true);
+
+        let apex = q.get_fqdn().trim_end_matches('.').to_lowercase();
+        let current: MessageRecord = (apex.clone(), self.class, soa_ttl, soa.clone().upcast());
+
+        //RFC 1995 S2 - CLIENT IS ALREADY UP TO DATE, ONLY THE CURRENT SOA IS SENT
+        if !serial_gt(soa.get_serial(), client_serial) {
+            response.add_section(0, &current.0, current.1, current.2, current.3);
+            return vec![response];
+        }
+
+        let records = match journal_diffs(journal, client_serial, soa.get_serial()) {
+            Some(diffs) => {
+                let mut records = Vec::with_capacity(diffs.len()+2);
+                records.push(current.clone());
+                records.extend(diffs);
+                records.push(current);
+                records
+            }
+            None => self.transfer_records(&apex)
+        };
+
+        split_transfer(response, records, MAX_TCP_MESSAGE_LEN)
+    }
+
+    //RFC 5936 S2.2 - STREAM THE WHOLE ZONE, THE SIGNER CHAINS A TSIG ONTO EVERY MESSAGE AT TIME_SIGNED,
+    //ROOM FOR IT IS LEFT IN EACH MESSAGE SO NOTHING IS TRUNCATED WHEN SENT WITH MAX_PAYLOAD_LEN
+    pub fn axfr(&self, query: &Message, max_payload_len: usize, signer: Option<(&mut TsigStream, u64)>) -> Vec<Message> {
+        let reserve = signer.as_ref().map(|(signer, _)| signer.get_key().get_tsig_len()).unwrap_or(0);
+
+        let mut response = Message::new(query.get_id());
+        response.set_qr(true);
+        response.set_op_code(query.get_op_code());
+
+        for q in query.get_queries() {
+            response.add_query(q.clone());
+        }
+
+        let mut messages = match query.get_queries().as_slice() {
+            [q] if query.get_op_code() == OpCodes::Query && q.get_type() == RRTypes::Axfr => {
+                match self.get_soa() {
+                    _ if self.expired => {
+                        response.set_response_code(ResponseCodes::ServFail);
+                        vec![response]
+                    }
+                    Some(_) if q.get_class() == self.class => {
+                        response.set_authoritative(true);
+                        let apex = q.get_fqdn().trim_end_matches('.').to_lowercase();
+                        split_transfer(response, self.transfer_records(&apex), max_payload_len.saturating_sub(reserve))
+                    }
+                    _ => {
+                        response.set_response_code(ResponseCodes::NotAuth);
+                        vec![response]
+                    }
+                }
+            }
+            _ => {
+                response.set_response_code(ResponseCodes::FormErr);
+                vec![response]
+            }
+        };
+
+        if let Some((signer, time_signed)) = signer {
+            for message in messages.iter_mut() {
+                signer.sign(message, time_signed, max_payload_len);
+            }
+        }
+
+        messages
     }
 
-    pub fn get_journal_reader(&self) -> io::Result<JournalReader> {
-        JournalReader::open(self.journal_path.as_ref().unwrap())
+    //RFC 5936 S2.2 - SOA, EVERY OTHER RRSET, SOA
+    fn transfer_records(&self, apex: &str) -> Vec<MessageRecord> {
+        let mut records = Vec::new();
+
+        let Some((soa_ttl, soa)) = self.get_soa() else {
+            return records;
+        };
+
+        records.push((apex.to_string(), self.class, soa_ttl, soa.clone().upcast()));
+
+        for (name, sets) in self.get_all_sets_recursive() {
+            let fqdn = to_fqdn(apex, &name);
+
+            for set in sets.iter().filter(|set| set.get_type() != RRTypes::Soa) {
+                for record in set.get_records() {
+                    records.push((fqdn.clone(), self.class, set.get_ttl(), record.clone()));
+                }
+            }
+        }
+
+        records.push((apex.to_string(), self.class, soa_ttl, soa.clone().upcast()));
+        records
+    }
+
+    pub fn get_journal_reader(&self) -> Result<JournalReader, JournalError> {
+        let journal_path = self.journal_path.as_ref()
+            .ok_or_else(|| JournalError::HeaderError("zone has no journal path".to_string()))?;
+        JournalReader::open(journal_path)
+    }
+
+    pub fn get_journal_writer(&self) -> Result<JournalWriter, JournalError> {
+        let journal_path = self.journal_path.as_ref()
+            .ok_or_else(|| JournalError::HeaderError("zone has no journal path".to_string()))?;
+
+        match journal_path.exists() {
+            true => JournalWriter::open(journal_path),
+            false => JournalWriter::create(journal_path).map_err(|e| JournalError::Io { offset: 0, reason: e.to_string() })
+        }
     }
 
     pub fn set_journal_path<P: Into<PathBuf>>(&mut self, journal_path: P) {
@@ -188,3 +808,329 @@ impl Zone {
         self
     }
 }
+
+//RFC 1982 - SERIAL NUMBER ARITHMETIC
+pub(crate) fn serial_gt(a: u32, b: u32) -> bool {
+    a != b && (a.wrapping_sub(b) as i32) > 0
+}
+
+//RFC 1995 S4 - EVERY TRANSACTION FROM SERIAL FROM TO SERIAL TO, OLD SOA AND DELETES THEN NEW SOA AND ADDS
+fn journal_diffs(journal: &Journal, from: u32, to: u32) -> Option<Vec<MessageRecord>> {
+    let mut records = Vec::new();
+    let mut serial = from;
+
+    for (_, txn) in journal.get_txns_from(from) {
+        if serial == to {
+            break;
+        }
+
+        if txn.get_serial_0() != serial {
+            return None;
+        }
+
+        records.extend(txn.get_records(TxnOpCodes::Delete).iter().cloned());
+        records.extend(txn.get_records(TxnOpCodes::Add).iter().cloned());
+        serial = txn.get_serial_1();
+    }
+
+    (serial == to).then_some(records)
+}
+
+//SIZES ARE MEASURED UNCOMPRESSED SO EVERY MESSAGE FITS NO MATTER HOW WELL IT COMPRESSES,
+//THE QUESTION IS ONLY CARRIED IN THE FIRST MESSAGE
+fn split_transfer(response: Message, records: Vec<MessageRecord>, max_payload_len: usize) -> Vec<Message> {
+    let mut template = response.clone();
+    template.get_queries_mut().clear();
+
+    let mut size = response.to_bytes(max_payload_len).len();
+    let mut messages = vec![response];
+
+    for (name, class, ttl, record) in records {
+        let length = name.len()+2+8+record.to_bytes(&mut HashMap::new(), 0).map(|buf| buf.len()).unwrap_or(0);
+
+        if size+length > max_payload_len && messages.last().is_some_and(|m| m.has_section(0)) {
+            messages.push(template.clone());
+            size = DNS_HEADER_LEN;
+        }
+
+        messages.last_mut().unwrap().add_section(0, &name, class, ttl, record);
+        size += length;
+    }
+
+    messages
+}
+
+fn is_empty_rdata(record: &dyn RecordBase) -> bool {
+    matches!(record.to_bytes(&mut HashMap::new(), 0), Ok(buf) if buf.len() <= 2)
+}
+
+#[test]
+fn test() {
+    use crate::records::in_a_record::InARecord;
+    use crate::records::mx_record::MxRecord;
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("www", 300, InARecord::new("10.0.0.1".parse().unwrap()).upcast());
+    zone.add_record("www", 300, InARecord::new("10.0.0.2".parse().unwrap()).upcast());
+    zone.add_record("www", 600, MxRecord::new(10, "mail.find9.net").upcast());
+    zone.add_record("a.b.c", 300, InARecord::new("10.0.0.3".parse().unwrap()).upcast());
+
+    assert!(zone.remove_record("www", &MxRecord::new(20, "mail.find9.net")).is_none());
+    let (name, class, ttl, record) = zone.remove_record("www", &MxRecord::new(10, "MAIL.find9.net")).unwrap();
+    assert_eq!((name.as_str(), class, ttl), ("www", RRClasses::In, 600));
+    assert_eq!(record.get_type(), RRTypes::Mx);
+    assert!(zone.get_sets("www", &RRTypes::Mx).is_none());
+
+    assert_eq!(zone.remove_set("www", &RRTypes::A).len(), 2);
+    assert!(zone.get_all_sets("www").is_none());
+    assert!(zone.remove_set("www", &RRTypes::A).is_empty());
+
+    assert!(zone.contains_name("b.c"));
+    assert_eq!(zone.remove_all_records("a.b.c").len(), 1);
+    assert!(!zone.contains_name("b.c"));
+    assert!(zone.remove_all_records("a.b.c").is_empty());
+}
+
+#[test]
+fn update() {
+    use crate::records::in_a_record::InARecord;
+    use crate::records::ns_record::NsRecord;
+    use crate::records::unknown_record::UnknownRecord;
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, SoaRecord::new("ns1.find9.net", "admin.find9.net", 10, 7200, 3600, 1209600, 60).upcast());
+    zone.add_record("", 300, NsRecord::new("ns1.find9.net").upcast());
+    zone.add_record("www", 300, InARecord::new("10.0.0.1".parse().unwrap()).upcast());
+
+    let update = |prerequisites: Vec<MessageRecord>, updates: Vec<MessageRecord>| {
+        let mut message = Message::new(1);
+        message.set_op_code(OpCodes::Update);
+        message.add_query(RRQuery::new("find9.net", RRTypes::Soa, RRClasses::In));
+        message.set_section(0, prerequisites);
+        message.set_section(1, updates);
+        Message::from_bytes(&message.to_bytes(512)).unwrap()
+    };
+
+    let empty = |_type: RRTypes| UnknownRecord::new(_type, Vec::new()).upcast();
+
+    let message = update(vec![("nope.find9.net".to_string(), RRClasses::Any, 0, empty(RRTypes::Any))], Vec::new());
+    assert_eq!(zone.apply_update("find9.net", &message).unwrap_err(), ResponseCodes::NxDomain);
+
+    let message = update(vec![("www.find9.net".to_string(), RRClasses::None, 0, empty(RRTypes::A))], Vec::new());
+    assert_eq!(zone.apply_update("find9.net", &message).unwrap_err(), ResponseCodes::YxRRSet);
+
+    let message = update(vec![("www.find9.net".to_string(), RRClasses::In, 0, InARecord::new("10.0.0.9".parse().unwrap()).upcast())], Vec::new());
+    assert_eq!(zone.apply_update("find9.net", &message).unwrap_err(), ResponseCodes::NxRRSet);
+
+    let message = update(Vec::new(), vec![("www.find9.org".to_string(), RRClasses::In, 300, InARecord::new("10.0.0.9".parse().unwrap()).upcast())]);
+    assert_eq!(zone.apply_update("find9.net", &message).unwrap_err(), ResponseCodes::NotZone);
+
+    let message = update(vec![
+        ("www.find9.net".to_string(), RRClasses::In, 0, InARecord::new("10.0.0.1".parse().unwrap()).upcast()),
+        ("host.find9.net".to_string(), RRClasses::None, 0, empty(RRTypes::Any))
+    ], vec![
+        ("host.find9.net".to_string(), RRClasses::In, 600, InARecord::new("10.0.0.2".parse().unwrap()).upcast()),
+        ("www.find9.net".to_string(), RRClasses::Any, 0, empty(RRTypes::A)),
+        ("find9.net".to_string(), RRClasses::None, 0, NsRecord::new("ns1.find9.net").upcast())
+    ]);
+    let txn = zone.apply_update("find9.net", &message).unwrap();
+    assert_eq!((txn.get_serial_0(), txn.get_serial_1()), (10, 11));
+    assert_eq!(txn.get_records(TxnOpCodes::Delete).iter().map(|r| r.0.as_str()).collect::<Vec<_>>(), vec!["find9.net", "www.find9.net"]);
+    assert_eq!(txn.get_records(TxnOpCodes::Add).iter().map(|r| r.0.as_str()).collect::<Vec<_>>(), vec!["find9.net", "host.find9.net"]);
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 11);
+    assert!(zone.get_sets("www", &RRTypes::A).is_none());
+    assert_eq!(zone.get_sets("", &RRTypes::Ns).unwrap().total_records(), 1);
+
+    let message = update(Vec::new(), vec![("host.find9.net".to_string(), RRClasses::In, 600, InARecord::new("10.0.0.2".parse().unwrap()).upcast())]);
+    assert_eq!(zone.apply_update("find9.net", &message).unwrap().get_serial_1(), 11);
+
+    //A ZONE SECTION THAT DOESN'T NAME THE ZONE BEING UPDATED IS REFUSED
+    assert_eq!(zone.apply_update("find9.org.", &message).unwrap_err(), ResponseCodes::NotAuth);
+    assert_eq!(zone.apply_update("Find9.NET.", &message).unwrap().get_serial_1(), 11);
+}
+
+#[test]
+fn journal() {
+    use crate::records::in_a_record::InARecord;
+
+    let path = std::env::temp_dir().join("rlibdns.zone.journal.test.jnl");
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+    let a = |last: u8| InARecord::new([10, 0, 0, last].into()).upcast();
+
+    let mut writer = JournalWriter::create(&path).unwrap();
+    for serial in 1..3 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, a(serial as u8));
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, a(serial as u8+1));
+        writer.write_txn(&txn).unwrap();
+    }
+
+    let mut zone = Zone::new_with_jnl(ZoneTypes::Master, RRClasses::In, &path);
+    zone.add_record("", 300, soa(1));
+    zone.add_record("www", 300, a(1));
+    assert_eq!(zone.apply_journal().unwrap(), 2);
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 3);
+    assert!(zone.get_sets("www", &RRTypes::A).unwrap().contains_record(a(3).as_ref()));
+    assert_eq!(zone.get_sets("www", &RRTypes::A).unwrap().total_records(), 1);
+
+    let mut zone = Zone::new_with_jnl(ZoneTypes::Master, RRClasses::In, &path);
+    zone.add_record("", 300, soa(2));
+    zone.add_record("www", 300, a(2));
+    assert_eq!(zone.apply_journal().unwrap(), 1);
+    assert_eq!(zone.apply_journal().unwrap(), 0);
+
+    let mut zone = Zone::new_with_jnl(ZoneTypes::Master, RRClasses::In, &path);
+    zone.add_record("", 300, soa(0));
+    assert_eq!(zone.apply_journal().unwrap_err(), ZoneError::SerialMismatch { expected: 0, found: 1 });
+
+    //A TRANSACTION THAT LANDS ON THE WRONG SERIAL LEAVES THE ZONE BYTE FOR BYTE AS IT WAS
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, soa(3));
+    zone.add_record("www", 300, a(3));
+    let mut query = Message::new(1);
+    query.add_query(RRQuery::new("find9.net", RRTypes::Axfr, RRClasses::In));
+    let before: Vec<Vec<u8>> = zone.axfr(&query, MAX_TCP_MESSAGE_LEN, None).iter().map(|m| m.to_bytes(MAX_TCP_MESSAGE_LEN)).collect();
+
+    let mut txn = Txn::new(3, 4);
+    txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(3));
+    txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, a(3));
+    txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(5));
+    txn.add_record(TxnOpCodes::Add, "mail.find9.net", RRClasses::In, 300, a(5));
+    assert_eq!(zone.apply_txn(&txn).unwrap_err(), ZoneError::SerialMismatch { expected: 4, found: 5 });
+    assert_eq!(zone.axfr(&query, MAX_TCP_MESSAGE_LEN, None).iter().map(|m| m.to_bytes(MAX_TCP_MESSAGE_LEN)).collect::<Vec<_>>(), before);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn ixfr() {
+    use crate::records::in_a_record::InARecord;
+    use crate::records::txt_record::TxtRecord;
+
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+    let a = |last: u8| InARecord::new([10, 0, 0, last].into()).upcast();
+    let serial_of = |record: &MessageRecord| record.3.as_any().downcast_ref::<SoaRecord>().map(|soa| soa.get_serial());
+
+    let mut journal = Journal::new();
+    for serial in 1..3 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, a(serial as u8));
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, a(serial as u8+1));
+        journal.add_txn(txn);
+    }
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, soa(3));
+    zone.add_record("www", 300, a(3));
+
+    let query = |serial| {
+        let mut query = Message::new(7);
+        query.add_query(RRQuery::new("find9.net", RRTypes::Ixfr, RRClasses::In));
+        query.add_section(1, "find9.net", RRClasses::In, 300, soa(serial));
+        query
+    };
+
+    let responses = zone.ixfr(&query(1), &journal);
+    assert_eq!(responses.len(), 1);
+    assert!(responses[0].is_authoritative());
+    let answers = responses[0].get_section(0);
+    assert_eq!(answers.iter().map(serial_of).collect::<Vec<_>>(),
+        vec![Some(3), Some(1), None, Some(2), None, Some(2), None, Some(3), None, Some(3)]);
+    assert_eq!(answers[2].0, "www.find9.net");
+
+    let responses = zone.ixfr(&query(3), &journal);
+    assert_eq!(responses[0].get_section(0).iter().map(serial_of).collect::<Vec<_>>(), vec![Some(3)]);
+
+    //JOURNAL DOESN'T REACH BACK TO SERIAL 0, WHOLE ZONE IS SENT
+    let responses = zone.ixfr(&query(0), &journal);
+    assert_eq!(responses[0].get_section(0).iter().map(serial_of).collect::<Vec<_>>(), vec![Some(3), None, Some(3)]);
+
+    let mut missing = query(1);
+    missing.get_section_mut(1).clear();
+    assert_eq!(zone.ixfr(&missing, &journal)[0].get_response_code(), ResponseCodes::FormErr);
+
+    for i in 0..2000 {
+        zone.add_record(&format!("txt{}", i), 300, TxtRecord::new(vec!["x".repeat(100)]).upcast());
+    }
+
+    let responses = zone.ixfr(&query(0), &journal);
+    assert!(responses.len() > 1);
+    assert_eq!(responses.iter().map(|m| m.total_section(0)).sum::<usize>(), 2003);
+    assert!(responses.iter().skip(1).all(|m| !m.has_queries()));
+
+    for response in &responses {
+        let buf = response.to_bytes(MAX_TCP_MESSAGE_LEN);
+        assert_eq!(Message::from_bytes(&buf).unwrap().total_section(0), response.total_section(0));
+    }
+}
+
+#[test]
+fn axfr() {
+    use crate::records::in_a_record::InARecord;
+    use crate::records::ns_record::NsRecord;
+    use crate::records::txt_record::TxtRecord;
+    use crate::tsig::inter::tsig_algorithms::TsigAlgorithms;
+    use crate::tsig::tsig_key::TsigKey;
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, SoaRecord::new("ns1.find9.net", "admin.find9.net", 5, 7200, 3600, 1209600, 60).upcast());
+    zone.add_record("", 300, NsRecord::new("ns1.find9.net").upcast());
+    zone.add_record("ns1", 300, InARecord::new([10, 0, 0, 1].into()).upcast());
+    zone.add_record("sub", 300, NsRecord::new("ns.sub.find9.net").upcast());
+    zone.add_record("ns.sub", 300, InARecord::new([10, 0, 0, 2].into()).upcast());
+    for i in 0..500 {
+        zone.add_record(&format!("txt{}", i), 300, TxtRecord::new(vec!["x".repeat(200)]).upcast());
+    }
+
+    let mut query = Message::new(9);
+    query.add_query(RRQuery::new("find9.net", RRTypes::Axfr, RRClasses::In));
+
+    //THE TSIG FITS IN EVERY MESSAGE AND THE MAC COVERS EXACTLY WHAT IS SENT
+    let key = TsigKey::new("xfr.find9.net", TsigAlgorithms::HmacSha512, b"secret");
+    let mut signer = TsigStream::new(key.clone(), None);
+    let responses = zone.axfr(&query, 16384, Some((&mut signer, 1000)));
+    assert!(responses.len() > 1);
+
+    let mut verifier = TsigStream::new(key.clone(), None);
+    let records = responses.iter().flat_map(|m| {
+        let buf = m.to_bytes(16384);
+        assert!(buf.len() <= 16384);
+        let received = Message::from_bytes(&buf).unwrap();
+        assert!(!received.is_truncated());
+        verifier.verify(&received, 1000).unwrap();
+        received.get_section(0).clone()
+    }).collect::<Vec<_>>();
+    assert!(verifier.is_complete());
+
+    assert_eq!(records.len(), 506);
+    assert_eq!(records.first().unwrap().3.get_type(), RRTypes::Soa);
+    assert_eq!(records.last().unwrap().3.get_type(), RRTypes::Soa);
+    assert!(records.iter().any(|(name, _, _, record)| name == "ns.sub.find9.net" && record.get_type() == RRTypes::A));
+
+    //A ONE LABEL APEX BARELY COMPRESSES, SO MESSAGES FILL RIGHT UP TO THE ROOM LEFT FOR THE TSIG
+    let mut short = Zone::new(ZoneTypes::Master, RRClasses::In);
+    short.add_record("", 300, SoaRecord::new("ns1.a", "admin.a", 1, 7200, 3600, 1209600, 60).upcast());
+    for i in 0..500 {
+        short.add_record(&format!("host{}", i), 300, TxtRecord::new(vec!["x".repeat(i % 200)]).upcast());
+    }
+
+    let mut query = Message::new(10);
+    query.add_query(RRQuery::new("a", RRTypes::Axfr, RRClasses::In));
+    let mut signer = TsigStream::new(key.clone(), None);
+    let mut verifier = TsigStream::new(key.clone(), None);
+    let mut total = 0;
+    for response in short.axfr(&query, 4096, Some((&mut signer, 1000))) {
+        let received = Message::from_bytes(&response.to_bytes(4096)).unwrap();
+        assert!(!received.is_truncated());
+        verifier.verify(&received, 1000).unwrap();
+        total += received.total_section(0);
+    }
+    assert_eq!(total, 502);
+
+    query.get_queries_mut()[0].set_class(RRClasses::Ch);
+    assert_eq!(zone.axfr(&query, 16384, None)[0].get_response_code(), ResponseCodes::NotAuth);
+}
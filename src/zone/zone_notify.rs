@@ -0,0 +1,263 @@
+use std::net::{IpAddr, SocketAddr};
+use crate::messages::inter::op_codes::OpCodes;
+use crate::messages::inter::response_codes::ResponseCodes;
+use crate::messages::inter::rr_classes::RRClasses;
+use crate::messages::inter::rr_types::RRTypes;
+use crate::messages::message::Message;
+use crate::messages::rr_query::RRQuery;
+use crate::records::inter::record_base::RecordBase;
+use crate::records::soa_record::SoaRecord;
+use crate::utils::random;
+use crate::zone::zone::Zone;
+
+//RFC 1996 S3.6 - RETRY INTERVAL DOUBLES AFTER EACH UNANSWERED ATTEMPT
+pub const NOTIFY_RETRY_INTERVAL: u64 = 60;
+pub const NOTIFY_MAX_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone)]
+struct NotifyTarget {
+    address: SocketAddr,
+    id: u16,
+    attempts: u32,
+    next_send: u64,
+    done: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct NotifySender {
+    apex: String,
+    class: RRClasses,
+    soa: Option<(u32, SoaRecord)>,
+    targets: Vec<NotifyTarget>
+}
+
+impl NotifySender {
+
+    pub fn new(apex: &str, class: RRClasses, secondaries: Vec<SocketAddr>) -> Self {
+        Self {
+            apex: apex.trim_end_matches('.').to_lowercase(),
+            class,
+            soa: None,
+            targets: secondaries.into_iter().map(|address| NotifyTarget {
+                address,
+                id: 0,
+                attempts: 0,
+                next_send: 0,
+                done: true
+            }).collect()
+        }
+    }
+
+    //CALL WHENEVER THE ZONE CHANGES, ANY NOTIFY STILL IN FLIGHT IS REPLACED BY ONE FOR THE NEW SERIAL
+    pub fn start(&mut self, now: u64, zone: &Zone) {
+        self.soa = zone.get_soa().map(|(ttl, soa)| (ttl, soa.clone()));
+
+        for target in self.targets.iter_mut() {
+            target.id = random::gen();
+            target.attempts = 0;
+            target.next_send = now;
+            target.done = self.soa.is_none();
+        }
+    }
+
+    //RETURNS EVERY NOTIFY DUE AT NOW, GIVING UP ON A SECONDARY AFTER NOTIFY_MAX_ATTEMPTS
+    pub fn poll(&mut self, now: u64) -> Vec<Message> {
+        let mut messages = Vec::new();
+
+        for i in 0..self.targets.len() {
+            let target = &mut self.targets[i];
+
+            if target.done || now < target.next_send {
+                continue;
+            }
+
+            if target.attempts >= NOTIFY_MAX_ATTEMPTS {
+                target.done = true;
+                continue;
+            }
+
+            target.next_send = now+(NOTIFY_RETRY_INTERVAL << target.attempts);
+            target.attempts += 1;
+
+            let (id, address) = (target.id, target.address);
+            if let Some(mut message) = self.to_message(id) {
+                message.set_destination(address);
+                messages.push(message);
+            }
+        }
+
+        messages
+    }
+
+    //RFC 1996 S4.7 - ANY RESPONSE FROM THE SECONDARY STOPS THE RETRIES, RETURNS FALSE IF IT MATCHES NOTHING WE SENT
+    pub fn response(&mut self, message: &Message) -> bool {
+        if !message.is_qr() || message.get_op_code() != OpCodes::Notify {
+            return false;
+        }
+
+        //MAP_OR RATHER THAN IS_NONE_OR, WHICH NEEDS RUST 1.82
+        match self.targets.iter_mut().find(|t| !t.done && t.id == message.get_id() && message.get_origin().map_or(true, |o| o == t.address)) {
+            Some(target) => {
+                target.done = true;
+                true
+            }
+            None => false
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.targets.iter().all(|t| t.done)
+    }
+
+    pub fn get_pending(&self) -> Vec<SocketAddr> {
+        self.targets.iter().filter(|t| !t.done).map(|t| t.address).collect()
+    }
+
+    //RFC 1996 S3.7 - THE CURRENT SOA GOES IN THE ANSWER AS A HINT
+    fn to_message(&self, id: u16) -> Option<Message> {
+        let (ttl, soa) = self.soa.as_ref()?;
+
+        let mut message = Message::new(id);
+        message.set_op_code(OpCodes::Notify);
+        message.set_authoritative(true);
+        message.add_query(RRQuery::new(&self.apex, RRTypes::Soa, self.class));
+        message.add_section(0, &self.apex, self.class, *ttl, soa.clone().upcast());
+
+        Some(message)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NotifyReceiver {
+    zones: Vec<(String, RRClasses, Vec<IpAddr>)>
+}
+
+impl Default for NotifyReceiver {
+
+    fn default() -> Self {
+        Self {
+            zones: Vec::new()
+        }
+    }
+}
+
+impl NotifyReceiver {
+
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn add_zone(&mut self, apex: &str, class: RRClasses, primaries: Vec<IpAddr>) {
+        self.zones.push((apex.trim_end_matches('.').to_lowercase(), class, primaries));
+    }
+
+    //RFC 1996 S3.7 - S3.11 - VALIDATES A NOTIFY AND RETURNS THE RESPONSE ALONG WITH THE ZONE TO REFRESH NOW AND THE HINTED SERIAL
+    pub fn receive(&self, message: &Message) -> (Message, Option<(String, RRClasses, Option<u32>)>) {
+        let mut response = Message::new(message.get_id());
+        response.set_qr(true);
+        response.set_op_code(message.get_op_code());
+
+        for q in message.get_queries() {
+            response.add_query(q.clone());
+        }
+
+        if message.get_op_code() != OpCodes::Notify || message.is_qr() {
+            response.set_response_code(ResponseCodes::NotImp);
+            return (response, None);
+        }
+
+        let [q] = message.get_queries().as_slice() else {
+            response.set_response_code(ResponseCodes::FormErr);
+            return (response, None);
+        };
+
+        if q.get_type() != RRTypes::Soa {
+            response.set_response_code(ResponseCodes::FormErr);
+            return (response, None);
+        }
+
+        let apex = q.get_fqdn().trim_end_matches('.').to_lowercase();
+        let class = q.get_class();
+
+        let Some((_, _, primaries)) = self.zones.iter().find(|(a, c, _)| a.eq(&apex) && c.eq(&class)) else {
+            response.set_response_code(ResponseCodes::NotAuth);
+            return (response, None);
+        };
+
+        //RFC 1996 S3.10 - ONLY OUR CONFIGURED PRIMARIES MAY TRIGGER A REFRESH
+        if !message.get_origin().is_some_and(|origin| primaries.contains(&origin.ip())) {
+            response.set_response_code(ResponseCodes::Refused);
+            return (response, None);
+        }
+
+        response.set_authoritative(true);
+
+        let serial = message.get_section(0).iter()
+            .filter(|(name, _, _, _)| name.trim_end_matches('.').eq_ignore_ascii_case(&apex))
+            .find_map(|(_, _, _, record)| record.as_any().downcast_ref::<SoaRecord>())
+            .map(|soa| soa.get_serial());
+
+        (response, Some((apex, class, serial)))
+    }
+}
+
+#[test]
+fn test() {
+    use crate::zone::inter::zone_types::ZoneTypes;
+
+    let primary: SocketAddr = "10.0.0.1:53".parse().unwrap();
+    let secondaries: Vec<SocketAddr> = vec!["10.0.0.2:53".parse().unwrap(), "10.0.0.3:53".parse().unwrap()];
+
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("", 300, SoaRecord::new("ns1.find9.net", "admin.find9.net", 7, 7200, 3600, 1209600, 60).upcast());
+
+    let mut sender = NotifySender::new("find9.net.", RRClasses::In, secondaries.clone());
+    assert!(sender.is_complete());
+    sender.start(0, &zone);
+
+    let messages = sender.poll(0);
+    assert_eq!(messages.len(), 2);
+    assert!(sender.poll(59).is_empty());
+
+    let mut receiver = NotifyReceiver::new();
+    receiver.add_zone("find9.net", RRClasses::In, vec![primary.ip()]);
+
+    //ROUND TRIP THROUGH THE WIRE LIKE A REAL TRANSPORT WOULD
+    let mut notify = Message::from_bytes(&messages[0].to_bytes(512)).unwrap();
+    notify.set_origin(primary);
+    let (response, refresh) = receiver.receive(&notify);
+    assert_eq!(response.get_response_code(), ResponseCodes::NoError);
+    assert_eq!(refresh, Some(("find9.net".to_string(), RRClasses::In, Some(7))));
+
+    let mut response = Message::from_bytes(&response.to_bytes(512)).unwrap();
+    response.set_origin(secondaries[0]);
+    assert!(sender.response(&response));
+    assert!(!sender.response(&response));
+    assert_eq!(sender.get_pending(), vec![secondaries[1]]);
+
+    //UNANSWERED SECONDARY IS RETRIED AT 60, 120, 240, 480 SECONDS APART THEN DROPPED
+    let mut now = 0;
+    for attempt in 1..NOTIFY_MAX_ATTEMPTS {
+        now += NOTIFY_RETRY_INTERVAL << (attempt-1);
+        let retries = sender.poll(now);
+        assert_eq!(retries.len(), 1);
+        assert_eq!(retries[0].get_destination(), Some(secondaries[1]));
+        assert_eq!(retries[0].get_id(), messages[1].get_id());
+    }
+    assert!(sender.poll(now+(NOTIFY_RETRY_INTERVAL << NOTIFY_MAX_ATTEMPTS)).is_empty());
+    assert!(sender.is_complete());
+
+    notify.set_origin("10.9.9.9:53".parse().unwrap());
+    assert_eq!(receiver.receive(&notify).0.get_response_code(), ResponseCodes::Refused);
+
+    notify.set_origin(primary);
+    notify.get_queries_mut()[0].set_fqdn("other.net");
+    let (response, refresh) = receiver.receive(&notify);
+    assert_eq!(response.get_response_code(), ResponseCodes::NotAuth);
+    assert!(refresh.is_none());
+
+    notify.set_op_code(OpCodes::Query);
+    assert_eq!(receiver.receive(&notify).0.get_response_code(), ResponseCodes::NotImp);
+}
@@ -11,7 +11,11 @@ use crate::records::{
     ch_a_record::ChARecord,
     aaaa_record::AaaaRecord,
     cname_record::CNameRecord,
+    dname_record::DNameRecord,
     dnskey_record::DnsKeyRecord,
+    ds_record::DsRecord,
+    cds_record::CdsRecord,
+    cdnskey_record::CdnsKeyRecord,
     hinfo_record::HInfoRecord,
     https_record::HttpsRecord,
     loc_record::LocRecord,
@@ -19,20 +23,25 @@ use crate::records::{
     naptr_record::NaptrRecord,
     ns_record::NsRecord,
     nsec_record::NSecRecord,
+    nsec3_record::NSec3Record,
+    nsec3param_record::NSec3ParamRecord,
     ptr_record::PtrRecord,
     rrsig_record::RRSigRecord,
+    tlsa_record::TlsaRecord,
     smimea_record::SmimeaRecord,
+    openpgpkey_record::OpenPgpKeyRecord,
     soa_record::SoaRecord,
     srv_record::SrvRecord,
     sshfp_record::SshFpRecord,
     svcb_record::SvcbRecord,
     txt_record::TxtRecord,
     uri_record::UriRecord,
+    caa_record::CaaRecord
 };
 use crate::records::inter::naptr_flags::NaptrFlags;
 use crate::records::inter::record_base::RecordBase;
 use crate::records::inter::svc_param::SvcParams;
-use crate::utils::{base64, hex};
+use crate::utils::{base32, base64, hex};
 use crate::utils::time_utils::TimeUtils;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -77,6 +86,7 @@ impl ZoneReader {
 
         let mut record: Option<(String, RRClasses, u32, Box<dyn RecordBase>)> = None;
         let mut data_count = 0;
+        let mut generic: Option<(usize, Vec<u8>)> = None;
 
         loop {
             let Some(line) = self.reader.by_ref().lines().next() else { break };
@@ -164,7 +174,21 @@ impl ZoneReader {
                         state = ParserState::Init;
                     }
                     ParserState::Data => {
-                        if part[0] == b'"' {
+                        if data_count == 0 && &part[0..word_len] == b"\\#" {
+                            generic = Some((0, Vec::new()));
+                            data_count += 1;
+
+                        } else if let Some((ref mut length, ref mut data)) = generic {
+                            let value = String::from_utf8(part[0..word_len].to_vec()).ok()?;
+
+                            match data_count {
+                                1 => *length = value.parse().ok()?,
+                                _ => data.extend_from_slice(&hex::decode(&value).ok()?)
+                            }
+
+                            data_count += 1;
+
+                        } else if part[0] == b'"' {
                             if part[word_len - 1] == b'"' {
                                 if let Some((_, class, _, ref mut record)) = record {
                                     set_data(&class, record.deref_mut(), data_count, &String::from_utf8(part[1..word_len - 1].to_vec()).ok()?);
@@ -206,11 +230,25 @@ impl ZoneReader {
             }
 
             if record.is_some() && paren_count == 0 {
-                return record;
+                break;
             }
         }
 
-        record
+        match generic {
+            Some((length, data)) => {
+                if length != data.len() {
+                    return None;
+                }
+
+                let (name, class, ttl, _) = record?;
+
+                let mut buf = (length as u16).to_be_bytes().to_vec();
+                buf.extend_from_slice(&data);
+
+                Some((name, class, ttl, <dyn RecordBase>::from_wire(_type, &class, &buf, 0).ok()?))
+            }
+            None => record
+        }
     }
 
     pub fn get_origin(&self) -> &str {
@@ -289,6 +327,10 @@ fn set_data(class: &RRClasses, record: &mut dyn RecordBase, pos: usize, value: &
             Some(base) => base.to_string(),
             None => panic!("target param is not fully qualified (missing trailing dot)")
         }),
+        RRTypes::DName => record.as_any_mut().downcast_mut::<DNameRecord>().unwrap().target = Some(match value.strip_suffix('.') {
+            Some(base) => base.to_string(),
+            None => panic!("target param is not fully qualified (missing trailing dot)")
+        }),
         RRTypes::Soa => {
             let record = record.as_any_mut().downcast_mut::<SoaRecord>().unwrap();
             match pos {
@@ -439,18 +481,95 @@ fn set_data(class: &RRClasses, record: &mut dyn RecordBase, pos: usize, value: &
                 _ => record.signature.extend_from_slice(&base64::decode(value).unwrap())
             }
         }
-        RRTypes::Nsec => {}//example.com.  NSEC  next.example.com. A MX RRSIG NSEC
-        RRTypes::DnsKey => {}//DNSKEY  <flags> <protocol> <algorithm> <public key>
+        RRTypes::Nsec => {
+            let record = record.as_any_mut().downcast_mut::<NSecRecord>().unwrap();
+            match pos {
+                0 => record.next_domain = Some(match value.strip_suffix('.') {
+                    Some(base) => base.to_string(),
+                    None => panic!("next_domain param is not fully qualified (missing trailing dot)")
+                }),
+                _ => record.rr_types.push(RRTypes::from_str(value).unwrap())
+            }
+        }
+        RRTypes::Nsec3 => {
+            let record = record.as_any_mut().downcast_mut::<NSec3Record>().unwrap();
+            match pos {
+                0 => record.hash_algorithm = value.parse().unwrap(),
+                1 => record.flags = value.parse().unwrap(),
+                2 => record.iterations = value.parse().unwrap(),
+                3 => record.salt = decode_salt(value),
+                4 => record.next_hashed = base32::decode(value).unwrap(),
+                _ => record.rr_types.push(RRTypes::from_str(value).unwrap())
+            }
+        }
+        RRTypes::Nsec3Param => {
+            let record = record.as_any_mut().downcast_mut::<NSec3ParamRecord>().unwrap();
+            match pos {
+                0 => record.hash_algorithm = value.parse().unwrap(),
+                1 => record.flags = value.parse().unwrap(),
+                2 => record.iterations = value.parse().unwrap(),
+                3 => record.salt = decode_salt(value),
+                _ => unimplemented!()
+            }
+        }
+        RRTypes::DnsKey => {
+            let record = record.as_any_mut().downcast_mut::<DnsKeyRecord>().unwrap();
+            match pos {
+                0 => record.flags = value.parse().unwrap(),
+                1 => record.protocol = value.parse().unwrap(),
+                2 => record.algorithm = value.parse().unwrap(),
+                _ => record.public_key.extend_from_slice(&base64::decode(value).unwrap())
+            }
+        }
+        RRTypes::Ds => {
+            let record = record.as_any_mut().downcast_mut::<DsRecord>().unwrap();
+            match pos {
+                0 => record.key_tag = value.parse().unwrap(),
+                1 => record.algorithm = value.parse().unwrap(),
+                2 => record.digest_type = value.parse().unwrap(),
+                _ => record.digest.extend_from_slice(&hex::decode(value).unwrap())
+            }
+        }
+        RRTypes::Cds => {
+            let record = record.as_any_mut().downcast_mut::<CdsRecord>().unwrap();
+            match pos {
+                0 => record.key_tag = value.parse().unwrap(),
+                1 => record.algorithm = value.parse().unwrap(),
+                2 => record.digest_type = value.parse().unwrap(),
+                _ => record.digest.extend_from_slice(&hex::decode(value).unwrap())
+            }
+        }
+        RRTypes::CdnsKey => {
+            let record = record.as_any_mut().downcast_mut::<CdnsKeyRecord>().unwrap();
+            match pos {
+                0 => record.flags = value.parse().unwrap(),
+                1 => record.protocol = value.parse().unwrap(),
+                2 => record.algorithm = value.parse().unwrap(),
+                _ => record.public_key.extend_from_slice(&base64::decode(value).unwrap())
+            }
+        }
+        RRTypes::Tlsa => {
+            let record = record.as_any_mut().downcast_mut::<TlsaRecord>().unwrap();
+            match pos {
+                0 => record.usage = value.parse().unwrap(),
+                1 => record.selector = value.parse().unwrap(),
+                2 => record.matching_type = value.parse().unwrap(),
+                _ => record.certificate.extend_from_slice(&hex::decode(value).unwrap())
+            }
+        }
         RRTypes::Smimea => {
             let record = record.as_any_mut().downcast_mut::<SmimeaRecord>().unwrap();
             match pos {
                 0 => record.usage = value.parse().unwrap(),
                 1 => record.selector = value.parse().unwrap(),
                 2 => record.matching_type = value.parse().unwrap(),
-                3 => record.certificate = hex::decode(value).unwrap(),
-                _ => unimplemented!()
+                _ => record.certificate.extend_from_slice(&hex::decode(value).unwrap())
             }
         }
+        RRTypes::OpenPgpKey => {
+            let record = record.as_any_mut().downcast_mut::<OpenPgpKeyRecord>().unwrap();
+            record.public_key.extend_from_slice(&base64::decode(value).unwrap());
+        }
         RRTypes::Svcb => {
             let record = record.as_any_mut().downcast_mut::<SvcbRecord>().unwrap();
             match pos {
@@ -483,11 +602,27 @@ fn set_data(class: &RRClasses, record: &mut dyn RecordBase, pos: usize, value: &
                 _ => unimplemented!()
             }
         }
-        RRTypes::Caa => {}//CAA     <flags> <tag> <value>
+        RRTypes::Caa => {
+            let record = record.as_any_mut().downcast_mut::<CaaRecord>().unwrap();
+            match pos {
+                0 => record.flags = value.parse().unwrap(),
+                1 => record.tag = Some(value.to_string()),
+                2 => record.value = value.as_bytes().to_vec(),
+                _ => unimplemented!()
+            }
+        }
+        RRTypes::Unknown(code) => panic!("TYPE{} rdata must use the \\# generic encoding", code),
         _ => unimplemented!()
     }
 }
 
+fn decode_salt(s: &str) -> Vec<u8> {
+    match s {
+        "-" => Vec::new(),
+        _ => hex::decode(s).unwrap()
+    }
+}
+
 fn encode_loc_precision(s: &str) -> u8 {
     let val = s.strip_suffix('m').unwrap_or(s).parse::<f64>().unwrap();
     for exp in 0..=9 {
@@ -500,3 +635,45 @@ fn encode_loc_precision(s: &str) -> u8 {
     }
     panic!("cannot encode LOC precision from value: {}", s);
 }
+
+#[test]
+fn test() {
+    let path = std::env::temp_dir().join("rlibdns.zone_reader.test.zone");
+    std::fs::write(&path, "$TTL 300\n\
+        @       IN      TYPE65534       \\# 5 0801020304\n\
+        www     CLASS1  A               \\# 4 0A000001\n\
+        @       CAA     128 issue \"letsencrypt.org; validationmethods=dns-01\"\n\
+        dskey   DNSKEY  256 3 5 AQOeiiR0GOMYkDshWoSKz9XzfwJr1AYtsmx3TGkJaNXVbfi/2pHm822aJ5iI9BMzNXxeYCmZDRD99WYwYqUSdjMmmAphXdvxegXd/M5+X7OrzKBaMbCVdFLUUh6DhweJBjEVv5f2wwjM9XzcnOf+EPbtG9DMBmADjFDc2w/rljwvFw==\n\
+        dskey   DS      60485 5 1 ( 2BB183AF5F22588179A53B0A9863\n\
+                        1FAD1A292118 )\n\
+        @       NSEC    dskey.find9.net. NS SOA RRSIG NSEC DNSKEY TYPE65534\n\
+        @       NSEC3PARAM 1 0 12 aabbccdd\n\
+        0p9mhaveqvm6t7vbl5lop2u3t2rp3tom NSEC3 1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr MX DNSKEY NS SOA NSEC3PARAM RRSIG\n\
+        _443._tcp.www TLSA 3 1 1 ( 0d6fce3468a9a6a0bc6f2b71c2ab0b4a\n\
+                        0aa9ff58fcbd6ee1e7d8af1f4a7dc0f4 )\n\
+        c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._openpgpkey OPENPGPKEY mQENBFVHm5sBCAC+ ( 0Sp3oFMW7VW6h6A= )\n").unwrap();
+
+    let mut reader = ZoneReader::open(&path, "find9.net").unwrap();
+    let records = reader.iter().collect::<Vec<_>>();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(records.len(), 10);
+    assert_eq!(records[0].3.get_type(), RRTypes::Unknown(65534));
+    assert_eq!(records[0].3.to_string(), "TYPE65534 \\# 5 0801020304");
+    assert_eq!(records[1].1, RRClasses::In);
+    assert_eq!(records[1].3.as_any().downcast_ref::<InARecord>().unwrap().address, Some("10.0.0.1".parse().unwrap()));
+    assert!(records[2].3.as_any().downcast_ref::<CaaRecord>().unwrap().is_issuer_critical());
+    assert_eq!(records[2].3.to_string(), "CAA     128 issue \"letsencrypt.org; validationmethods=dns-01\"");
+    let dnskey = records[3].3.as_any().downcast_ref::<DnsKeyRecord>().unwrap();
+    assert!(records[4].3.as_any().downcast_ref::<DsRecord>().unwrap().matches("dskey.example.com", dnskey));
+    assert_eq!(records[5].3.to_string(), "NSEC    dskey.find9.net. NS SOA RRSIG NSEC DNSKEY TYPE65534");
+    let nsec3param = records[6].3.as_any().downcast_ref::<NSec3ParamRecord>().unwrap();
+    assert_eq!(base32::encode(&nsec3param.hash_owner("example").unwrap()), "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+    let nsec3 = records[7].3.as_any().downcast_ref::<NSec3Record>().unwrap();
+    assert_eq!(records[7].0, "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+    assert!(nsec3.is_opt_out());
+    assert_eq!(records[7].3.to_string(), "NSEC3   1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr MX DNSKEY NS SOA NSEC3PARAM RRSIG");
+    assert_eq!(records[8].3.as_any().downcast_ref::<TlsaRecord>().unwrap().get_certificate().len(), 32);
+    assert_eq!(records[9].0, OpenPgpKeyRecord::owner_name("hugh", "find9.net").strip_suffix(".find9.net").unwrap());
+    assert_eq!(records[9].3.to_string(), "OPENPGPKEY mQENBFVHm5sBCAC+0Sp3oFMW7VW6h6A=");
+}
@@ -0,0 +1,300 @@
+use crate::zone::inter::refresh_actions::RefreshActions;
+use crate::zone::zone::{serial_gt, Zone};
+
+//USED UNTIL THE FIRST TRANSFER GIVES US AN SOA TO TAKE TIMERS FROM
+const DEFAULT_RETRY: u32 = 60;
+
+//RFC 1034 S4.3.5 - DRIVES A SECONDARY ZONE FROM ITS SOA TIMERS, TIMES ARE SECONDS ON WHATEVER CLOCK THE CALLER USES
+#[derive(Debug, Clone)]
+pub struct ZoneRefresh {
+    last_refresh: Option<u64>,
+    next_check: u64,
+    pending: Option<RefreshActions>,
+    notified: bool,
+    force_axfr: bool
+}
+
+impl ZoneRefresh {
+
+    //A ZONE LOADED FROM DISK IS CHECKED STRAIGHT AWAY, ONE WITH NO SOA ISN'T SERVED UNTIL IT HAS BEEN TRANSFERRED
+    pub fn new(zone: &mut Zone, now: u64) -> Self {
+        if zone.get_soa().is_none() {
+            zone.set_expired(true);
+        }
+
+        Self {
+            last_refresh: zone.get_soa().map(|_| now),
+            next_check: now,
+            pending: None,
+            notified: false,
+            force_axfr: false
+        }
+    }
+
+    //CALL WHENEVER THE CLOCK MOVES, RETURNS WHAT TO SEND TO THE PRIMARY IF ANYTHING
+    pub fn poll(&mut self, now: u64, zone: &mut Zone) -> Option<RefreshActions> {
+        if let (Some(last_refresh), Some((_, soa))) = (self.last_refresh, zone.get_soa()) {
+            if !zone.is_expired() && now >= last_refresh+soa.get_expire() as u64 {
+                zone.set_expired(true);
+            }
+        }
+
+        if self.pending.is_some() || now < self.next_check {
+            return None;
+        }
+
+        self.pending = Some(RefreshActions::QuerySoa);
+        self.pending
+    }
+
+    //RFC 1982 - ONLY A STRICTLY NEWER SERIAL IS WORTH A TRANSFER
+    pub fn soa_response(&mut self, now: u64, serial: u32, zone: &mut Zone) -> Option<RefreshActions> {
+        if self.pending != Some(RefreshActions::QuerySoa) {
+            return None;
+        }
+
+        self.pending = match zone.get_soa() {
+            Some((_, soa)) if !serial_gt(serial, soa.get_serial()) => {
+                self.refreshed(now, zone);
+                return None;
+            }
+            Some((_, soa)) if !self.force_axfr => Some(RefreshActions::Ixfr(soa.get_serial())),
+            _ => Some(RefreshActions::Axfr)
+        };
+
+        self.pending
+    }
+
+    pub fn transfer_complete(&mut self, now: u64, zone: &mut Zone) {
+        if matches!(self.pending, Some(RefreshActions::Ixfr(_)) | Some(RefreshActions::Axfr)) {
+            self.force_axfr = false;
+            self.refreshed(now, zone);
+        }
+    }
+
+    //RFC 1034 S4.3.5 - TRY AGAIN AFTER RETRY, A FAILED IXFR IS FOLLOWED BY AN AXFR
+    pub fn failed(&mut self, now: u64, zone: &Zone) {
+        if let Some(RefreshActions::Ixfr(_)) = self.pending {
+            self.force_axfr = true;
+        }
+
+        self.pending = None;
+        self.next_check = now+zone.get_soa().map(|(_, soa)| soa.get_retry()).unwrap_or(DEFAULT_RETRY) as u64;
+    }
+
+    //RFC 1996 S3.11 - A NOTIFY NOT OFFERING A NEWER SERIAL IS IGNORED, OTHERWISE CHECK NOW OR AS SOON AS THE CURRENT ONE ENDS
+    pub fn notify(&mut self, now: u64, serial: Option<u32>, zone: &Zone) -> bool {
+        if let (Some(serial), Some((_, soa))) = (serial, zone.get_soa()) {
+            if !serial_gt(serial, soa.get_serial()) {
+                return false;
+            }
+        }
+
+        match self.pending {
+            Some(_) => self.notified = true,
+            None => self.next_check = now
+        }
+
+        true
+    }
+
+    pub fn get_last_refresh(&self) -> Option<u64> {
+        self.last_refresh
+    }
+
+    pub fn get_next_check(&self) -> u64 {
+        self.next_check
+    }
+
+    pub fn get_pending(&self) -> Option<RefreshActions> {
+        self.pending
+    }
+
+    fn refreshed(&mut self, now: u64, zone: &mut Zone) {
+        zone.set_expired(false);
+        self.pending = None;
+        self.last_refresh = Some(now);
+
+        self.next_check = match self.notified {
+            true => now,
+            false => now+zone.get_soa().map(|(_, soa)| soa.get_refresh()).unwrap_or(DEFAULT_RETRY) as u64
+        };
+        self.notified = false;
+    }
+}
+
+#[test]
+fn test() {
+    use crate::journal::inter::txn_op_codes::TxnOpCodes;
+    use crate::journal::journal::Journal;
+    use crate::journal::txn::Txn;
+    use crate::messages::inter::response_codes::ResponseCodes;
+    use crate::messages::inter::rr_classes::RRClasses;
+    use crate::messages::inter::rr_types::RRTypes;
+    use crate::messages::message::Message;
+    use crate::messages::rr_query::RRQuery;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::inter::record_base::RecordBase;
+    use crate::records::soa_record::SoaRecord;
+    use crate::zone::inter::zone_types::ZoneTypes;
+    use crate::zone::zone_transfer::ZoneTransfer;
+
+    struct MockPrimary {
+        zone: Zone,
+        journal: Journal,
+        up: bool,
+        transfers: Vec<RefreshActions>
+    }
+
+    impl MockPrimary {
+
+        fn serial(&self) -> Option<u32> {
+            self.up.then(|| self.zone.get_soa().unwrap().1.get_serial())
+        }
+
+        fn bump(&mut self) {
+            let serial = self.zone.get_soa().unwrap().1.get_serial();
+            let mut txn = Txn::new(serial, serial+1);
+            txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+            txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, a(serial as u8));
+            txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+            txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, a(serial as u8+1));
+            self.zone.apply_txn(&txn).unwrap();
+            self.journal.add_txn(txn);
+        }
+
+        fn transfer(&mut self, action: RefreshActions) -> Option<Vec<Message>> {
+            if !self.up {
+                return None;
+            }
+
+            self.transfers.push(action);
+            let mut query = Message::new(1);
+
+            Some(match action {
+                RefreshActions::Ixfr(serial) => {
+                    query.add_query(RRQuery::new("find9.net", RRTypes::Ixfr, RRClasses::In));
+                    query.add_section(1, "find9.net", RRClasses::In, 300, soa(serial));
+                    self.zone.ixfr(&query, &self.journal)
+                }
+                _ => {
+                    query.add_query(RRQuery::new("find9.net", RRTypes::Axfr, RRClasses::In));
+                    self.zone.axfr(&query, 65535, None)
+                }
+            })
+        }
+    }
+
+    fn soa(serial: u32) -> Box<dyn RecordBase> {
+        SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast()
+    }
+
+    fn a(last: u8) -> Box<dyn RecordBase> {
+        InARecord::new([10, 0, 0, last].into()).upcast()
+    }
+
+    //ONE PASS OF WHATEVER TRANSPORT THE CALLER WOULD HAVE
+    fn drive(now: u64, refresh: &mut ZoneRefresh, zone: &mut Zone, primary: &mut MockPrimary) {
+        if refresh.poll(now, zone).is_none() {
+            return;
+        }
+
+        let Some(serial) = primary.serial() else {
+            refresh.failed(now, zone);
+            return;
+        };
+
+        let Some(action) = refresh.soa_response(now, serial, zone) else {
+            return;
+        };
+
+        let Some(messages) = primary.transfer(action) else {
+            refresh.failed(now, zone);
+            return;
+        };
+
+        let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, zone.get_soa().map(|(_, soa)| soa.get_serial()));
+        for message in &messages {
+            transfer.add_message(message).unwrap();
+        }
+
+        match transfer.apply(zone) {
+            Ok(_) => refresh.transfer_complete(now, zone),
+            Err(_) => refresh.failed(now, zone)
+        }
+    }
+
+    let mut primary = MockPrimary {
+        zone: Zone::new(ZoneTypes::Master, RRClasses::In),
+        journal: Journal::new(),
+        up: true,
+        transfers: Vec::new()
+    };
+    primary.zone.add_record("", 300, soa(1));
+    primary.zone.add_record("www", 300, a(1));
+
+    let mut zone = Zone::new(ZoneTypes::Slave, RRClasses::In);
+    let mut refresh = ZoneRefresh::new(&mut zone, 0);
+    assert!(zone.is_expired());
+
+    drive(0, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(primary.transfers, vec![RefreshActions::Axfr]);
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 1);
+    assert!(!zone.is_expired());
+    assert_eq!(refresh.get_next_check(), 7200);
+
+    drive(100, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(refresh.get_pending(), None);
+
+    //SAME SERIAL, NO TRANSFER
+    drive(7200, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(primary.transfers.len(), 1);
+    assert_eq!(refresh.get_next_check(), 14400);
+
+    primary.bump();
+    drive(14400, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(primary.transfers[1], RefreshActions::Ixfr(1));
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 2);
+    assert_eq!(refresh.get_last_refresh(), Some(14400));
+
+    //PRIMARY GOES AWAY, RETRY EVERY HOUR UNTIL EXPIRE
+    primary.up = false;
+    let mut now = 21600;
+    while now < 14400+1209600 {
+        drive(now, &mut refresh, &mut zone, &mut primary);
+        assert!(!zone.is_expired());
+        assert_eq!(refresh.get_next_check(), now+3600);
+        now += 3600;
+    }
+
+    drive(now, &mut refresh, &mut zone, &mut primary);
+    assert!(zone.is_expired());
+
+    let mut query = Message::new(1);
+    query.add_query(RRQuery::new("find9.net", RRTypes::Axfr, RRClasses::In));
+    assert_eq!(zone.axfr(&query, 65535, None)[0].get_response_code(), ResponseCodes::ServFail);
+
+    //NOTIFY WITH NOTHING NEWER IS IGNORED, A NEWER ONE TRIGGERS AN IMMEDIATE CHECK
+    primary.up = true;
+    primary.bump();
+    now += 10;
+    assert!(!refresh.notify(now, Some(2), &zone));
+    assert!(refresh.notify(now, Some(3), &zone));
+    assert_eq!(refresh.get_next_check(), now);
+
+    drive(now, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(primary.transfers[2], RefreshActions::Ixfr(2));
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 3);
+    assert!(!zone.is_expired());
+
+    //A FAILED IXFR FALLS BACK TO AXFR ON THE RETRY
+    primary.bump();
+    primary.journal = Journal::new();
+    refresh.notify(now, None, &zone);
+    assert_eq!(refresh.poll(now, &mut zone), Some(RefreshActions::QuerySoa));
+    assert_eq!(refresh.soa_response(now, 4, &mut zone), Some(RefreshActions::Ixfr(3)));
+    refresh.failed(now, &zone);
+    drive(now+3600, &mut refresh, &mut zone, &mut primary);
+    assert_eq!(primary.transfers.last(), Some(&RefreshActions::Axfr));
+    assert_eq!(zone.get_soa().unwrap().1.get_serial(), 4);
+}
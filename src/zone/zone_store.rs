@@ -1,12 +1,26 @@
 use std::io;
 use std::path::PathBuf;
+use crate::messages::inter::op_codes::OpCodes;
+use crate::messages::inter::response_codes::ResponseCodes;
 use crate::messages::inter::rr_classes::RRClasses;
-use crate::utils::fqdn_utils::{encode_fqdn, decode_fqdn};
+use crate::messages::inter::rr_types::RRTypes;
+use crate::messages::message::{Message, MessageRecord};
+use crate::records::cname_record::CNameRecord;
+use crate::records::dname_record::DNameRecord;
+use crate::records::inter::record_base::RecordBase;
+use crate::records::mx_record::MxRecord;
+use crate::records::ns_record::NsRecord;
+use crate::records::soa_record::SoaRecord;
+use crate::records::srv_record::SrvRecord;
+use crate::utils::fqdn_utils::{encode_fqdn, decode_fqdn, fqdn_to_relative, to_fqdn};
 use crate::utils::trie::trie::Trie;
 use crate::zone::inter::zone_types::ZoneTypes;
+use crate::zone::rr_set::RRSet;
 use crate::zone::zone::Zone;
 use crate::zone::zone_reader::ZoneReader;
 
+const MAX_CHAIN_LENGTH: usize = 16;
+
 #[derive(Debug, Clone)]
 pub struct ZoneStore {
     trie: Trie<Vec<Zone>>
@@ -47,6 +61,8 @@ impl ZoneStore {
             zone.add_record(&query, ttl, record);
         }
 
+        zone.apply_journal().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
         let key = encode_fqdn(reader.get_origin());
         match self.trie.get_mut(&key) {
             Some(zones) => zones.push(zone),
@@ -90,4 +106,308 @@ impl ZoneStore {
         let (key, zones) = self.trie.get_deepest_mut(&encode_fqdn(name))?;
         Some((decode_fqdn(&key), zones.iter_mut().find(|z| z.get_class().eq(class))?))
     }
+
+    //RFC 6672 S3.2 - RETURNS THE DNAME AND THE CNAME SYNTHESISED FROM IT, OR YXDOMAIN WITH ONLY THE DNAME
+    pub fn get_dname_records(&self, name: &str, class: &RRClasses) -> Option<(ResponseCodes, Vec<MessageRecord>)> {
+        let (apex, zone) = self.get_deepest_zone(name, class)?;
+        let (owner, set) = zone.get_dname(&fqdn_to_relative(&apex, name)?)?;
+        let owner = to_fqdn(&apex, &owner);
+
+        let mut records: Vec<MessageRecord> = set.get_records()
+            .iter()
+            .map(|record| (owner.clone(), *class, set.get_ttl(), record.clone()))
+            .collect();
+
+        match set.get_records().first()?.as_any().downcast_ref::<DNameRecord>()?.substitute(&owner, name)? {
+            Ok(target) => {
+                records.push((name.to_string(), *class, set.get_ttl(), CNameRecord::new(&target).upcast()));
+                Some((ResponseCodes::NoError, records))
+            }
+            Err(_) => Some((ResponseCodes::YxDomain, records))
+        }
+    }
+
+    //RFC 1034 S4.3.2 / RFC 4592 - ANSWER A QUERY FROM THE ZONES IN THIS STORE
+    pub fn answer(&self, query: &Message) -> Message {
+        let mut response = Message::new(query.get_id());
+        response.set_qr(true);
+        response.set_op_code(query.get_op_code());
+        response.set_recursion_desired(query.is_recursion_desired());
+
+        for q in query.get_queries() {
+            response.add_query(q.clone());
+        }
+
+        if query.get_op_code() != OpCodes::Query {
+            response.set_response_code(ResponseCodes::NotImp);
+            return response;
+        }
+
+        if query.get_queries().len() != 1 {
+            response.set_response_code(ResponseCodes::FormErr);
+            return response;
+        }
+
+        let q = &query.get_queries()[0];
+        let _type = q.get_type();
+        let class = q.get_class();
+        let mut name = q.get_fqdn().trim_end_matches('.').to_lowercase();
+
+        let Some((apex, zone)) = self.get_deepest_zone(&name, &class) else {
+            response.set_response_code(ResponseCodes::Refused);
+            return response;
+        };
+
+        if zone.is_expired() {
+            response.set_response_code(ResponseCodes::ServFail);
+            return response;
+        }
+
+        response.set_authoritative(zone.is_authority());
+
+        for _ in 0..MAX_CHAIN_LENGTH {
+            let Some(relative) = fqdn_to_relative(&apex, &name) else {
+                break;
+            };
+
+            //DS IS ANSWERED BY THE PARENT SIDE OF THE CUT
+            if let Some((cut, set)) = zone.get_delegation_point(&relative) {
+                if cut != relative || _type != RRTypes::Ds {
+                    if !response.has_section(0) {
+                        response.set_authoritative(false);
+                    }
+
+                    add_set(&mut response, 1, &to_fqdn(&apex, &cut), class, set);
+                    self.add_additional(&mut response, set, &class);
+                    break;
+                }
+            }
+
+            if let Some((response_code, records)) = self.get_dname_records(&name, &class) {
+                let target = records.last()
+                    .and_then(|(_, _, _, record)| record.as_any().downcast_ref::<CNameRecord>())
+                    .and_then(|record| record.get_target().cloned());
+
+                response.get_section_mut(0).extend(records);
+                response.set_response_code(response_code);
+
+                match target {
+                    Some(target) if response_code == ResponseCodes::NoError => {
+                        name = target;
+                        continue;
+                    }
+                    _ => break
+                }
+            }
+
+            let sets = match zone.get_all_sets(&relative) {
+                Some(sets) => sets,
+                None => {
+                    if zone.contains_name(&relative) {
+                        self.add_soa(&mut response, &apex, zone, &class);
+                        break;
+                    }
+
+                    match get_wildcard(zone, &relative) {
+                        Some(sets) => sets,
+                        None => {
+                            response.set_response_code(ResponseCodes::NxDomain);
+                            self.add_soa(&mut response, &apex, zone, &class);
+                            break;
+                        }
+                    }
+                }
+            };
+
+            if _type == RRTypes::Any {
+                for set in sets {
+                    add_set(&mut response, 0, &name, class, set);
+                }
+                break;
+            }
+
+            if let Some(set) = sets.iter().find(|s| s.get_type().eq(&_type)) {
+                add_set(&mut response, 0, &name, class, set);
+                self.add_additional(&mut response, set, &class);
+                break;
+            }
+
+            match sets.iter().find(|s| s.get_type().eq(&RRTypes::CName)) {
+                Some(set) => {
+                    add_set(&mut response, 0, &name, class, set);
+
+                    match set.get_records().first()
+                            .and_then(|record| record.as_any().downcast_ref::<CNameRecord>())
+                            .and_then(|record| record.get_target()) {
+                        Some(target) => name = target.to_lowercase(),
+                        None => break
+                    }
+                }
+                None => {
+                    self.add_soa(&mut response, &apex, zone, &class);
+                    break;
+                }
+            }
+        }
+
+        response
+    }
+
+    //RFC 2308 S3 - THE NEGATIVE TTL IS THE LOWER OF THE SOA TTL AND THE SOA MINIMUM
+    fn add_soa(&self, response: &mut Message, apex: &str, zone: &Zone, class: &RRClasses) {
+        let Some(set) = zone.get_sets("", &RRTypes::Soa) else { return };
+
+        for record in set.get_records() {
+            let ttl = match record.as_any().downcast_ref::<SoaRecord>() {
+                Some(soa) => set.get_ttl().min(soa.get_minimum_ttl()),
+                None => set.get_ttl()
+            };
+
+            response.add_section(1, apex, *class, ttl, record.clone());
+        }
+    }
+
+    //RFC 1034 S4.3.2 STEP 6 - ADDRESSES FOR MX, SRV AND NS TARGETS, GLUE INCLUDED
+    fn add_additional(&self, response: &mut Message, set: &RRSet, class: &RRClasses) {
+        for target in set.get_records().iter().filter_map(|record| get_target(record.as_ref())) {
+            let target = target.to_lowercase();
+
+            let Some((apex, zone)) = self.get_deepest_zone(&target, class) else { continue };
+            let Some(relative) = fqdn_to_relative(&apex, &target) else { continue };
+
+            for _type in [RRTypes::A, RRTypes::Aaaa] {
+                if response.get_sections().iter().flatten().any(|(name, _, _, record)| name.eq(&target) && record.get_type().eq(&_type)) {
+                    continue;
+                }
+
+                if let Some(set) = zone.get_sets(&relative, &_type) {
+                    add_set(response, 2, &target, *class, set);
+                }
+            }
+        }
+    }
+}
+
+fn add_set(response: &mut Message, section: usize, name: &str, class: RRClasses, set: &RRSet) {
+    for record in set.get_records() {
+        response.add_section(section, name, class, set.get_ttl(), record.clone());
+    }
+}
+
+fn get_target(record: &dyn RecordBase) -> Option<&String> {
+    match record.get_type() {
+        RRTypes::Mx => record.as_any().downcast_ref::<MxRecord>()?.get_server(),
+        RRTypes::Srv => record.as_any().downcast_ref::<SrvRecord>()?.get_target(),
+        RRTypes::Ns => record.as_any().downcast_ref::<NsRecord>()?.get_server(),
+        _ => None
+    }
+}
+
+//RFC 4592 S3.3.1 - THE SOURCE OF SYNTHESIS IS THE WILDCARD AT THE CLOSEST ENCLOSER
+fn get_wildcard<'a>(zone: &'a Zone, query: &str) -> Option<&'a Vec<RRSet>> {
+    let labels = query.split('.').collect::<Vec<_>>();
+
+    for i in 1..=labels.len() {
+        let encloser = labels[i..].join(".");
+        if zone.contains_name(&encloser) {
+            return zone.get_all_sets(&match encloser.is_empty() {
+                true => "*".to_string(),
+                false => format!("*.{}", encloser)
+            });
+        }
+    }
+
+    None
+}
+
+
+#[test]
+fn test() {
+    let mut zone = Zone::new(ZoneTypes::Master, RRClasses::In);
+    zone.add_record("old", 300, DNameRecord::new("find9.net").upcast());
+    zone.add_record("long", 300, DNameRecord::new(&vec!["a".repeat(63); 3].join(".")).upcast());
+
+    let mut store = ZoneStore::new();
+    store.add_zone("example.com", zone);
+
+    let (response_code, records) = store.get_dname_records("www.old.example.com", &RRClasses::In).unwrap();
+    assert_eq!(response_code, ResponseCodes::NoError);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].0, "old.example.com");
+    assert_eq!(records[1].0, "www.old.example.com");
+    assert_eq!(records[1].3.as_any().downcast_ref::<CNameRecord>().unwrap().get_target().unwrap(), "www.find9.net");
+
+    assert!(store.get_dname_records("old.example.com", &RRClasses::In).is_none());
+    assert!(store.get_dname_records("www.example.com", &RRClasses::In).is_none());
+
+    let (response_code, records) = store.get_dname_records(&format!("{}.long.example.com", "b".repeat(63)), &RRClasses::In).unwrap();
+    assert_eq!(response_code, ResponseCodes::YxDomain);
+    assert_eq!(records.len(), 1);
+}
+
+#[test]
+fn answer() {
+    use crate::messages::rr_query::RRQuery;
+    use crate::records::in_a_record::InARecord;
+
+    let path = std::env::temp_dir().join("rlibdns.zone_store.answer.zone");
+    std::fs::write(&path, "$TTL 300\n\
+        @       SOA     ns1.find9.net. admin.find9.net. 1 7200 3600 1209600 60\n\
+        @       NS      ns1.find9.net.\n\
+        @       MX      10 mail.find9.net.\n\
+        ns1     A       10.0.0.1\n\
+        www     A       10.0.0.2\n\
+        mail    A       10.0.0.3\n\
+        alias   CNAME   www.find9.net.\n\
+        *.wild  TXT     \"wild\"\n\
+        a.b.c   A       10.0.0.4\n\
+        sub     NS      ns.sub.find9.net.\n\
+        ns.sub  A       10.0.0.5\n\
+        old     DNAME   find9.net.\n").unwrap();
+
+    let mut store = ZoneStore::new();
+    store.open(&path, "find9.net", RRClasses::In).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let ask = |name: &str, _type: RRTypes| {
+        let mut query = Message::new(1);
+        query.add_query(RRQuery::new(name, _type, RRClasses::In));
+        store.answer(&query)
+    };
+
+    let response = ask("WWW.find9.net.", RRTypes::A);
+    assert!(response.is_qr() && response.is_authoritative());
+    assert_eq!(response.get_section(0)[0].3.as_any().downcast_ref::<InARecord>().unwrap().address, Some("10.0.0.2".parse().unwrap()));
+
+    let response = ask("alias.find9.net", RRTypes::A);
+    assert_eq!(response.get_section(0).iter().map(|r| r.3.get_type()).collect::<Vec<_>>(), vec![RRTypes::CName, RRTypes::A]);
+    assert_eq!(response.get_section(0)[1].0, "www.find9.net");
+
+    let response = ask("find9.net", RRTypes::Mx);
+    assert_eq!(response.total_section(0), 1);
+    assert_eq!(response.get_section(2)[0].0, "mail.find9.net");
+
+    let response = ask("x.wild.find9.net", RRTypes::Txt);
+    assert_eq!(response.get_response_code(), ResponseCodes::NoError);
+    assert_eq!(response.get_section(0)[0].0, "x.wild.find9.net");
+
+    let response = ask("b.c.find9.net", RRTypes::A);
+    assert_eq!(response.get_response_code(), ResponseCodes::NoError);
+    assert_eq!(response.total_section(0), 0);
+    assert_eq!(response.get_section(1)[0].2, 60);
+
+    let response = ask("nope.find9.net", RRTypes::A);
+    assert_eq!(response.get_response_code(), ResponseCodes::NxDomain);
+    assert_eq!(response.get_section(1)[0].3.get_type(), RRTypes::Soa);
+
+    let response = ask("host.sub.find9.net", RRTypes::A);
+    assert!(!response.is_authoritative());
+    assert_eq!(response.total_section(0), 0);
+    assert_eq!(response.get_section(1)[0].0, "sub.find9.net");
+    assert_eq!(response.get_section(2)[0].0, "ns.sub.find9.net");
+
+    let response = ask("www.old.find9.net", RRTypes::A);
+    assert_eq!(response.get_section(0).iter().map(|r| r.3.get_type()).collect::<Vec<_>>(), vec![RRTypes::DName, RRTypes::CName, RRTypes::A]);
+
+    assert_eq!(ask("find9.org", RRTypes::A).get_response_code(), ResponseCodes::Refused);
 }
@@ -0,0 +1,327 @@
+use crate::journal::inter::txn_op_codes::TxnOpCodes;
+use crate::journal::journal_writer::JournalWriter;
+use crate::journal::txn::Txn;
+use crate::messages::inter::response_codes::ResponseCodes;
+use crate::messages::inter::rr_classes::RRClasses;
+use crate::messages::message::{Message, MessageRecord};
+use crate::records::soa_record::SoaRecord;
+use crate::utils::fqdn_utils::fqdn_to_relative;
+use crate::zone::inter::zone_types::ZoneTypes;
+use crate::zone::zone::{serial_gt, Zone, ZoneError};
+
+#[derive(Debug, Clone)]
+pub enum Transfer {
+    UpToDate(u32),
+    Axfr(Zone),
+    Ixfr(Vec<Txn>)
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoneTransfer {
+    apex: String,
+    class: RRClasses,
+    serial: Option<u32>,
+    end_serial: Option<u32>,
+    incremental: Option<bool>,
+    phase: TxnOpCodes,
+    complete: bool,
+    records: Vec<MessageRecord>
+}
+
+impl ZoneTransfer {
+
+    //SERIAL IS THE ONE WE SENT IN THE IXFR AUTHORITY SECTION, NONE FOR AN AXFR
+    pub fn new(apex: &str, class: RRClasses, serial: Option<u32>) -> Self {
+        Self {
+            apex: apex.trim_end_matches('.').to_lowercase(),
+            class,
+            serial,
+            end_serial: None,
+            incremental: None,
+            phase: TxnOpCodes::Delete,
+            complete: false,
+            records: Vec::new()
+        }
+    }
+
+    //FEED RESPONSES IN THE ORDER THEY ARRIVED, RETURNS TRUE ONCE THE CLOSING SOA HAS BEEN SEEN
+    pub fn add_message(&mut self, message: &Message) -> Result<bool, ZoneError> {
+        if message.get_response_code() != ResponseCodes::NoError {
+            return Err(ZoneError::Rejected(message.get_response_code()));
+        }
+
+        for (name, class, ttl, record) in message.get_section(0) {
+            if self.complete {
+                return Err(ZoneError::MalformedTransfer("records after the closing SOA".to_string()));
+            }
+
+            let name = name.trim_end_matches('.').to_lowercase();
+            if fqdn_to_relative(&self.apex, &name).is_none() {
+                return Err(ZoneError::NotInZone(name));
+            }
+
+            let serial = record.as_any().downcast_ref::<SoaRecord>().map(|soa| soa.get_serial());
+
+            match (self.end_serial, self.incremental, serial) {
+                (None, _, Some(serial)) => self.end_serial = Some(serial),
+                (None, _, None) => return Err(ZoneError::MalformedTransfer("transfer does not start with an SOA".to_string())),
+                //RFC 1995 S4 - A SECOND SOA THAT ISN'T THE NEW ONE MEANS DIFFERENCES, ANYTHING ELSE IS A FULL ZONE
+                (Some(end), None, Some(serial)) if self.serial.is_some() && serial != end => self.incremental = Some(true),
+                (Some(end), _, Some(serial)) if self.incremental != Some(true) => {
+                    if serial != end {
+                        return Err(ZoneError::SerialMismatch { expected: end, found: serial });
+                    }
+
+                    self.incremental = Some(false);
+                    self.complete = true;
+                }
+                (Some(_), None, None) => self.incremental = Some(false),
+                (Some(end), Some(true), Some(serial)) => {
+                    match self.phase {
+                        TxnOpCodes::Delete => self.phase = TxnOpCodes::Add,
+                        TxnOpCodes::Add if serial == end => self.complete = true,
+                        TxnOpCodes::Add => self.phase = TxnOpCodes::Delete
+                    }
+                }
+                _ => {}
+            }
+
+            self.records.push((name, *class, *ttl, record.clone()));
+        }
+
+        //RFC 1995 S4 - A LONE SOA NO NEWER THAN OURS MEANS WE ARE ALREADY UP TO DATE
+        if let (Some(end), None, Some(serial)) = (self.end_serial, self.incremental, self.serial) {
+            if !serial_gt(end, serial) {
+                self.complete = true;
+            }
+        }
+
+        Ok(self.complete)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn finish(self) -> Result<Transfer, ZoneError> {
+        if !self.complete {
+            return Err(ZoneError::IncompleteTransfer);
+        }
+
+        let end_serial = self.end_serial.ok_or(ZoneError::IncompleteTransfer)?;
+
+        match self.incremental {
+            None => Ok(Transfer::UpToDate(end_serial)),
+            Some(false) => {
+                let mut zone = Zone::new(ZoneTypes::Slave, self.class);
+
+                //THE CLOSING SOA IS THE SAME RECORD AS THE OPENING ONE
+                let records = self.records.len()-1;
+                for (name, _, ttl, record) in self.records.into_iter().take(records) {
+                    let query = fqdn_to_relative(&self.apex, &name).ok_or(ZoneError::NotInZone(name))?;
+                    zone.add_record(&query, ttl, record);
+                }
+
+                Ok(Transfer::Axfr(zone))
+            }
+            Some(true) => {
+                let mut txns: Vec<Txn> = Vec::new();
+                let mut phase = TxnOpCodes::Add;
+                let mut serial = self.serial.ok_or(ZoneError::IncompleteTransfer)?;
+
+                let records = self.records.len()-1;
+                for (name, class, ttl, record) in self.records.into_iter().take(records).skip(1) {
+                    if let Some(soa) = record.as_any().downcast_ref::<SoaRecord>() {
+                        match phase {
+                            TxnOpCodes::Add => {
+                                if soa.get_serial() != serial {
+                                    return Err(ZoneError::SerialMismatch { expected: serial, found: soa.get_serial() });
+                                }
+
+                                txns.push(Txn::new(soa.get_serial(), soa.get_serial()));
+                                phase = TxnOpCodes::Delete;
+                            }
+                            TxnOpCodes::Delete => {
+                                serial = soa.get_serial();
+                                txns.last_mut().ok_or(ZoneError::IncompleteTransfer)?.set_serial_1(serial);
+                                phase = TxnOpCodes::Add;
+                            }
+                        }
+                    }
+
+                    txns.last_mut().ok_or(ZoneError::IncompleteTransfer)?.add_record(phase, &name, class, ttl, record);
+                }
+
+                if phase != TxnOpCodes::Add || serial != end_serial {
+                    return Err(ZoneError::SerialMismatch { expected: end_serial, found: serial });
+                }
+
+                Ok(Transfer::Ixfr(txns))
+            }
+        }
+    }
+
+    //REPLACES THE ZONE FOR AN AXFR, OTHERWISE APPLIES AND JOURNALS THE TRANSACTIONS, RETURNS THE NEW SERIAL,
+    //ON ANY ERROR THE ZONE IS LEFT WHERE ITS JOURNAL ENDS
+    pub fn apply(self, zone: &mut Zone) -> Result<u32, ZoneError> {
+        match self.finish()? {
+            Transfer::UpToDate(serial) => Ok(serial),
+            Transfer::Axfr(mut fresh) => {
+                fresh.set_type(zone.get_type());
+                let serial = fresh.get_soa().map(|(_, soa)| soa.get_serial()).ok_or(ZoneError::MissingSoa)?;
+
+                //THE OLD JOURNAL NO LONGER LEADS TO THIS ZONE, AN EMPTY ONE IS RENAMED OVER IT RIGHT BEFORE THE SWAP
+                if let Some(journal_path) = zone.get_journal_path() {
+                    if journal_path.exists() {
+                        JournalWriter::reset(journal_path).map_err(|e| ZoneError::Journal(e.to_string()))?;
+                    }
+
+                    fresh.set_journal_path(journal_path.clone());
+                }
+
+                *zone = fresh;
+                Ok(serial)
+            }
+            Transfer::Ixfr(txns) => {
+                //EVERY TRANSACTION HAS TO APPLY BEFORE ANY OF THEM IS JOURNALED
+                let mut updated = zone.clone();
+                for txn in &txns {
+                    updated.apply_txn(txn)?;
+                }
+
+                if zone.get_journal_path().is_some() {
+                    let mut writer = zone.get_journal_writer().map_err(|e| ZoneError::Journal(e.to_string()))?;
+
+                    for (i, txn) in txns.iter().enumerate() {
+                        if let Err(e) = writer.write_txn(txn) {
+                            //THESE ALREADY APPLIED CLEANLY ABOVE, SO THE ZONE CATCHES UP TO WHAT WAS JOURNALED
+                            for txn in &txns[..i] {
+                                zone.apply_txn(txn)?;
+                            }
+
+                            return Err(ZoneError::Journal(e.to_string()));
+                        }
+                    }
+                }
+
+                *zone = updated;
+                zone.get_soa().map(|(_, soa)| soa.get_serial()).ok_or(ZoneError::MissingSoa)
+            }
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use std::fs;
+    use crate::journal::journal::Journal;
+    use crate::journal::journal_reader::JournalReader;
+    use crate::messages::inter::rr_types::RRTypes;
+    use crate::messages::rr_query::RRQuery;
+    use crate::records::in_a_record::InARecord;
+    use crate::records::inter::record_base::RecordBase;
+    use crate::records::txt_record::TxtRecord;
+
+    let soa = |serial| SoaRecord::new("ns1.find9.net", "admin.find9.net", serial, 7200, 3600, 1209600, 60).upcast();
+    let a = |last: u8| InARecord::new([10, 0, 0, last].into()).upcast();
+
+    let mut journal = Journal::new();
+    for serial in 1..3 {
+        let mut txn = Txn::new(serial, serial+1);
+        txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(serial));
+        txn.add_record(TxnOpCodes::Delete, "www.find9.net", RRClasses::In, 300, a(serial as u8));
+        txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(serial+1));
+        txn.add_record(TxnOpCodes::Add, "www.find9.net", RRClasses::In, 300, a(serial as u8+1));
+        journal.add_txn(txn);
+    }
+
+    let mut primary = Zone::new(ZoneTypes::Master, RRClasses::In);
+    primary.add_record("", 300, soa(3));
+    primary.add_record("www", 300, a(3));
+    for i in 0..1000 {
+        primary.add_record(&format!("txt{}", i), 300, TxtRecord::new(vec!["x".repeat(100)]).upcast());
+    }
+
+    //ROUND TRIP THROUGH THE WIRE SO NAMES LOOK LIKE A REAL TRANSFER
+    let ixfr = |serial| {
+        let mut query = Message::new(1);
+        query.add_query(RRQuery::new("find9.net", RRTypes::Ixfr, RRClasses::In));
+        query.add_section(1, "find9.net", RRClasses::In, 300, soa(serial));
+        primary.ixfr(&query, &journal).iter()
+            .map(|m| Message::from_bytes(&m.to_bytes(65535)).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let path = std::env::temp_dir().join("rlibdns.zone_transfer.test.jnl");
+    let _ = fs::remove_file(&path);
+
+    let mut secondary = Zone::new_with_jnl(ZoneTypes::Slave, RRClasses::In, &path);
+    secondary.add_record("", 300, soa(1));
+    secondary.add_record("www", 300, a(1));
+
+    let mut transfer = ZoneTransfer::new("find9.net.", RRClasses::In, Some(1));
+    for message in ixfr(1) {
+        transfer.add_message(&message).unwrap();
+    }
+    assert!(matches!(transfer.clone().finish(), Ok(Transfer::Ixfr(txns)) if txns.len() == 2));
+    assert_eq!(transfer.apply(&mut secondary).unwrap(), 3);
+    assert!(secondary.get_sets("www", &RRTypes::A).unwrap().contains_record(a(3).as_ref()));
+    assert_eq!(JournalReader::open(&path).unwrap().iter().count(), 2);
+
+    //A JOURNAL THAT CAN'T TAKE THE TRANSACTIONS LEAVES THE ZONE WHERE IT WAS
+    let stale = std::env::temp_dir().join("rlibdns.zone_transfer.stale.jnl");
+    let mut txn = Txn::new(7, 8);
+    txn.add_record(TxnOpCodes::Delete, "find9.net", RRClasses::In, 300, soa(7));
+    txn.add_record(TxnOpCodes::Add, "find9.net", RRClasses::In, 300, soa(8));
+    JournalWriter::create(&stale).unwrap().write_txn(&txn).unwrap();
+
+    let mut behind = Zone::new_with_jnl(ZoneTypes::Slave, RRClasses::In, &stale);
+    behind.add_record("", 300, soa(1));
+    behind.add_record("www", 300, a(1));
+
+    let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, Some(1));
+    for message in ixfr(1) {
+        transfer.add_message(&message).unwrap();
+    }
+    assert!(matches!(transfer.apply(&mut behind), Err(ZoneError::Journal(_))));
+    assert_eq!(behind.get_soa().unwrap().1.get_serial(), 1);
+    assert!(behind.get_sets("www", &RRTypes::A).unwrap().contains_record(a(1).as_ref()));
+    assert_eq!(JournalReader::open(&stale).unwrap().iter().count(), 1);
+    fs::remove_file(&stale).unwrap();
+
+    let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, Some(3));
+    assert!(transfer.add_message(&ixfr(3)[0]).unwrap());
+    assert!(matches!(transfer.finish(), Ok(Transfer::UpToDate(3))));
+
+    let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, Some(2));
+    for message in ixfr(1) {
+        transfer.add_message(&message).unwrap();
+    }
+    assert_eq!(transfer.finish().unwrap_err(), ZoneError::SerialMismatch { expected: 2, found: 1 });
+
+    //JOURNAL DOESN'T REACH SERIAL 0 SO THE PRIMARY FALLS BACK TO THE WHOLE ZONE
+    let responses = ixfr(0);
+    assert!(responses.len() > 1);
+
+    let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, Some(0));
+    for message in &responses[..responses.len()-1] {
+        assert!(!transfer.add_message(message).unwrap());
+    }
+    assert_eq!(transfer.clone().finish().unwrap_err(), ZoneError::IncompleteTransfer);
+    assert!(transfer.add_message(responses.last().unwrap()).unwrap());
+    assert!(transfer.add_message(responses.last().unwrap()).is_err());
+
+    let mut transfer = ZoneTransfer::new("find9.net", RRClasses::In, Some(0));
+    for message in &responses {
+        transfer.add_message(message).unwrap();
+    }
+    assert_eq!(transfer.apply(&mut secondary).unwrap(), 3);
+    assert_eq!(secondary.get_type(), ZoneTypes::Slave);
+    assert_eq!(secondary.get_all_sets_recursive().count(), 1002);
+    assert!(JournalWriter::open(&path).unwrap().is_empty());
+    fs::remove_file(&path).unwrap();
+
+    let mut refused = Message::new(1);
+    refused.set_response_code(ResponseCodes::Refused);
+    assert_eq!(ZoneTransfer::new("find9.net", RRClasses::In, None).add_message(&refused), Err(ZoneError::Rejected(ResponseCodes::Refused)));
+}